@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
 use anchor_lang::system_program;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as SplTransfer};
 
@@ -14,6 +15,12 @@ pub const MIN_ORDER_AMOUNT: u64 = 100_000; // 0.0001 in 9-decimal tokens
 /// Maximum order lifetime in slots (~400ms/slot → ~24 hours)
 pub const MAX_EXPIRY_SLOTS: u64 = 216_000;
 
+// ── Kill Flags ───────────────────────────────────────────────────────
+/// Bits of `BridgeConfig::disabled_instructions`.
+/// `cancel_order` has no flag: makers can always reclaim their escrow.
+pub const FLAG_CREATE_ORDER: u64 = 1 << 0;
+pub const FLAG_FILL_ORDER: u64 = 1 << 1;
+
 // ── Direction Enum ───────────────────────────────────────────────────
 /// Direction 0 = Maker sells sGOR (SPL), wants gGOR (native) in return
 /// Direction 1 = Maker sells gGOR (native), wants sGOR (SPL) in return
@@ -22,6 +29,82 @@ pub const MAX_EXPIRY_SLOTS: u64 = 216_000;
 pub mod gorbagana_bridge {
     use super::*;
 
+    // ═══════════════════════════════════════════════════════════════════
+    // INITIALIZE CONFIG
+    // ═══════════════════════════════════════════════════════════════════
+    /// Creates the bridge config. Only the program upgrade authority can
+    /// call this, and the pause authority must be a different key.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        pause_authority: Pubkey,
+    ) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
+        require_keys_neq!(pause_authority, admin, BridgeError::PauseAuthorityNotDistinct);
+
+        let config = &mut ctx.accounts.config;
+        config.admin = admin;
+        config.pause_authority = pause_authority;
+        config.paused = false;
+        config.disabled_instructions = 0;
+        config.bump = ctx.bumps.config;
+
+        emit!(PauseUpdated {
+            authority: admin,
+            paused: false,
+            disabled_instructions: 0,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // SET PAUSE — Emergency stop
+    // ═══════════════════════════════════════════════════════════════════
+    /// Sets the global pause flag and the per-instruction kill bitmask.
+    /// `cancel_order` stays open regardless so makers can exit.
+    pub fn set_pause(
+        ctx: Context<SetPause>,
+        paused: bool,
+        disabled_instructions: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+        config.disabled_instructions = disabled_instructions;
+
+        emit!(PauseUpdated {
+            authority: ctx.accounts.pause_authority.key(),
+            paused,
+            disabled_instructions,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // SET PAUSE AUTHORITY
+    // ═══════════════════════════════════════════════════════════════════
+    pub fn set_pause_authority(
+        ctx: Context<SetPauseAuthority>,
+        new_pause_authority: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require_keys_neq!(
+            new_pause_authority,
+            config.admin,
+            BridgeError::PauseAuthorityNotDistinct
+        );
+
+        let old_pause_authority = config.pause_authority;
+        config.pause_authority = new_pause_authority;
+
+        emit!(PauseAuthorityUpdated {
+            old_pause_authority,
+            new_pause_authority,
+        });
+
+        Ok(())
+    }
+
     // ═══════════════════════════════════════════════════════════════════
     // CREATE ORDER
     // ═══════════════════════════════════════════════════════════════════
@@ -35,6 +118,7 @@ pub mod gorbagana_bridge {
         expiration_slot: u64,
     ) -> Result<()> {
         // ── Validation ───────────────────────────────────────────────
+        ctx.accounts.config.require_enabled(FLAG_CREATE_ORDER)?;
         require!(amount >= MIN_ORDER_AMOUNT, BridgeError::InvalidAmount);
         require!(direction <= 1, BridgeError::InvalidDirection);
 
@@ -131,6 +215,7 @@ pub mod gorbagana_bridge {
         let order = &ctx.accounts.order;
 
         // ── Validation ───────────────────────────────────────────────
+        ctx.accounts.config.require_enabled(FLAG_FILL_ORDER)?;
         require!(!order.is_filled, BridgeError::OrderAlreadyFilled);
         require!(
             Clock::get()?.slot <= order.expiration_slot,
@@ -309,12 +394,69 @@ pub mod gorbagana_bridge {
 // ACCOUNT STRUCTS
 // ═══════════════════════════════════════════════════════════════════════
 
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [b"config"],
+        bump,
+        payer = admin,
+        space = BridgeConfig::LEN,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    /// Program data — proves `admin` is the upgrade authority
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ BridgeError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    pub pause_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = pause_authority @ BridgeError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetPauseAuthority<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ BridgeError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+}
+
 #[derive(Accounts)]
 #[instruction(amount: u64, direction: u8)]
 pub struct CreateOrder<'info> {
     #[account(mut)]
     pub maker: Signer<'info>,
 
+    /// Global config — checked for pause / kill flags
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
     /// Order PDA — deterministic from maker + amount
     #[account(
         init,
@@ -354,6 +496,10 @@ pub struct FillOrder<'info> {
     #[account(mut)]
     pub taker: Signer<'info>,
 
+    /// Global config — checked for pause / kill flags
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
     /// CHECK: Maker receives funds. Validated via order.maker constraint.
     #[account(
         mut,
@@ -420,6 +566,33 @@ pub struct CancelOrder<'info> {
 // STATE
 // ═══════════════════════════════════════════════════════════════════════
 
+#[account]
+pub struct BridgeConfig {
+    pub admin: Pubkey,                // 32 (upgrade authority at init)
+    pub pause_authority: Pubkey,      // 32
+    pub paused: bool,                 // 1
+    pub disabled_instructions: u64,   // 8  (FLAG_* bits)
+    pub bump: u8,                     // 1
+}
+
+impl BridgeConfig {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // admin
+        + 32  // pause_authority
+        + 1   // paused
+        + 8   // disabled_instructions
+        + 1;  // bump
+
+    pub fn require_enabled(&self, flag: u64) -> Result<()> {
+        require!(!self.paused, BridgeError::BridgePaused);
+        require!(
+            self.disabled_instructions & flag == 0,
+            BridgeError::InstructionDisabled
+        );
+        Ok(())
+    }
+}
+
 #[account]
 pub struct Order {
     pub maker: Pubkey,       // 32
@@ -487,6 +660,15 @@ pub enum BridgeError {
 
     #[msg("Missing maker receive token account.")]
     MissingMakerReceiveTokenAccount,
+
+    #[msg("Bridge is paused.")]
+    BridgePaused,
+
+    #[msg("This instruction is currently disabled.")]
+    InstructionDisabled,
+
+    #[msg("Pause authority must differ from the upgrade authority.")]
+    PauseAuthorityNotDistinct,
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -518,3 +700,16 @@ pub struct OrderCancelled {
     pub amount: u64,
     pub direction: u8,
 }
+
+#[event]
+pub struct PauseUpdated {
+    pub authority: Pubkey,
+    pub paused: bool,
+    pub disabled_instructions: u64,
+}
+
+#[event]
+pub struct PauseAuthorityUpdated {
+    pub old_pause_authority: Pubkey,
+    pub new_pause_authority: Pubkey,
+}
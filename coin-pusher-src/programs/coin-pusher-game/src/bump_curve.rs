@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+
+use crate::{GameConfig, GameError, BPS_DENOMINATOR};
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the bump cost curve (admin only)
+pub(crate) fn set_bump_curve(ctx: Context<SetBumpCurve>, curve: BumpCurve) -> Result<()> {
+    curve.validate()?;
+    ctx.accounts.config.bump_curve = curve;
+
+    emit!(BumpCurveUpdated {
+        curve,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetBumpCurve<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// Bump pricing in credits: `base_cost × strength²`, raised by
+/// `escalation_bps` of that for every earlier bump in the player's window
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BumpCurve {
+    /// Cost of a strength 1 bump
+    pub base_cost: u64,
+    pub max_strength: u8,
+    /// Seconds a bump window lasts from its first bump; 0 turns escalation off
+    pub escalation_window: i64,
+    /// Extra cost per earlier bump in the window, in basis points
+    pub escalation_bps: u16,
+}
+
+impl Default for BumpCurve {
+    /// 50 credits at strength 1, up to strength 5, no escalation
+    fn default() -> Self {
+        Self {
+            base_cost: 50,
+            max_strength: 5,
+            escalation_window: 0,
+            escalation_bps: 0,
+        }
+    }
+}
+
+impl BumpCurve {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.base_cost > 0 && self.max_strength > 0 && self.escalation_window >= 0,
+            GameError::InvalidBumpCurve
+        );
+        Ok(())
+    }
+
+    /// Credits for a bump of `strength` after `earlier_bumps` in the window
+    pub fn cost(&self, strength: u8, earlier_bumps: u32) -> Result<u64> {
+        require!(
+            strength > 0 && strength <= self.max_strength,
+            GameError::InvalidBumpStrength
+        );
+        let strength = strength as u128;
+        let escalation_bps = self.escalation_bps as u128 * earlier_bumps as u128;
+        let cost = (self.base_cost as u128 * strength * strength)
+            .checked_mul(BPS_DENOMINATOR as u128 + escalation_bps)
+            .ok_or(GameError::InvalidAmount)?
+            / BPS_DENOMINATOR as u128;
+        u64::try_from(cost).map_err(|_| error!(GameError::InvalidAmount))
+    }
+}
+
+/// Bumps the player made in the current escalation window. The window
+/// opens with the first bump after the previous one has lapsed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BumpWindow {
+    pub start: i64,
+    pub bumps: u32,
+}
+
+impl BumpWindow {
+    /// Count a bump at `now`, returning how many came before it in the window
+    pub fn record(&mut self, window: i64, now: i64) -> u32 {
+        if now.saturating_sub(self.start) >= window {
+            self.start = now;
+            self.bumps = 0;
+        }
+        let earlier_bumps = self.bumps;
+        self.bumps = self.bumps.saturating_add(1);
+        earlier_bumps
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct BumpCurveUpdated {
+    pub curve: BumpCurve,
+    pub timestamp: i64,
+}
@@ -0,0 +1,545 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{GameConfig, GameError, GameState, GlobalStats};
+
+/// JUNK base units paid per credit (1 credit = 1 JUNK, the price of a drop)
+pub const JUNK_PER_CREDIT: u64 = 1_000_000;
+
+/// Length of a cash-out cap window in seconds
+pub const CASH_OUT_WINDOW: i64 = 86_400;
+
+/// Buckets the cap window is counted in, one per hour
+pub const CASH_OUT_BUCKETS: usize = 24;
+
+const CASH_OUT_BUCKET_SECONDS: i64 = CASH_OUT_WINDOW / CASH_OUT_BUCKETS as i64;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the cash-out caps and queueing rules (admin only)
+pub(crate) fn set_cash_out_limits(
+    ctx: Context<SetCashOutLimits>,
+    limits: CashOutLimits,
+) -> Result<()> {
+    require!(limits.withdrawal_delay >= 0, GameError::InvalidAmount);
+    ctx.accounts.config.cash_out_limits = limits;
+
+    emit!(CashOutLimitsUpdated {
+        limits,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Convert `amount` withdrawable credits into JUNK from the vault. Amounts
+/// at or above the queue threshold must go through `queue_cash_out`.
+pub(crate) fn cash_out(ctx: Context<CashOut>, amount: u64) -> Result<()> {
+    let limits = ctx.accounts.config.cash_out_limits;
+    require!(
+        amount < limits.queue_threshold,
+        GameError::CashOutQueueRequired
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    debit_cash_out(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.game_state,
+        amount,
+        now,
+    )?;
+
+    let junk_amount = junk_for_credits(amount)?;
+    pay_from_vault(
+        &ctx.accounts.vault_authority,
+        ctx.bumps.vault_authority,
+        &ctx.accounts.vault_junk_account,
+        &ctx.accounts.player_junk_account,
+        &ctx.accounts.token_program,
+        junk_amount,
+    )?;
+    ctx.accounts.global_stats.record_junk_paid_out(junk_amount);
+
+    emit!(CashedOut {
+        player: ctx.accounts.player.key(),
+        amount,
+        junk_amount,
+        balance: ctx.accounts.game_state.balance,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Take `amount` credits now and hold the JUNK in a `PendingWithdrawal`
+/// until the configured delay has passed
+pub(crate) fn queue_cash_out(ctx: Context<QueueCashOut>, amount: u64) -> Result<()> {
+    let limits = ctx.accounts.config.cash_out_limits;
+    require!(amount >= limits.queue_threshold, GameError::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    debit_cash_out(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.game_state,
+        amount,
+        now,
+    )?;
+
+    let pending = &mut ctx.accounts.pending_withdrawal;
+    pending.player = ctx.accounts.player.key();
+    pending.amount = amount;
+    pending.requested_at = now;
+    pending.release_at = now.saturating_add(limits.withdrawal_delay);
+    pending.frozen = false;
+    pending.bump = ctx.bumps.pending_withdrawal;
+
+    emit!(CashOutQueued {
+        player: pending.player,
+        amount,
+        release_at: pending.release_at,
+        balance: ctx.accounts.game_state.balance,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Pay out a queued cash-out once its delay has passed. Not pausable.
+pub(crate) fn claim_cash_out(ctx: Context<ClaimCashOut>) -> Result<()> {
+    let pending = &ctx.accounts.pending_withdrawal;
+    let now = Clock::get()?.unix_timestamp;
+    require!(!pending.frozen, GameError::WithdrawalFrozen);
+    require!(now >= pending.release_at, GameError::WithdrawalLocked);
+
+    let amount = pending.amount;
+    let junk_amount = junk_for_credits(amount)?;
+    pay_from_vault(
+        &ctx.accounts.vault_authority,
+        ctx.bumps.vault_authority,
+        &ctx.accounts.vault_junk_account,
+        &ctx.accounts.player_junk_account,
+        &ctx.accounts.token_program,
+        junk_amount,
+    )?;
+    ctx.accounts.global_stats.record_junk_paid_out(junk_amount);
+
+    emit!(CashedOut {
+        player: pending.player,
+        amount,
+        junk_amount,
+        balance: ctx.accounts.game_state.balance,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Freeze or release a queued cash-out (admin only)
+pub(crate) fn set_cash_out_frozen(ctx: Context<SetCashOutFrozen>, frozen: bool) -> Result<()> {
+    let pending = &mut ctx.accounts.pending_withdrawal;
+    pending.frozen = frozen;
+
+    emit!(CashOutFrozen {
+        player: pending.player,
+        amount: pending.amount,
+        frozen,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Give a queued cash-out back to the player and close it (admin only).
+/// This is the way out for a frozen withdrawal: the credits return to the
+/// player's balance and stop counting against the caps.
+pub(crate) fn cancel_cash_out(ctx: Context<CancelCashOut>) -> Result<()> {
+    let amount = ctx.accounts.pending_withdrawal.amount;
+    let requested_at = ctx.accounts.pending_withdrawal.requested_at;
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts
+        .config
+        .cash_out_window
+        .release(amount, requested_at, now);
+    let game_state = &mut ctx.accounts.game_state;
+    game_state
+        .cash_out_window
+        .release(amount, requested_at, now);
+    game_state.balance = game_state.balance.saturating_add(amount);
+    game_state.last_updated = now;
+
+    emit!(CashOutCancelled {
+        player: game_state.player,
+        amount,
+        balance: game_state.balance,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Check both caps and take `amount` withdrawable credits from the player
+fn debit_cash_out(
+    config: &mut GameConfig,
+    game_state: &mut GameState,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    require!(amount > 0, GameError::InvalidAmount);
+    require!(
+        game_state.withdrawable_balance() >= amount,
+        GameError::InsufficientBalance
+    );
+
+    let limits = config.cash_out_limits;
+    game_state
+        .cash_out_window
+        .record(amount, limits.player_daily_cap, now)?;
+    config
+        .cash_out_window
+        .record(amount, limits.global_daily_cap, now)?;
+
+    game_state.balance -= amount;
+    game_state.last_updated = now;
+    Ok(())
+}
+
+fn junk_for_credits(amount: u64) -> Result<u64> {
+    amount
+        .checked_mul(JUNK_PER_CREDIT)
+        .ok_or_else(|| error!(GameError::InvalidAmount))
+}
+
+fn pay_from_vault<'info>(
+    vault_authority: &UncheckedAccount<'info>,
+    vault_bump: u8,
+    vault_junk_account: &Account<'info, TokenAccount>,
+    player_junk_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"vault".as_ref(), &[vault_bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: vault_junk_account.to_account_info(),
+        to: player_junk_account.to_account_info(),
+        authority: vault_authority.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer),
+        amount,
+    )
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetCashOutLimits<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CashOut<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_junk_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Account<'info, TokenAccount>,
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct QueueCashOut<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<PendingWithdrawal>(),
+        seeds = [b"pending_withdrawal", player.key().as_ref()],
+        bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCashOut<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"pending_withdrawal", player.key().as_ref()],
+        bump = pending_withdrawal.bump,
+        has_one = player,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_junk_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetCashOutFrozen<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"pending_withdrawal", pending_withdrawal.player.as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelCashOut<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"pending_withdrawal", player.key().as_ref()],
+        bump = pending_withdrawal.bump,
+        has_one = player,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+    /// CHECK: the player the withdrawal belongs to, refunded its rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+    pub admin: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// Cash-out caps, in credits. The default (zero caps) disables cash-out.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CashOutLimits {
+    /// Most credits one player can cash out per window
+    pub player_daily_cap: u64,
+    /// Most credits all players together can cash out per window
+    pub global_daily_cap: u64,
+    /// Cash-outs of at least this many credits are queued; 0 queues all
+    pub queue_threshold: u64,
+    /// Seconds a queued cash-out waits before it can be claimed
+    pub withdrawal_delay: i64,
+}
+
+/// Credits cashed out over the last `CASH_OUT_WINDOW`, in hourly buckets.
+/// The window rolls forward an hour at a time: a cash-out stops counting
+/// at the start of the same hour the next day.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CashOutWindow {
+    /// Hour (unix time / 3600) of the newest bucket
+    pub hour: i64,
+    /// Credits per hour; hour `h` is kept at index `h % CASH_OUT_BUCKETS`
+    pub buckets: [u64; CASH_OUT_BUCKETS],
+}
+
+impl CashOutWindow {
+    /// Credits cashed out inside the window
+    pub fn total(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Add `amount` to the current hour if the window stays within `cap`
+    pub fn record(&mut self, amount: u64, cap: u64, now: i64) -> Result<()> {
+        self.advance(now);
+        let total = self
+            .total()
+            .checked_add(amount)
+            .ok_or(GameError::InvalidAmount)?;
+        require!(total <= cap, GameError::CashOutCapExceeded);
+        self.buckets[Self::index(self.hour)] += amount;
+        Ok(())
+    }
+
+    /// Take back `amount` recorded at `recorded_at`, if it is still in the
+    /// window
+    pub fn release(&mut self, amount: u64, recorded_at: i64, now: i64) {
+        self.advance(now);
+        let hour = recorded_at.div_euclid(CASH_OUT_BUCKET_SECONDS);
+        if hour <= self.hour && self.hour - hour < CASH_OUT_BUCKETS as i64 {
+            let bucket = &mut self.buckets[Self::index(hour)];
+            *bucket = bucket.saturating_sub(amount);
+        }
+    }
+
+    /// Move the window up to `now`, emptying the hours that fell out of it
+    fn advance(&mut self, now: i64) {
+        let hour = now.div_euclid(CASH_OUT_BUCKET_SECONDS);
+        if hour <= self.hour {
+            return;
+        }
+        if hour - self.hour >= CASH_OUT_BUCKETS as i64 {
+            self.buckets = [0; CASH_OUT_BUCKETS];
+        } else {
+            for expired in self.hour + 1..=hour {
+                self.buckets[Self::index(expired)] = 0;
+            }
+        }
+        self.hour = hour;
+    }
+
+    fn index(hour: i64) -> usize {
+        hour.rem_euclid(CASH_OUT_BUCKETS as i64) as usize
+    }
+}
+
+/// A large cash-out waiting out its delay. One per player at a time.
+#[account]
+pub struct PendingWithdrawal {
+    pub player: Pubkey,
+    /// Credits already taken from the player's balance
+    pub amount: u64,
+    pub requested_at: i64,
+    pub release_at: i64,
+    pub frozen: bool,
+    pub bump: u8,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct CashOutLimitsUpdated {
+    pub limits: CashOutLimits,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CashedOut {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub junk_amount: u64,
+    pub balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CashOutQueued {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub release_at: i64,
+    pub balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CashOutFrozen {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub frozen: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CashOutCancelled {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+    pub timestamp: i64,
+}
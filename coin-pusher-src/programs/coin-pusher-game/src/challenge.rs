@@ -0,0 +1,636 @@
+use std::cmp::Ordering;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+
+use crate::{instruction_flags, GameConfig, GameError, GameState, BPS_DENOMINATOR};
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Set the challenge rake and stake/duration limits (admin only). Open
+/// challenges keep the rake they were created with.
+pub(crate) fn set_challenge_rules(
+    ctx: Context<SetChallengeRules>,
+    rules: ChallengeRules,
+) -> Result<()> {
+    require!(
+        rules.rake_bps as u64 <= BPS_DENOMINATOR,
+        GameError::InvalidChallenge
+    );
+    ctx.accounts.config.challenge_rules = rules;
+
+    emit!(ChallengeRulesUpdated {
+        rake_bps: rules.rake_bps,
+        min_stake: rules.min_stake,
+        max_duration: rules.max_duration,
+    });
+
+    Ok(())
+}
+
+/// Escrow `stake` JUNK and challenge `opponent` (or anyone, if default) to
+/// a match lasting `duration` seconds from acceptance
+pub(crate) fn create_challenge(
+    ctx: Context<CreateChallenge>,
+    challenge_id: u64,
+    opponent: Pubkey,
+    stake: u64,
+    duration: i64,
+    accept_deadline: i64,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    config.require_enabled(instruction_flags::CHALLENGE)?;
+
+    let rules = config.challenge_rules;
+    let challenger = ctx.accounts.challenger.key();
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.game_state.require_active(now)?;
+    // Both stakes together must fit in a pot
+    require!(
+        stake > 0 && stake >= rules.min_stake && stake.checked_mul(2).is_some(),
+        GameError::InvalidChallenge
+    );
+    require!(
+        duration > 0 && (rules.max_duration == 0 || duration <= rules.max_duration),
+        GameError::InvalidChallenge
+    );
+    require!(accept_deadline > now, GameError::InvalidChallenge);
+    require_keys_neq!(opponent, challenger, GameError::InvalidChallenge);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.challenger_junk_account.to_account_info(),
+        to: ctx.accounts.challenge_vault.to_account_info(),
+        authority: ctx.accounts.challenger.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), stake)?;
+
+    ctx.accounts.challenge.set_inner(Challenge {
+        challenge_id,
+        challenger,
+        opponent,
+        stake,
+        rake_bps: rules.rake_bps,
+        duration,
+        accept_deadline,
+        start_time: 0,
+        end_time: 0,
+        challenger_score: 0,
+        opponent_score: 0,
+        status: ChallengeStatus::Open,
+        bump: ctx.bumps.challenge,
+    });
+
+    emit!(ChallengeCreated {
+        challenge: ctx.accounts.challenge.key(),
+        challenge_id,
+        challenger,
+        opponent,
+        stake,
+        duration,
+        accept_deadline,
+    });
+
+    Ok(())
+}
+
+/// Match the stake and start the match window
+pub(crate) fn accept_challenge(ctx: Context<AcceptChallenge>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::CHALLENGE)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.game_state.require_active(now)?;
+    let opponent = ctx.accounts.opponent.key();
+    let challenge = &ctx.accounts.challenge;
+    require!(
+        challenge.status == ChallengeStatus::Open && now < challenge.accept_deadline,
+        GameError::ChallengeClosed
+    );
+    require!(
+        challenge.is_open_to_anyone() || challenge.opponent == opponent,
+        GameError::Unauthorized
+    );
+    require_keys_neq!(opponent, challenge.challenger, GameError::Unauthorized);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.opponent_junk_account.to_account_info(),
+        to: ctx.accounts.challenge_vault.to_account_info(),
+        authority: ctx.accounts.opponent.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), challenge.stake)?;
+
+    let challenge = &mut ctx.accounts.challenge;
+    challenge.opponent = opponent;
+    challenge.start_time = now;
+    challenge.end_time = now.saturating_add(challenge.duration);
+    challenge.status = ChallengeStatus::Active;
+
+    emit!(ChallengeAccepted {
+        challenge: challenge.key(),
+        challenger: challenge.challenger,
+        opponent,
+        start_time: challenge.start_time,
+        end_time: challenge.end_time,
+    });
+
+    Ok(())
+}
+
+/// Pay out a finished match and close it. The winner takes both stakes
+/// less the rake; a tie refunds both players. Permissionless, not pausable.
+pub(crate) fn settle_challenge(ctx: Context<SettleChallenge>) -> Result<()> {
+    let challenge = &ctx.accounts.challenge;
+    require!(
+        challenge.status == ChallengeStatus::Active
+            && Clock::get()?.unix_timestamp >= challenge.end_time,
+        GameError::ChallengeNotEnded
+    );
+
+    let pot = challenge
+        .stake
+        .checked_mul(2)
+        .ok_or(GameError::InvalidAmount)?;
+    let (winner, rake) = match challenge.challenger_score.cmp(&challenge.opponent_score) {
+        Ordering::Greater => (challenge.challenger, challenge.rake(pot)),
+        Ordering::Less => (challenge.opponent, challenge.rake(pot)),
+        Ordering::Equal => (Pubkey::default(), 0),
+    };
+    let payout = pot - rake;
+
+    let accounts = &ctx.accounts;
+    let payments = if winner == Pubkey::default() {
+        [
+            (&accounts.challenger_junk_account, challenge.stake),
+            (&accounts.opponent_junk_account, challenge.stake),
+        ]
+    } else if winner == challenge.challenger {
+        [
+            (&accounts.challenger_junk_account, payout),
+            (&accounts.vault_junk_account, rake),
+        ]
+    } else {
+        [
+            (&accounts.opponent_junk_account, payout),
+            (&accounts.vault_junk_account, rake),
+        ]
+    };
+    for (to, amount) in payments {
+        pay_from_escrow(
+            &accounts.challenge,
+            &accounts.challenge_vault,
+            to,
+            &accounts.token_program,
+            amount,
+        )?;
+    }
+
+    emit!(ChallengeSettled {
+        challenge: challenge.key(),
+        challenger_score: challenge.challenger_score,
+        opponent_score: challenge.opponent_score,
+        winner,
+        payout,
+        rake,
+    });
+
+    // Anything sent to the escrow on top of the stakes goes to the house
+    let accounts = &mut *ctx.accounts;
+    close_challenge_vault(
+        &accounts.challenge,
+        &mut accounts.challenge_vault,
+        &accounts.vault_junk_account,
+        &accounts.challenger,
+        &accounts.token_program,
+    )
+}
+
+/// Return the challenger's stake once the accept deadline passes with no
+/// opponent, and close the challenge. Permissionless, not pausable.
+pub(crate) fn refund_challenge(ctx: Context<RefundChallenge>) -> Result<()> {
+    let challenge = &ctx.accounts.challenge;
+    require!(
+        challenge.status == ChallengeStatus::Open
+            && Clock::get()?.unix_timestamp >= challenge.accept_deadline,
+        GameError::ChallengeNotEnded
+    );
+
+    pay_from_escrow(
+        &ctx.accounts.challenge,
+        &ctx.accounts.challenge_vault,
+        &ctx.accounts.challenger_junk_account,
+        &ctx.accounts.token_program,
+        challenge.stake,
+    )?;
+
+    emit!(ChallengeRefunded {
+        challenge: challenge.key(),
+        challenger: challenge.challenger,
+        stake: challenge.stake,
+    });
+
+    let accounts = &mut *ctx.accounts;
+    close_challenge_vault(
+        &accounts.challenge,
+        &mut accounts.challenge_vault,
+        &accounts.challenger_junk_account,
+        &accounts.challenger,
+        &accounts.token_program,
+    )
+}
+
+/// Add an attested collection to the player's side of an active match.
+/// Called from `collect_coin`.
+pub fn credit_challenge_score(
+    challenge: &mut Challenge,
+    challenge_key: Pubkey,
+    player: Pubkey,
+    points: u64,
+    now: i64,
+) -> Result<()> {
+    let score = if player == challenge.challenger {
+        &mut challenge.challenger_score
+    } else if player == challenge.opponent && !challenge.is_open_to_anyone() {
+        &mut challenge.opponent_score
+    } else {
+        return err!(GameError::NotChallengeParticipant);
+    };
+    if challenge.status != ChallengeStatus::Active
+        || now < challenge.start_time
+        || now >= challenge.end_time
+    {
+        return Ok(());
+    }
+    *score = score.saturating_add(points);
+
+    emit!(ChallengeScoreUpdated {
+        challenge: challenge_key,
+        player,
+        challenger_score: challenge.challenger_score,
+        opponent_score: challenge.opponent_score,
+    });
+
+    Ok(())
+}
+
+fn pay_from_escrow<'info>(
+    challenge: &Account<'info, Challenge>,
+    challenge_vault: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let id_bytes = challenge.challenge_id.to_le_bytes();
+    let seeds = &[
+        b"challenge".as_ref(),
+        challenge.challenger.as_ref(),
+        id_bytes.as_ref(),
+        &[challenge.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: challenge_vault.to_account_info(),
+        to: to.to_account_info(),
+        authority: challenge.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer),
+        amount,
+    )
+}
+
+/// Sweep what is left in the escrow to `leftover_to` and close it,
+/// returning its rent to the challenger. Tokens sent to the escrow from
+/// outside would otherwise make the close fail and lock the stakes.
+fn close_challenge_vault<'info>(
+    challenge: &Account<'info, Challenge>,
+    challenge_vault: &mut Account<'info, TokenAccount>,
+    leftover_to: &Account<'info, TokenAccount>,
+    challenger: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    challenge_vault.reload()?;
+    let leftover = challenge_vault.amount;
+    pay_from_escrow(
+        challenge,
+        challenge_vault,
+        leftover_to,
+        token_program,
+        leftover,
+    )?;
+
+    let id_bytes = challenge.challenge_id.to_le_bytes();
+    let seeds = &[
+        b"challenge".as_ref(),
+        challenge.challenger.as_ref(),
+        id_bytes.as_ref(),
+        &[challenge.bump],
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = CloseAccount {
+        account: challenge_vault.to_account_info(),
+        destination: challenger.to_account_info(),
+        authority: challenge.to_account_info(),
+    };
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    ))
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetChallengeRules<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(challenge_id: u64)]
+pub struct CreateChallenge<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", challenger.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + std::mem::size_of::<Challenge>(),
+        seeds = [b"challenge", challenger.key().as_ref(), challenge_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub challenge: Box<Account<'info, Challenge>>,
+    #[account(
+        init,
+        payer = challenger,
+        token::mint = junk_mint,
+        token::authority = challenge,
+        seeds = [b"challenge_vault", challenge.key().as_ref()],
+        bump,
+    )]
+    pub challenge_vault: Box<Account<'info, TokenAccount>>,
+    #[account(address = config.junk_mint)]
+    pub junk_mint: Box<Account<'info, Mint>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub challenger_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptChallenge<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", opponent.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [
+            b"challenge",
+            challenge.challenger.as_ref(),
+            challenge.challenge_id.to_le_bytes().as_ref(),
+        ],
+        bump = challenge.bump,
+    )]
+    pub challenge: Box<Account<'info, Challenge>>,
+    #[account(
+        mut,
+        seeds = [b"challenge_vault", challenge.key().as_ref()],
+        bump,
+    )]
+    pub challenge_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub opponent_junk_account: Box<Account<'info, TokenAccount>>,
+    pub opponent: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SettleChallenge<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [
+            b"challenge",
+            challenge.challenger.as_ref(),
+            challenge.challenge_id.to_le_bytes().as_ref(),
+        ],
+        bump = challenge.bump,
+        close = challenger,
+    )]
+    pub challenge: Box<Account<'info, Challenge>>,
+    #[account(
+        mut,
+        seeds = [b"challenge_vault", challenge.key().as_ref()],
+        bump,
+    )]
+    pub challenge_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = challenge.challenger,
+    )]
+    pub challenger_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = challenge.opponent,
+    )]
+    pub opponent_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = challenge.challenger)]
+    /// CHECK: receives the challenge and escrow rent
+    pub challenger: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RefundChallenge<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"challenge",
+            challenge.challenger.as_ref(),
+            challenge.challenge_id.to_le_bytes().as_ref(),
+        ],
+        bump = challenge.bump,
+        close = challenger,
+    )]
+    pub challenge: Box<Account<'info, Challenge>>,
+    #[account(
+        mut,
+        seeds = [b"challenge_vault", challenge.key().as_ref()],
+        bump,
+    )]
+    pub challenge_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = challenge_vault.mint,
+        token::authority = challenge.challenger,
+    )]
+    pub challenger_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = challenge.challenger)]
+    /// CHECK: receives the challenge and escrow rent
+    pub challenger: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// Admin limits for new challenges
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChallengeRules {
+    /// House share of the pot when there is a winner; 10_000 = everything
+    pub rake_bps: u16,
+    /// Smallest stake accepted, in JUNK base units
+    pub min_stake: u64,
+    /// Longest match allowed, in seconds; 0 = no limit
+    pub max_duration: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChallengeStatus {
+    /// Waiting for an opponent until `accept_deadline`
+    #[default]
+    Open,
+    /// Both stakes escrowed; collections count until `end_time`
+    Active,
+}
+
+#[account]
+pub struct Challenge {
+    pub challenge_id: u64,
+    pub challenger: Pubkey,
+    /// Invited opponent, or the default key for an open challenge until
+    /// someone accepts
+    pub opponent: Pubkey,
+    /// Escrowed by each side
+    pub stake: u64,
+    /// Rake in force when the challenge was created
+    pub rake_bps: u16,
+    /// Match length in seconds, counted from acceptance
+    pub duration: i64,
+    pub accept_deadline: i64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub challenger_score: u64,
+    pub opponent_score: u64,
+    pub status: ChallengeStatus,
+    pub bump: u8,
+}
+
+impl Challenge {
+    pub fn is_open_to_anyone(&self) -> bool {
+        self.opponent == Pubkey::default()
+    }
+
+    pub fn rake(&self, pot: u64) -> u64 {
+        (pot as u128 * self.rake_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct ChallengeRulesUpdated {
+    pub rake_bps: u16,
+    pub min_stake: u64,
+    pub max_duration: i64,
+}
+
+#[event]
+pub struct ChallengeCreated {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub challenger: Pubkey,
+    pub opponent: Pubkey,
+    pub stake: u64,
+    pub duration: i64,
+    pub accept_deadline: i64,
+}
+
+#[event]
+pub struct ChallengeAccepted {
+    pub challenge: Pubkey,
+    pub challenger: Pubkey,
+    pub opponent: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct ChallengeScoreUpdated {
+    pub challenge: Pubkey,
+    pub player: Pubkey,
+    pub challenger_score: u64,
+    pub opponent_score: u64,
+}
+
+#[event]
+pub struct ChallengeSettled {
+    pub challenge: Pubkey,
+    pub challenger_score: u64,
+    pub opponent_score: u64,
+    /// Default key on a tie
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub rake: u64,
+}
+
+#[event]
+pub struct ChallengeRefunded {
+    pub challenge: Pubkey,
+    pub challenger: Pubkey,
+    pub stake: u64,
+}
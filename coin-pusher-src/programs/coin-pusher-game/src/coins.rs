@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{GameConfig, GameError, GameState};
+
+/// Number of entries in `GameConfig::coin_types`
+pub const MAX_COIN_TYPES: usize = 8;
+
+/// Plain coin, worth 1 credit by default
+pub const COIN_TYPE_NORMAL: u8 = 0;
+
+/// Trashcoin, worth 5 credits by default. Also consumes `TrashcoinDrop`
+/// effects and counts towards `GameState::trashcoins_collected`.
+pub const COIN_TYPE_TRASHCOIN: u8 = 1;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the coin type table (admin only). Coins can't pay out
+/// TRASHCOIN, which is only minted against the emission cap.
+pub(crate) fn set_coin_types(
+    ctx: Context<SetCoinTypes>,
+    coin_types: [CoinType; MAX_COIN_TYPES],
+) -> Result<()> {
+    for coin_type in coin_types.iter() {
+        coin_type.validate(&ctx.accounts.config.trashcoin_mint)?;
+    }
+    ctx.accounts.config.coin_types = coin_types;
+
+    emit!(CoinTypesUpdated {
+        coin_types,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Count a collected coin against the player's per-type counters and the
+/// type's per-epoch cap
+pub(crate) fn record_coin_collection(
+    game_state: &mut GameState,
+    coin_type: u8,
+    coin: &CoinType,
+    epoch: u64,
+) -> Result<()> {
+    if game_state.coin_epoch != epoch {
+        game_state.coin_epoch = epoch;
+        game_state.epoch_coins_collected = [0; MAX_COIN_TYPES];
+    }
+
+    let index = coin_type as usize;
+    let collected = game_state.epoch_coins_collected[index].saturating_add(1);
+    require!(
+        coin.epoch_cap == 0 || collected <= coin.epoch_cap,
+        GameError::CoinTypeCapReached
+    );
+    game_state.epoch_coins_collected[index] = collected;
+    game_state.coins_collected[index] = game_state.coins_collected[index].saturating_add(1);
+    Ok(())
+}
+
+/// Pay a coin type's token bonus from the house vault. The vault and
+/// player token accounts must both hold `coin.payout_mint`.
+pub(crate) fn pay_coin_payout<'info>(
+    coin: &CoinType,
+    player: &Pubkey,
+    vault_authority: Option<&UncheckedAccount<'info>>,
+    vault_bump: Option<u8>,
+    vault_payout_account: Option<&Account<'info, TokenAccount>>,
+    player_payout_account: Option<&Account<'info, TokenAccount>>,
+    token_program: Option<&Program<'info, Token>>,
+) -> Result<()> {
+    let (
+        Some(vault_authority),
+        Some(vault_bump),
+        Some(vault_payout_account),
+        Some(player_payout_account),
+        Some(token_program),
+    ) = (
+        vault_authority,
+        vault_bump,
+        vault_payout_account,
+        player_payout_account,
+        token_program,
+    )
+    else {
+        return err!(GameError::MissingPayoutAccount);
+    };
+    require_keys_eq!(
+        vault_payout_account.mint,
+        coin.payout_mint,
+        GameError::InvalidMint
+    );
+    require_keys_eq!(
+        vault_payout_account.owner,
+        vault_authority.key(),
+        GameError::Unauthorized
+    );
+    require_keys_eq!(
+        player_payout_account.mint,
+        coin.payout_mint,
+        GameError::InvalidMint
+    );
+    require_keys_eq!(
+        player_payout_account.owner,
+        *player,
+        GameError::Unauthorized
+    );
+
+    let seeds = &[b"vault".as_ref(), &[vault_bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: vault_payout_account.to_account_info(),
+        to: player_payout_account.to_account_info(),
+        authority: vault_authority.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer),
+        coin.payout_amount,
+    )
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetCoinTypes<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// One row of the config's coin type table. A zero `reward` marks the slot
+/// as unused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CoinType {
+    /// Credits awarded per coin, before staking and item boosts
+    pub reward: u64,
+    /// Relative odds of spawning this coin; read by the game server
+    pub spawn_weight: u16,
+    /// Token paid from the vault per coin, if `payout_amount` is non-zero
+    pub payout_mint: Pubkey,
+    /// Token base units paid per coin
+    pub payout_amount: u64,
+    /// Most coins of this type one player can collect per epoch; 0 = no cap
+    pub epoch_cap: u32,
+}
+
+impl CoinType {
+    /// The built-in table: normal coins and trashcoins
+    pub fn default_table() -> [CoinType; MAX_COIN_TYPES] {
+        let mut table = [CoinType::default(); MAX_COIN_TYPES];
+        table[COIN_TYPE_NORMAL as usize] = CoinType {
+            reward: 1,
+            spawn_weight: 95,
+            ..CoinType::default()
+        };
+        table[COIN_TYPE_TRASHCOIN as usize] = CoinType {
+            reward: 5,
+            spawn_weight: 5,
+            ..CoinType::default()
+        };
+        table
+    }
+
+    pub fn validate(&self, trashcoin_mint: &Pubkey) -> Result<()> {
+        if self.reward == 0 {
+            require!(
+                self.spawn_weight == 0 && self.payout_amount == 0,
+                GameError::InvalidCoinType
+            );
+            return Ok(());
+        }
+        if self.payout_amount > 0 {
+            require_keys_neq!(
+                self.payout_mint,
+                Pubkey::default(),
+                GameError::InvalidCoinType
+            );
+            require_keys_neq!(
+                self.payout_mint,
+                *trashcoin_mint,
+                GameError::InvalidCoinType
+            );
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.reward > 0
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct CoinTypesUpdated {
+    pub coin_types: [CoinType; MAX_COIN_TYPES],
+    pub timestamp: i64,
+}
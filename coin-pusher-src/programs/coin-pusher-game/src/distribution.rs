@@ -0,0 +1,433 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::{instruction_flags, GameConfig, GameError, GameState};
+
+/// Most claims one distribution can hold; keeps the claimed bitmap small
+/// enough to be created in a single instruction
+pub const MAX_DISTRIBUTION_CLAIMS: u32 = 65_536;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Post a distribution (admin only): the merkle root of its
+/// `(index, claimant, amount)` leaves, funded with `total_amount` of `mint`
+/// from the admin's token account. Unclaimed tokens can be clawed back
+/// once `expires_at` has passed.
+pub(crate) fn create_distribution(
+    ctx: Context<CreateDistribution>,
+    distribution_id: u64,
+    merkle_root: [u8; 32],
+    total_amount: u64,
+    num_claims: u32,
+    expires_at: i64,
+) -> Result<()> {
+    require!(
+        total_amount > 0 && num_claims > 0 && num_claims <= MAX_DISTRIBUTION_CLAIMS,
+        GameError::InvalidDistribution
+    );
+    require!(
+        expires_at > Clock::get()?.unix_timestamp,
+        GameError::InvalidDistribution
+    );
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.admin_token_account.to_account_info(),
+        to: ctx.accounts.distribution_vault.to_account_info(),
+        authority: ctx.accounts.admin.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), total_amount)?;
+
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.distribution_id = distribution_id;
+    distribution.mint = ctx.accounts.mint.key();
+    distribution.merkle_root = merkle_root;
+    distribution.total_amount = total_amount;
+    distribution.total_claimed = 0;
+    distribution.num_claims = num_claims;
+    distribution.claim_count = 0;
+    distribution.expires_at = expires_at;
+    distribution.clawed_back = false;
+    distribution.bump = ctx.bumps.distribution;
+
+    let claims = &mut ctx.accounts.distribution_claims;
+    claims.distribution = distribution.key();
+    claims.bits = vec![0; DistributionClaims::bitmap_len(num_claims)];
+    claims.bump = ctx.bumps.distribution_claims;
+
+    emit!(DistributionCreated {
+        distribution: distribution.key(),
+        distribution_id,
+        mint: distribution.mint,
+        merkle_root,
+        total_amount,
+        num_claims,
+        expires_at,
+    });
+
+    Ok(())
+}
+
+/// Claim leaf `index` of a distribution, proving `(index, claimant, amount)`
+/// is in its merkle tree. Each index pays out once.
+pub(crate) fn claim_distribution(
+    ctx: Context<ClaimDistribution>,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::CLAIM_DISTRIBUTION)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    // Claimants needn't have played, but a frozen or banned player can't claim
+    let game_state = &ctx.accounts.claimant_game_state;
+    if game_state.owner == &crate::ID {
+        GameState::try_deserialize(&mut &game_state.try_borrow_data()?[..])?
+            .require_active(now)?;
+    }
+
+    let distribution = &ctx.accounts.distribution;
+    require!(
+        now < distribution.expires_at,
+        GameError::DistributionExpired
+    );
+    require!(
+        index < distribution.num_claims,
+        GameError::InvalidDistribution
+    );
+
+    let claimant = ctx.accounts.claimant.key();
+    let leaf = distribution_leaf(index, &claimant, amount);
+    require!(
+        verify_merkle_proof(&proof, &distribution.merkle_root, leaf),
+        GameError::InvalidMerkleProof
+    );
+    ctx.accounts.distribution_claims.set_claimed(index)?;
+
+    let total_claimed = distribution
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(GameError::InvalidAmount)?;
+    require!(
+        total_claimed <= distribution.total_amount,
+        GameError::InvalidDistribution
+    );
+
+    let id_bytes = distribution.distribution_id.to_le_bytes();
+    let seeds = &[
+        b"distribution".as_ref(),
+        id_bytes.as_ref(),
+        &[distribution.bump],
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.distribution_vault.to_account_info(),
+        to: ctx.accounts.claimant_token_account.to_account_info(),
+        authority: distribution.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+        amount,
+    )?;
+
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.total_claimed = total_claimed;
+    distribution.claim_count += 1;
+
+    emit!(DistributionClaimed {
+        distribution: distribution.key(),
+        claimant,
+        index,
+        amount,
+        total_claimed,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Return an expired distribution's unclaimed tokens to the admin
+pub(crate) fn clawback_distribution(ctx: Context<ClawbackDistribution>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let distribution = &ctx.accounts.distribution;
+    require!(
+        now >= distribution.expires_at,
+        GameError::DistributionNotExpired
+    );
+    require!(!distribution.clawed_back, GameError::InvalidDistribution);
+
+    let amount = ctx.accounts.distribution_vault.amount;
+    if amount > 0 {
+        let id_bytes = distribution.distribution_id.to_le_bytes();
+        let seeds = &[
+            b"distribution".as_ref(),
+            id_bytes.as_ref(),
+            &[distribution.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.distribution_vault.to_account_info(),
+            to: ctx.accounts.admin_token_account.to_account_info(),
+            authority: distribution.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            amount,
+        )?;
+    }
+    ctx.accounts.distribution.clawed_back = true;
+
+    emit!(DistributionClawedBack {
+        distribution: ctx.accounts.distribution.key(),
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Leaf hash of one claim. The prefix keeps leaves and inner nodes apart.
+pub fn distribution_leaf(index: u32, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        &[0],
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Inner node over two children, hashed in sorted order so proofs don't
+/// need to say which side each sibling is on
+pub fn merkle_parent(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1], left, right]).to_bytes()
+}
+
+/// Whether `proof` leads from `leaf` up to `root`
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let node = proof
+        .iter()
+        .fold(leaf, |node, sibling| merkle_parent(&node, sibling));
+    node == *root
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(distribution_id: u64, merkle_root: [u8; 32], total_amount: u64, num_claims: u32)]
+pub struct CreateDistribution<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<Distribution>(),
+        seeds = [b"distribution", distribution_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub distribution: Box<Account<'info, Distribution>>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = distribution,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump,
+    )]
+    pub distribution_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        space = DistributionClaims::space(num_claims),
+        seeds = [b"distribution_claims", distribution.key().as_ref()],
+        bump,
+    )]
+    pub distribution_claims: Account<'info, DistributionClaims>,
+    pub mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub admin_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDistribution<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"distribution", distribution.distribution_id.to_le_bytes().as_ref()],
+        bump = distribution.bump,
+    )]
+    pub distribution: Box<Account<'info, Distribution>>,
+    #[account(
+        mut,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump,
+    )]
+    pub distribution_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"distribution_claims", distribution.key().as_ref()],
+        bump = distribution_claims.bump,
+    )]
+    pub distribution_claims: Account<'info, DistributionClaims>,
+    #[account(
+        mut,
+        token::mint = distribution.mint,
+    )]
+    pub claimant_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"game_state", claimant.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: the claimant's game, if they have one; checked in the handler
+    pub claimant_game_state: UncheckedAccount<'info>,
+    pub claimant: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClawbackDistribution<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"distribution", distribution.distribution_id.to_le_bytes().as_ref()],
+        bump = distribution.bump,
+    )]
+    pub distribution: Box<Account<'info, Distribution>>,
+    #[account(
+        mut,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump,
+    )]
+    pub distribution_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = distribution.mint,
+    )]
+    pub admin_token_account: Box<Account<'info, TokenAccount>>,
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// An airdrop of `mint` committed to by a merkle root
+#[account]
+pub struct Distribution {
+    pub distribution_id: u64,
+    pub mint: Pubkey,
+    /// Root over `distribution_leaf(index, claimant, amount)` leaves
+    pub merkle_root: [u8; 32],
+    /// Funded into the vault when the distribution is posted
+    pub total_amount: u64,
+    pub total_claimed: u64,
+    /// Leaf indices run from 0 to `num_claims - 1`
+    pub num_claims: u32,
+    pub claim_count: u32,
+    /// Claims close at this time and the remainder can be clawed back
+    pub expires_at: i64,
+    pub clawed_back: bool,
+    pub bump: u8,
+}
+
+/// One bit per leaf index, set once the leaf has been claimed
+#[account]
+pub struct DistributionClaims {
+    pub distribution: Pubkey,
+    pub bits: Vec<u8>,
+    pub bump: u8,
+}
+
+impl DistributionClaims {
+    pub fn bitmap_len(num_claims: u32) -> usize {
+        (num_claims as usize).div_ceil(8)
+    }
+
+    /// Account size for a distribution with `num_claims` leaves
+    pub fn space(num_claims: u32) -> usize {
+        8 + 32 + 4 + Self::bitmap_len(num_claims) + 1
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.bits
+            .get(index as usize / 8)
+            .is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+    }
+
+    /// Mark `index` claimed, failing if it already was
+    pub fn set_claimed(&mut self, index: u32) -> Result<()> {
+        require!(
+            !self.is_claimed(index),
+            GameError::DistributionAlreadyClaimed
+        );
+        let byte = self
+            .bits
+            .get_mut(index as usize / 8)
+            .ok_or(GameError::InvalidDistribution)?;
+        *byte |= 1 << (index % 8);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct DistributionCreated {
+    pub distribution: Pubkey,
+    pub distribution_id: u64,
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_claims: u32,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct DistributionClaimed {
+    pub distribution: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u32,
+    pub amount: u64,
+    pub total_claimed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DistributionClawedBack {
+    pub distribution: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+
+use crate::{GameConfig, GameError, GameState, SECONDS_PER_DAY};
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the TRASHCOIN emission schedule (admin only). Emission already
+/// counted for the current epoch still counts against the new budget.
+pub(crate) fn set_emission_schedule(
+    ctx: Context<SetEmissionSchedule>,
+    schedule: EmissionSchedule,
+) -> Result<()> {
+    ctx.accounts.config.emission_schedule = schedule;
+
+    emit!(EmissionScheduleUpdated {
+        schedule,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Count a TRASHCOIN award against the epoch budget and the player's daily
+/// cap. Fails without recording anything if either would be exceeded.
+pub(crate) fn record_emission(
+    config: &mut GameConfig,
+    game_state: &mut GameState,
+    amount: u64,
+    clock: &Clock,
+) -> Result<()> {
+    let schedule = config.emission_schedule;
+    let emission = &mut config.trashcoin_emission;
+    if emission.epoch != clock.epoch {
+        emission.epoch = clock.epoch;
+        emission.epoch_emitted = 0;
+    }
+    let epoch_emitted = emission
+        .epoch_emitted
+        .checked_add(amount)
+        .ok_or(GameError::InvalidAmount)?;
+    require!(
+        epoch_emitted <= schedule.epoch_budget(clock.epoch),
+        GameError::EmissionExhausted
+    );
+
+    let today = clock.unix_timestamp.div_euclid(SECONDS_PER_DAY);
+    if game_state.trashcoin_day != today {
+        game_state.trashcoin_day = today;
+        game_state.trashcoin_awarded_today = 0;
+    }
+    let awarded_today = game_state
+        .trashcoin_awarded_today
+        .checked_add(amount)
+        .ok_or(GameError::InvalidAmount)?;
+    require!(
+        awarded_today <= schedule.player_daily_cap,
+        GameError::TrashcoinCapExceeded
+    );
+
+    emission.epoch_emitted = epoch_emitted;
+    emission.total_emitted = emission.total_emitted.saturating_add(amount);
+    game_state.trashcoin_awarded_today = awarded_today;
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetEmissionSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// How much TRASHCOIN `award_trashcoin` may pay out. The default emits
+/// nothing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EmissionSchedule {
+    /// TRASHCOIN base units that may be awarded per epoch before halving
+    pub initial_epoch_budget: u64,
+    /// First epoch of the schedule; nothing is emitted before it
+    pub start_epoch: u64,
+    /// Epochs between halvings of the budget; 0 = never halves
+    pub halving_interval: u64,
+    /// Most TRASHCOIN base units one player can be awarded per UTC day
+    pub player_daily_cap: u64,
+}
+
+impl EmissionSchedule {
+    /// TRASHCOIN that may be awarded across all players in `epoch`
+    pub fn epoch_budget(&self, epoch: u64) -> u64 {
+        let Some(elapsed) = epoch.checked_sub(self.start_epoch) else {
+            return 0;
+        };
+        if self.halving_interval == 0 {
+            return self.initial_epoch_budget;
+        }
+        let halvings = elapsed / self.halving_interval;
+        self.initial_epoch_budget
+            .checked_shr(halvings.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0)
+    }
+}
+
+/// Running TRASHCOIN emission totals
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TrashcoinEmission {
+    /// Epoch `epoch_emitted` was counted in
+    pub epoch: u64,
+    pub epoch_emitted: u64,
+    /// Everything `award_trashcoin` has ever paid out
+    pub total_emitted: u64,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct EmissionScheduleUpdated {
+    pub schedule: EmissionSchedule,
+    pub timestamp: i64,
+}
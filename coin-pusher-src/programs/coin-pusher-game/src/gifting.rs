@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+
+use crate::{instruction_flags, GameConfig, GameError, GameState, SECONDS_PER_DAY};
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the gifting limits (admin only)
+pub(crate) fn set_gift_limits(ctx: Context<SetGiftLimits>, limits: GiftLimits) -> Result<()> {
+    require!(limits.min_account_age >= 0, GameError::InvalidAmount);
+    ctx.accounts.config.gift_limits = limits;
+
+    emit!(GiftLimitsUpdated {
+        daily_cap: limits.daily_cap,
+        min_account_age: limits.min_account_age,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Move `amount` withdrawable credits from the sender's game to the recipient's
+pub(crate) fn gift_balance(ctx: Context<GiftBalance>, amount: u64) -> Result<()> {
+    let config = &ctx.accounts.config;
+    config.require_enabled(instruction_flags::GIFT_BALANCE)?;
+    require!(amount > 0, GameError::InvalidAmount);
+    require_keys_neq!(
+        ctx.accounts.sender.key(),
+        ctx.accounts.recipient.key(),
+        GameError::CannotGiftSelf
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let limits = config.gift_limits;
+    ctx.accounts.sender_game_state.require_active(now)?;
+    ctx.accounts.recipient_game_state.require_active(now)?;
+
+    let sender_state = &mut ctx.accounts.sender_game_state;
+    limits.require_account_age(sender_state.created_at, now)?;
+    require!(
+        sender_state.withdrawable_balance() >= amount,
+        GameError::InsufficientBalance
+    );
+
+    let (gift_day, gifted_today) = limits.daily_total(
+        sender_state.gift_day,
+        sender_state.gifted_today,
+        amount,
+        now,
+    )?;
+    sender_state.gift_day = gift_day;
+    sender_state.gifted_today = gifted_today;
+    sender_state.balance -= amount;
+    sender_state.last_updated = now;
+
+    // Gifts arrive as withdrawable credits
+    let recipient_state = &mut ctx.accounts.recipient_game_state;
+    recipient_state.balance = recipient_state.balance.saturating_add(amount);
+
+    emit!(BalanceGifted {
+        from: sender_state.player,
+        to: recipient_state.player,
+        amount,
+        sender_balance: sender_state.balance,
+        recipient_balance: recipient_state.balance,
+        gifted_today,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetGiftLimits<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GiftBalance<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"game_state", sender.key().as_ref()],
+        bump = sender_game_state.bump,
+        constraint = sender_game_state.player == sender.key() @ GameError::Unauthorized,
+    )]
+    pub sender_game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"game_state", recipient.key().as_ref()],
+        bump = recipient_game_state.bump,
+        constraint = recipient_game_state.player == recipient.key() @ GameError::Unauthorized,
+    )]
+    pub recipient_game_state: Account<'info, GameState>,
+    /// CHECK: only used to derive the recipient's game state PDA
+    pub recipient: UncheckedAccount<'info>,
+    pub sender: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// Abuse limits on `gift_balance`. The default (zero cap) disables gifting.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GiftLimits {
+    /// Most credits a player can send per UTC day
+    pub daily_cap: u64,
+    /// Seconds since `GameState::created_at` before a player can send gifts
+    pub min_account_age: i64,
+}
+
+impl GiftLimits {
+    /// Check that a game started at `created_at` is old enough to move credits
+    pub fn require_account_age(&self, created_at: i64, now: i64) -> Result<()> {
+        require!(
+            now.saturating_sub(created_at) >= self.min_account_age,
+            GameError::AccountTooNew
+        );
+        Ok(())
+    }
+
+    /// Add `amount` to `sent_today`, the total sent on `day`, starting over
+    /// on a new UTC day. Returns today and the new total.
+    pub fn daily_total(
+        &self,
+        day: i64,
+        sent_today: u64,
+        amount: u64,
+        now: i64,
+    ) -> Result<(i64, u64)> {
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        let sent_today = if day == today { sent_today } else { 0 };
+        let total = sent_today
+            .checked_add(amount)
+            .ok_or(GameError::InvalidAmount)?;
+        require!(total <= self.daily_cap, GameError::GiftCapExceeded);
+        Ok((today, total))
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct GiftLimitsUpdated {
+    pub daily_cap: u64,
+    pub min_account_age: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BalanceGifted {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub sender_balance: u64,
+    pub recipient_balance: u64,
+    pub gifted_today: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,753 @@
+use anchor_lang::prelude::*;
+
+use crate::{instruction_flags, GameConfig, GameError, GameState, BPS_DENOMINATOR};
+
+/// Length of `Guild::name` in bytes (UTF-8, zero padded)
+pub const GUILD_NAME_LEN: usize = 32;
+
+/// Most members a guild can have, leader included
+pub const MAX_GUILD_MEMBERS: u32 = 50;
+
+/// Highest tithe a guild can take from member collections
+pub const MAX_GUILD_TITHE_BPS: u16 = 2_000;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Start or end the guild season (admin only). Guild and member scores
+/// restart the first time they are credited in a new season.
+pub(crate) fn set_guild_season(ctx: Context<SetGuildSeason>, season: GuildSeason) -> Result<()> {
+    require!(season.start < season.end, GameError::InvalidGuildSeason);
+    ctx.accounts.config.guild_season = season;
+
+    emit!(GuildSeasonUpdated {
+        season: season.season,
+        start: season.start,
+        end: season.end,
+    });
+
+    Ok(())
+}
+
+/// Found guild `guild_id` with the caller as its leader
+pub(crate) fn create_guild(
+    ctx: Context<CreateGuild>,
+    guild_id: u64,
+    name: [u8; GUILD_NAME_LEN],
+    tithe_bps: u16,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::JOIN_GUILD)?;
+    validate_name(&name)?;
+    require!(
+        tithe_bps <= MAX_GUILD_TITHE_BPS,
+        GameError::InvalidGuildTithe
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let player = ctx.accounts.player.key();
+    let guild = &mut ctx.accounts.guild;
+    guild.guild_id = guild_id;
+    guild.name = name;
+    guild.leader = player;
+    guild.member_count = 1;
+    guild.tithe_bps = tithe_bps;
+    guild.treasury = 0;
+    guild.season = 0;
+    guild.season_score = 0;
+    guild.total_score = 0;
+    guild.payout_day = 0;
+    guild.paid_out_today = 0;
+    guild.created_at = now;
+    guild.bump = ctx.bumps.guild;
+
+    ctx.accounts.membership.set_inner(GuildMember {
+        player,
+        guild: guild.key(),
+        role: GuildRole::Leader,
+        joined_at: now,
+        season: 0,
+        season_score: 0,
+        bump: ctx.bumps.membership,
+    });
+
+    emit!(GuildCreated {
+        guild: guild.key(),
+        guild_id,
+        leader: player,
+        name,
+        tithe_bps,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Join a guild as a plain member. A player belongs to one guild at a time.
+pub(crate) fn join_guild(ctx: Context<JoinGuild>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::JOIN_GUILD)?;
+
+    let guild = &mut ctx.accounts.guild;
+    require!(guild.member_count < MAX_GUILD_MEMBERS, GameError::GuildFull);
+    guild.member_count += 1;
+
+    let now = Clock::get()?.unix_timestamp;
+    let player = ctx.accounts.player.key();
+    ctx.accounts.membership.set_inner(GuildMember {
+        player,
+        guild: guild.key(),
+        role: GuildRole::Member,
+        joined_at: now,
+        season: 0,
+        season_score: 0,
+        bump: ctx.bumps.membership,
+    });
+
+    emit!(GuildJoined {
+        guild: guild.key(),
+        player,
+        member_count: guild.member_count,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Leave the guild and reclaim the membership rent. The leader can only
+/// leave as the last member, once the treasury is empty; the guild is
+/// closed with them.
+pub(crate) fn leave_guild(ctx: Context<LeaveGuild>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::MANAGE_GUILD)?;
+    let guild = &mut ctx.accounts.guild;
+    let player = ctx.accounts.player.key();
+    guild.member_count -= 1;
+
+    let closed = ctx.accounts.membership.role == GuildRole::Leader;
+    if closed {
+        require!(
+            guild.member_count == 0 && guild.treasury == 0,
+            GameError::GuildNotEmpty
+        );
+    }
+
+    emit!(GuildLeft {
+        guild: guild.key(),
+        player,
+        removed_by: player,
+        member_count: guild.member_count,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    if closed {
+        ctx.accounts
+            .guild
+            .close(ctx.accounts.player.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+/// Remove a member (officers and the leader). Officers can only remove
+/// plain members. The membership rent goes back to the removed player.
+pub(crate) fn kick_guild_member(ctx: Context<KickGuildMember>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::MANAGE_GUILD)?;
+    let officer = &ctx.accounts.officer_membership;
+    let target = &ctx.accounts.membership;
+    require!(officer.role.outranks(target.role), GameError::Unauthorized);
+
+    let guild = &mut ctx.accounts.guild;
+    guild.member_count -= 1;
+
+    emit!(GuildLeft {
+        guild: guild.key(),
+        player: target.player,
+        removed_by: officer.player,
+        member_count: guild.member_count,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Promote or demote a member (leader only). Making a member `Leader`
+/// hands over leadership; the old leader becomes an officer.
+pub(crate) fn set_guild_role(ctx: Context<SetGuildRole>, role: GuildRole) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::MANAGE_GUILD)?;
+    require!(
+        ctx.accounts.leader_membership.role == GuildRole::Leader,
+        GameError::Unauthorized
+    );
+    require_keys_neq!(
+        ctx.accounts.membership.player,
+        ctx.accounts.leader.key(),
+        GameError::Unauthorized
+    );
+
+    ctx.accounts.membership.role = role;
+    if role == GuildRole::Leader {
+        ctx.accounts.leader_membership.role = GuildRole::Officer;
+        ctx.accounts.guild.leader = ctx.accounts.membership.player;
+    }
+
+    emit!(GuildRoleChanged {
+        guild: ctx.accounts.guild.key(),
+        player: ctx.accounts.membership.player,
+        role,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Rename the guild (officers and the leader)
+pub(crate) fn rename_guild(ctx: Context<UpdateGuild>, name: [u8; GUILD_NAME_LEN]) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::MANAGE_GUILD)?;
+    require!(
+        ctx.accounts.officer_membership.role.is_officer(),
+        GameError::Unauthorized
+    );
+    validate_name(&name)?;
+
+    let guild = &mut ctx.accounts.guild;
+    guild.name = name;
+
+    emit!(GuildRenamed {
+        guild: guild.key(),
+        name,
+        renamed_by: ctx.accounts.officer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Change the tithe taken from member collections (leader only)
+pub(crate) fn set_guild_tithe(ctx: Context<UpdateGuild>, tithe_bps: u16) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::MANAGE_GUILD)?;
+    require!(
+        ctx.accounts.officer_membership.role == GuildRole::Leader,
+        GameError::Unauthorized
+    );
+    require!(
+        tithe_bps <= MAX_GUILD_TITHE_BPS,
+        GameError::InvalidGuildTithe
+    );
+
+    let guild = &mut ctx.accounts.guild;
+    guild.tithe_bps = tithe_bps;
+
+    emit!(GuildTitheUpdated {
+        guild: guild.key(),
+        tithe_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Pay treasury credits to a member's balance (leader only). Payouts are
+/// held to the gifting limits: the guild can pay out at most the daily gift
+/// cap per UTC day, and only to members whose game is old enough to gift.
+pub(crate) fn distribute_guild_treasury(
+    ctx: Context<DistributeGuildTreasury>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::DISTRIBUTE_GUILD_TREASURY)?;
+    require!(
+        ctx.accounts.leader_membership.role == GuildRole::Leader,
+        GameError::Unauthorized
+    );
+    require!(amount > 0, GameError::InvalidAmount);
+    let now = Clock::get()?.unix_timestamp;
+    let limits = ctx.accounts.config.gift_limits;
+    let recipient_state = &ctx.accounts.recipient_game_state;
+    recipient_state.require_active(now)?;
+    limits.require_account_age(recipient_state.created_at, now)?;
+
+    let guild = &mut ctx.accounts.guild;
+    guild.treasury = guild
+        .treasury
+        .checked_sub(amount)
+        .ok_or(GameError::InsufficientBalance)?;
+    let (payout_day, paid_out_today) =
+        limits.daily_total(guild.payout_day, guild.paid_out_today, amount, now)?;
+    guild.payout_day = payout_day;
+    guild.paid_out_today = paid_out_today;
+
+    // Like gifts, treasury payouts arrive as withdrawable credits
+    let recipient_state = &mut ctx.accounts.recipient_game_state;
+    recipient_state.balance = recipient_state.balance.saturating_add(amount);
+
+    emit!(GuildTreasuryDistributed {
+        guild: guild.key(),
+        recipient: recipient_state.player,
+        amount,
+        treasury: guild.treasury,
+        paid_out_today,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Credit a member's attested collection to their guild. Returns the tithe
+/// to take out of the player's reward; the score counts the full reward
+/// but only inside the current season.
+pub fn credit_guild_collection(
+    guild: &mut Guild,
+    guild_key: Pubkey,
+    member: &mut GuildMember,
+    player: Pubkey,
+    reward: u64,
+    season: &GuildSeason,
+    now: i64,
+) -> Result<u64> {
+    require_keys_eq!(member.guild, guild_key, GameError::InvalidGuildMember);
+    require_keys_eq!(member.player, player, GameError::InvalidGuildMember);
+
+    let tithe = guild.tithe(reward);
+    guild.treasury = guild.treasury.saturating_add(tithe);
+
+    if season.is_active(now) {
+        if guild.season != season.season {
+            guild.season = season.season;
+            guild.season_score = 0;
+        }
+        if member.season != season.season {
+            member.season = season.season;
+            member.season_score = 0;
+        }
+        guild.season_score = guild.season_score.saturating_add(reward);
+        guild.total_score = guild.total_score.saturating_add(reward);
+        member.season_score = member.season_score.saturating_add(reward);
+    }
+
+    emit!(GuildScoreUpdated {
+        guild: guild_key,
+        guild_id: guild.guild_id,
+        season: guild.season,
+        season_score: guild.season_score,
+        player,
+        member_season_score: member.season_score,
+        tithe,
+        treasury: guild.treasury,
+    });
+
+    Ok(tithe)
+}
+
+fn validate_name(name: &[u8; GUILD_NAME_LEN]) -> Result<()> {
+    let len = name.iter().position(|&b| b == 0).unwrap_or(GUILD_NAME_LEN);
+    require!(
+        len > 0 && name[len..].iter().all(|&b| b == 0) && std::str::from_utf8(&name[..len]).is_ok(),
+        GameError::InvalidGuildName
+    );
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetGuildSeason<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(guild_id: u64)]
+pub struct CreateGuild<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<Guild>(),
+        seeds = [b"guild", guild_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<GuildMember>(),
+        seeds = [b"guild_member", player.key().as_ref()],
+        bump,
+    )]
+    pub membership: Account<'info, GuildMember>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinGuild<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"guild", guild.guild_id.to_le_bytes().as_ref()],
+        bump = guild.bump,
+    )]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<GuildMember>(),
+        seeds = [b"guild_member", player.key().as_ref()],
+        bump,
+    )]
+    pub membership: Account<'info, GuildMember>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveGuild<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"guild", guild.guild_id.to_le_bytes().as_ref()],
+        bump = guild.bump,
+    )]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"guild_member", player.key().as_ref()],
+        bump = membership.bump,
+        has_one = guild @ GameError::InvalidGuildMember,
+        has_one = player,
+    )]
+    pub membership: Account<'info, GuildMember>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct KickGuildMember<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"guild", guild.guild_id.to_le_bytes().as_ref()],
+        bump = guild.bump,
+    )]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        seeds = [b"guild_member", officer.key().as_ref()],
+        bump = officer_membership.bump,
+        has_one = guild @ GameError::Unauthorized,
+    )]
+    pub officer_membership: Account<'info, GuildMember>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"guild_member", player.key().as_ref()],
+        bump = membership.bump,
+        has_one = guild @ GameError::InvalidGuildMember,
+        has_one = player,
+    )]
+    pub membership: Account<'info, GuildMember>,
+    /// CHECK: the removed player, refunded the membership rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+    pub officer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuildRole<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"guild", guild.guild_id.to_le_bytes().as_ref()],
+        bump = guild.bump,
+    )]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        mut,
+        seeds = [b"guild_member", leader.key().as_ref()],
+        bump = leader_membership.bump,
+        has_one = guild @ GameError::Unauthorized,
+    )]
+    pub leader_membership: Account<'info, GuildMember>,
+    #[account(
+        mut,
+        seeds = [b"guild_member", membership.player.as_ref()],
+        bump = membership.bump,
+        has_one = guild @ GameError::InvalidGuildMember,
+    )]
+    pub membership: Account<'info, GuildMember>,
+    pub leader: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGuild<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"guild", guild.guild_id.to_le_bytes().as_ref()],
+        bump = guild.bump,
+    )]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        seeds = [b"guild_member", officer.key().as_ref()],
+        bump = officer_membership.bump,
+        has_one = guild @ GameError::Unauthorized,
+    )]
+    pub officer_membership: Account<'info, GuildMember>,
+    pub officer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeGuildTreasury<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"guild", guild.guild_id.to_le_bytes().as_ref()],
+        bump = guild.bump,
+    )]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        seeds = [b"guild_member", leader.key().as_ref()],
+        bump = leader_membership.bump,
+        has_one = guild @ GameError::Unauthorized,
+    )]
+    pub leader_membership: Account<'info, GuildMember>,
+    #[account(
+        seeds = [b"guild_member", recipient_membership.player.as_ref()],
+        bump = recipient_membership.bump,
+        has_one = guild @ GameError::InvalidGuildMember,
+    )]
+    pub recipient_membership: Account<'info, GuildMember>,
+    #[account(
+        mut,
+        seeds = [b"game_state", recipient_membership.player.as_ref()],
+        bump = recipient_game_state.bump,
+    )]
+    pub recipient_game_state: Account<'info, GameState>,
+    pub leader: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// The season guild scores are counted in. Collections outside
+/// `start..end` still pay the tithe but don't score.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GuildSeason {
+    pub season: u32,
+    pub start: i64,
+    pub end: i64,
+}
+
+impl GuildSeason {
+    pub fn is_active(&self, now: i64) -> bool {
+        self.start <= now && now < self.end
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GuildRole {
+    #[default]
+    Member,
+    /// Can rename the guild and remove plain members
+    Officer,
+    /// Everything an officer can do, plus roles, tithe and treasury payouts
+    Leader,
+}
+
+impl GuildRole {
+    pub fn is_officer(&self) -> bool {
+        matches!(self, GuildRole::Officer | GuildRole::Leader)
+    }
+
+    /// Whether this role may remove a member holding `other`
+    pub fn outranks(&self, other: GuildRole) -> bool {
+        match self {
+            GuildRole::Leader => other != GuildRole::Leader,
+            GuildRole::Officer => other == GuildRole::Member,
+            GuildRole::Member => false,
+        }
+    }
+}
+
+#[account]
+pub struct Guild {
+    pub guild_id: u64,
+    /// UTF-8, zero padded
+    pub name: [u8; GUILD_NAME_LEN],
+    pub leader: Pubkey,
+    pub member_count: u32,
+    /// Share of member collections paid into `treasury`
+    pub tithe_bps: u16,
+    /// Credits collected from tithes, paid out by the leader
+    pub treasury: u64,
+    /// Season `season_score` was counted in
+    pub season: u32,
+    pub season_score: u64,
+    /// Score across all seasons
+    pub total_score: u64,
+    /// Day (unix time / 86400) that `paid_out_today` refers to
+    pub payout_day: i64,
+    /// Treasury credits paid out on `payout_day`
+    pub paid_out_today: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Guild {
+    pub fn tithe(&self, reward: u64) -> u64 {
+        (reward as u128 * self.tithe_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+/// A player's membership. Seeded by the player alone, so a player can only
+/// be in one guild at a time.
+#[account]
+pub struct GuildMember {
+    pub player: Pubkey,
+    pub guild: Pubkey,
+    pub role: GuildRole,
+    pub joined_at: i64,
+    /// Season `season_score` was counted in
+    pub season: u32,
+    /// The member's contribution to the guild's season score
+    pub season_score: u64,
+    pub bump: u8,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct GuildSeasonUpdated {
+    pub season: u32,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[event]
+pub struct GuildCreated {
+    pub guild: Pubkey,
+    pub guild_id: u64,
+    pub leader: Pubkey,
+    pub name: [u8; GUILD_NAME_LEN],
+    pub tithe_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuildJoined {
+    pub guild: Pubkey,
+    pub player: Pubkey,
+    pub member_count: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuildLeft {
+    pub guild: Pubkey,
+    pub player: Pubkey,
+    /// The player themselves, or the officer who removed them
+    pub removed_by: Pubkey,
+    pub member_count: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuildRoleChanged {
+    pub guild: Pubkey,
+    pub player: Pubkey,
+    pub role: GuildRole,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuildRenamed {
+    pub guild: Pubkey,
+    pub name: [u8; GUILD_NAME_LEN],
+    pub renamed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuildTitheUpdated {
+    pub guild: Pubkey,
+    pub tithe_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuildTreasuryDistributed {
+    pub guild: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub treasury: u64,
+    pub paid_out_today: u64,
+    pub timestamp: i64,
+}
+
+/// Leaderboard update, emitted for every collection credited to a guild
+#[event]
+pub struct GuildScoreUpdated {
+    pub guild: Pubkey,
+    pub guild_id: u64,
+    pub season: u32,
+    pub season_score: u64,
+    pub player: Pubkey,
+    pub member_season_score: u64,
+    pub tithe: u64,
+    pub treasury: u64,
+}
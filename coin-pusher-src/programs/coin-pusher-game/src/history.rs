@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::{instruction_flags, GameConfig};
+
+/// Plays kept per player; older ones are overwritten
+pub const PLAY_HISTORY_LEN: usize = 64;
+
+/// `PlayEntry::action` values
+pub const PLAY_ACTION_DROP: u8 = 0;
+pub const PLAY_ACTION_BUMP: u8 = 1;
+pub const PLAY_ACTION_COLLECT: u8 = 2;
+pub const PLAY_ACTION_TRASHCOIN: u8 = 3;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Create the player's play history. Plays are only recorded once it exists
+/// and is passed to the gameplay instructions.
+pub(crate) fn initialize_play_history(ctx: Context<InitializePlayHistory>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::PLAY_HISTORY)?;
+    let mut history = ctx.accounts.play_history.load_init()?;
+    history.player = ctx.accounts.player.key();
+    history.bump = ctx.bumps.play_history;
+
+    Ok(())
+}
+
+/// Append a play to the player's history, if it was passed in
+pub fn record_play(
+    play_history: Option<&AccountLoader<PlayHistory>>,
+    action: u8,
+    amount: u64,
+    balance: u64,
+) -> Result<()> {
+    if let Some(play_history) = play_history {
+        play_history.load_mut()?.record(PlayEntry {
+            slot: Clock::get()?.slot,
+            amount,
+            balance,
+            action,
+            _padding: [0; 7],
+        });
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializePlayHistory<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<PlayHistory>(),
+        seeds = [b"play_history", player.key().as_ref()],
+        bump,
+    )]
+    pub play_history: AccountLoader<'info, PlayHistory>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// One play. `amount` is in credits, except for TRASHCOIN awards where it
+/// is the TRASHCOIN amount; `balance` is the credit balance afterwards.
+#[zero_copy]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PlayEntry {
+    pub slot: u64,
+    pub amount: u64,
+    pub balance: u64,
+    /// One of the `PLAY_ACTION_*` values
+    pub action: u8,
+    pub _padding: [u8; 7],
+}
+
+/// The player's last `PLAY_HISTORY_LEN` plays in a ring buffer, zero-copy
+/// so recording a play doesn't deserialise the whole account
+#[account(zero_copy)]
+pub struct PlayHistory {
+    pub player: Pubkey,
+    /// Plays recorded so far; the next one goes at `total_plays % PLAY_HISTORY_LEN`
+    pub total_plays: u64,
+    pub entries: [PlayEntry; PLAY_HISTORY_LEN],
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl PlayHistory {
+    pub fn record(&mut self, entry: PlayEntry) {
+        let index = (self.total_plays % PLAY_HISTORY_LEN as u64) as usize;
+        self.entries[index] = entry;
+        self.total_plays = self.total_plays.saturating_add(1);
+    }
+
+    /// Recorded plays, newest first
+    pub fn recent(&self) -> impl Iterator<Item = &PlayEntry> {
+        let len = self.total_plays.min(PLAY_HISTORY_LEN as u64) as usize;
+        let next = (self.total_plays % PLAY_HISTORY_LEN as u64) as usize;
+        (1..=len)
+            .map(move |back| &self.entries[(next + PLAY_HISTORY_LEN - back) % PLAY_HISTORY_LEN])
+    }
+}
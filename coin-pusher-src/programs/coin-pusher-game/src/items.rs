@@ -0,0 +1,348 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{
+    instruction_flags, GameConfig, GameError, GameState, BPS_DENOMINATOR, MAX_REDEMPTION_OPTIONS,
+};
+
+/// Number of entries in `GameConfig::item_catalogue`
+pub const MAX_ITEMS: usize = 8;
+
+/// Number of effects a player can have running at once
+pub const MAX_ACTIVE_EFFECTS: usize = 4;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the power-up catalogue (admin only)
+pub(crate) fn set_item_catalogue(
+    ctx: Context<SetItemCatalogue>,
+    items: [ItemDef; MAX_ITEMS],
+) -> Result<()> {
+    for item in items.iter() {
+        item.validate()?;
+    }
+    ctx.accounts.config.item_catalogue = items;
+
+    emit!(ItemCatalogueUpdated {
+        items,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Buy `quantity` of catalogue item `item_id`, paid into the house vault
+pub(crate) fn buy_item(
+    ctx: Context<BuyItem>,
+    item_id: u8,
+    quantity: u16,
+    payment_token: PaymentToken,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    config.require_enabled(instruction_flags::BUY_ITEM)?;
+    require!(quantity > 0, GameError::InvalidAmount);
+    ctx.accounts.game_state.require_active(Clock::get()?.unix_timestamp)?;
+
+    let item = config.item(item_id)?;
+    let (mint, unit_price) = match payment_token {
+        PaymentToken::Junk => (config.junk_mint, item.price_junk),
+        PaymentToken::Trashcoin => (config.trashcoin_mint, item.price_trashcoin),
+    };
+    require!(unit_price > 0, GameError::ItemNotForSale);
+    require_keys_eq!(ctx.accounts.player_token_account.mint, mint, GameError::InvalidMint);
+    let price = unit_price
+        .checked_mul(quantity as u64)
+        .ok_or(GameError::InvalidAmount)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.player_token_account.to_account_info(),
+        to: ctx.accounts.vault_token_account.to_account_info(),
+        authority: ctx.accounts.player.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), price)?;
+
+    let inventory = &mut ctx.accounts.inventory;
+    if inventory.player == Pubkey::default() {
+        inventory.player = ctx.accounts.player.key();
+        inventory.bump = ctx.bumps.inventory;
+    }
+    let owned = inventory.items[item_id as usize]
+        .checked_add(quantity)
+        .ok_or(GameError::InvalidAmount)?;
+    inventory.items[item_id as usize] = owned;
+
+    emit!(ItemPurchased {
+        player: inventory.player,
+        item_id,
+        quantity,
+        payment_token,
+        price,
+        owned,
+    });
+
+    Ok(())
+}
+
+/// Consume one of `item_id` and start its effect
+pub(crate) fn use_item(ctx: Context<UseItem>, item_id: u8) -> Result<()> {
+    ctx.accounts.config.require_enabled(instruction_flags::USE_ITEM)?;
+
+    let item = ctx.accounts.config.item(item_id)?;
+    let clock = Clock::get()?;
+    ctx.accounts.game_state.require_active(clock.unix_timestamp)?;
+    let slot = clock.slot;
+
+    let inventory = &mut ctx.accounts.inventory;
+    let count = &mut inventory.items[item_id as usize];
+    require!(*count > 0, GameError::ItemNotOwned);
+    *count -= 1;
+
+    let expires_slot = inventory.activate(item_id, &item, slot)?;
+
+    emit!(ItemUsed {
+        player: inventory.player,
+        item_id,
+        kind: item.kind,
+        expires_slot,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetItemCatalogue<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BuyItem<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + std::mem::size_of::<Inventory>(),
+        seeds = [b"inventory", player.key().as_ref()],
+        bump,
+    )]
+    pub inventory: Box<Account<'info, Inventory>>,
+    #[account(mut)]
+    pub player_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = player_token_account.mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UseItem<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump = inventory.bump,
+        has_one = player,
+    )]
+    pub inventory: Box<Account<'info, Inventory>>,
+    pub player: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ItemKind {
+    /// Empty catalogue slot
+    #[default]
+    None,
+    /// Side walls stop coins falling off the edges (applied by the game engine)
+    SideWalls,
+    /// Multiplies credited collections by `multiplier_bps`
+    CollectMultiplier,
+    /// The next credited trashcoin collection consumes this effect
+    TrashcoinDrop,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PaymentToken {
+    #[default]
+    Junk,
+    Trashcoin,
+}
+
+/// One power-up in the config's catalogue. A zero price means the item
+/// can't be bought with that token.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemDef {
+    pub kind: ItemKind,
+    pub price_junk: u64,
+    pub price_trashcoin: u64,
+    /// How long the effect lasts once used (~400ms per slot)
+    pub duration_slots: u64,
+    /// Collect multiplier for `CollectMultiplier`; 10_000 = 1x
+    pub multiplier_bps: u16,
+}
+
+impl ItemDef {
+    pub fn validate(&self) -> Result<()> {
+        if self.kind == ItemKind::None {
+            return Ok(());
+        }
+        require!(self.duration_slots > 0, GameError::InvalidItem);
+        if self.kind == ItemKind::CollectMultiplier {
+            require!(
+                self.multiplier_bps as u64 >= BPS_DENOMINATOR,
+                GameError::InvalidItem
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ActiveEffect {
+    pub item_id: u8,
+    pub kind: ItemKind,
+    pub multiplier_bps: u16,
+    pub expires_slot: u64,
+}
+
+impl ActiveEffect {
+    pub fn is_active(&self, slot: u64) -> bool {
+        self.kind != ItemKind::None && slot < self.expires_slot
+    }
+}
+
+#[account]
+pub struct Inventory {
+    pub player: Pubkey,
+    /// Owned count per catalogue item
+    pub items: [u16; MAX_ITEMS],
+    pub active_effects: [ActiveEffect; MAX_ACTIVE_EFFECTS],
+    /// Coin skins redeemed, one bit per skin id
+    pub skins: u64,
+    /// Raffle entries redeemed and not yet used
+    pub raffle_entries: u32,
+    /// Units redeemed per redemption shop slot
+    pub redeemed: [u32; MAX_REDEMPTION_OPTIONS],
+    pub bump: u8,
+}
+
+impl Inventory {
+    pub fn owns_skin(&self, skin_id: u8) -> bool {
+        skin_id < u64::BITS as u8 && self.skins & (1 << skin_id) != 0
+    }
+
+    /// Start (or extend) the effect of `item` and return its expiry slot
+    pub fn activate(&mut self, item_id: u8, item: &ItemDef, slot: u64) -> Result<u64> {
+        let index = self
+            .active_effects
+            .iter()
+            .position(|effect| effect.item_id == item_id && effect.is_active(slot))
+            .or_else(|| self.active_effects.iter().position(|effect| !effect.is_active(slot)))
+            .ok_or(GameError::TooManyActiveEffects)?;
+
+        let effect = &mut self.active_effects[index];
+        let start = if effect.is_active(slot) { effect.expires_slot } else { slot };
+        *effect = ActiveEffect {
+            item_id,
+            kind: item.kind,
+            multiplier_bps: item.multiplier_bps,
+            expires_slot: start.saturating_add(item.duration_slots),
+        };
+        Ok(effect.expires_slot)
+    }
+
+    /// Apply active effects to a credited collection and return the new reward
+    pub fn apply_to_collection(&mut self, reward: u64, is_trashcoin: bool, slot: u64) -> u64 {
+        let mut reward = reward;
+        for effect in self.active_effects.iter_mut() {
+            if !effect.is_active(slot) {
+                continue;
+            }
+            match effect.kind {
+                ItemKind::CollectMultiplier => {
+                    reward = (reward as u128 * effect.multiplier_bps as u128
+                        / BPS_DENOMINATOR as u128) as u64;
+                }
+                ItemKind::TrashcoinDrop if is_trashcoin => {
+                    *effect = ActiveEffect::default();
+                }
+                _ => {}
+            }
+        }
+        reward
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct ItemCatalogueUpdated {
+    pub items: [ItemDef; MAX_ITEMS],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ItemPurchased {
+    pub player: Pubkey,
+    pub item_id: u8,
+    pub quantity: u16,
+    pub payment_token: PaymentToken,
+    pub price: u64,
+    pub owned: u16,
+}
+
+#[event]
+pub struct ItemUsed {
+    pub player: Pubkey,
+    pub item_id: u8,
+    pub kind: ItemKind,
+    pub expires_slot: u64,
+}
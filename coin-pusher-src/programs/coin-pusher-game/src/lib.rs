@@ -0,0 +1,2103 @@
+// The `cpi` feature generates a wrapper per instruction with the same
+// argument list, which clippy flags for `create_tournament`.
+#![allow(clippy::too_many_arguments)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+
+pub mod bump_curve;
+pub mod cashout;
+pub mod challenge;
+pub mod coins;
+pub mod distribution;
+pub mod emission;
+pub mod gifting;
+pub mod guild;
+pub mod history;
+pub mod items;
+pub mod moderation;
+pub mod raffle;
+pub mod redemption;
+pub mod reward;
+pub mod schedule;
+pub mod snapshot;
+pub mod sponsor;
+pub mod staking;
+pub mod stats;
+pub mod tournament;
+
+pub use bump_curve::*;
+pub use cashout::*;
+pub use challenge::*;
+pub use coins::*;
+pub use distribution::*;
+pub use emission::*;
+pub use gifting::*;
+pub use guild::*;
+pub use history::*;
+pub use items::*;
+pub use moderation::*;
+pub use raffle::*;
+pub use redemption::*;
+pub use reward::*;
+pub use schedule::*;
+pub use snapshot::*;
+pub use sponsor::*;
+pub use staking::*;
+pub use stats::*;
+pub use tournament::*;
+
+#[cfg(not(feature = "localnet"))]
+declare_id!("11111111111111111111111111111111");
+// The placeholder above is the system program's address, which no runtime
+// can load a second program at, so the test suites build with this one
+#[cfg(feature = "localnet")]
+declare_id!("7EjDcMd3nvP5nfat334qAgBYX2rPss1vHy5Rbmrf14aV");
+
+// JUNK Token: BgvprjyRDq1erzQocRTmLPBzMuEmcARg64LE9eGX9XRF
+// TRASHCOIN Token: GNFqCqaU9R2jas4iaKEFZM5hiX5AHxBL7rPHTCpX5T6z
+
+/// Basis-point denominator for `FeeSplit`
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Bits of `GameConfig::disabled_instructions`. A set bit turns the
+/// instruction off independently of the global `paused` flag.
+pub mod instruction_flags {
+    pub const INITIALIZE_GAME: u64 = 1 << 0;
+    pub const DROP_COIN: u64 = 1 << 1;
+    pub const COLLECT_COIN: u64 = 1 << 2;
+    pub const BUMP_MACHINE: u64 = 1 << 3;
+    pub const AWARD_TRASHCOIN: u64 = 1 << 4;
+    pub const RESET_GAME: u64 = 1 << 5;
+    pub const STAKE: u64 = 1 << 6;
+    pub const EXTEND_LOCK: u64 = 1 << 7;
+    pub const ENTER_TOURNAMENT: u64 = 1 << 8;
+    pub const BUY_ITEM: u64 = 1 << 9;
+    pub const USE_ITEM: u64 = 1 << 10;
+    pub const GIFT_BALANCE: u64 = 1 << 11;
+    pub const SAVE_SNAPSHOT: u64 = 1 << 12;
+    pub const REDEEM: u64 = 1 << 13;
+    pub const JOIN_GUILD: u64 = 1 << 14;
+    pub const CHALLENGE: u64 = 1 << 15;
+    pub const RAFFLE: u64 = 1 << 16;
+    // 1 << 17 was CLOSE_GAME; closing a game is an exit path and stays open
+    pub const AWARD_TOKEN: u64 = 1 << 18;
+    pub const CLAIM_DISTRIBUTION: u64 = 1 << 19;
+    pub const PLAY_HISTORY: u64 = 1 << 20;
+    pub const STATS_SNAPSHOT: u64 = 1 << 21;
+    pub const SPONSOR_BUDGET: u64 = 1 << 22;
+    pub const MANAGE_GUILD: u64 = 1 << 23;
+    pub const DISTRIBUTE_GUILD_TREASURY: u64 = 1 << 24;
+}
+
+#[program]
+pub mod coin_pusher_game {
+    use super::*;
+
+    /// Create the global config (program upgrade authority only)
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        pause_authority: Pubkey,
+    ) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
+        require_keys_neq!(pause_authority, admin, GameError::PauseAuthorityNotDistinct);
+
+        let config = &mut ctx.accounts.config;
+        config.admin = admin;
+        config.pause_authority = pause_authority;
+        config.game_authority = admin;
+        config.moderator = admin;
+        config.paused = false;
+        config.disabled_instructions = 0;
+        config.junk_mint = ctx.accounts.junk_mint.key();
+        config.trashcoin_mint = ctx.accounts.trashcoin_mint.key();
+        config.fee_split = FeeSplit::default();
+        config.jackpot_junk_account = Pubkey::default();
+        config.treasury_junk_account = Pubkey::default();
+        config.stake_tiers = [StakeTier::default(); MAX_STAKE_TIERS];
+        config.item_catalogue = [ItemDef::default(); MAX_ITEMS];
+        config.coin_types = CoinType::default_table();
+        config.gift_limits = GiftLimits::default();
+        config.cash_out_limits = CashOutLimits::default();
+        config.cash_out_window = CashOutWindow::default();
+        config.emission_schedule = EmissionSchedule::default();
+        config.trashcoin_emission = TrashcoinEmission::default();
+        config.redemption_options = [RedemptionOption::default(); MAX_REDEMPTION_OPTIONS];
+        config.guild_season = GuildSeason::default();
+        config.challenge_rules = ChallengeRules::default();
+        config.raffle_rules = RaffleRules::default();
+        config.bump_curve = BumpCurve::default();
+        config.bump = ctx.bumps.config;
+
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_burned = 0;
+        global_stats.total_staked = 0;
+        global_stats.total_players = 0;
+        global_stats.total_drops = 0;
+        global_stats.total_bumps = 0;
+        global_stats.total_coins_collected = 0;
+        global_stats.total_trashcoin_awarded = 0;
+        global_stats.total_junk_wagered = 0;
+        global_stats.total_junk_paid_out = 0;
+        global_stats.bump = ctx.bumps.global_stats;
+
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.windows = [ScheduleWindow::default(); MAX_SCHEDULE_WINDOWS];
+        schedule.bump = ctx.bumps.schedule;
+
+        emit!(ConfigInitialized {
+            admin,
+            pause_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pause/unpause the program and set per-instruction kill flags
+    pub fn set_pause(
+        ctx: Context<SetPause>,
+        paused: bool,
+        disabled_instructions: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+        config.disabled_instructions = disabled_instructions;
+
+        emit!(PauseUpdated {
+            authority: ctx.accounts.pause_authority.key(),
+            paused,
+            disabled_instructions,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set how JUNK payments are split between burn, vault, jackpot and treasury (admin only)
+    pub fn set_fee_split(
+        ctx: Context<SetFeeSplit>,
+        fee_split: FeeSplit,
+    ) -> Result<()> {
+        fee_split.validate()?;
+
+        let config = &mut ctx.accounts.config;
+        config.fee_split = fee_split;
+        config.jackpot_junk_account = ctx.accounts.jackpot_junk_account.key();
+        config.treasury_junk_account = ctx.accounts.treasury_junk_account.key();
+
+        emit!(FeeSplitUpdated {
+            burn_bps: fee_split.burn_bps,
+            vault_bps: fee_split.vault_bps,
+            jackpot_bps: fee_split.jackpot_bps,
+            treasury_bps: fee_split.treasury_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the backend key that attests collections (admin only)
+    pub fn set_game_authority(
+        ctx: Context<SetGameAuthority>,
+        new_game_authority: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_game_authority = config.game_authority;
+        config.game_authority = new_game_authority;
+
+        emit!(GameAuthorityUpdated {
+            old_game_authority,
+            new_game_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Rotate the pause authority (admin only)
+    pub fn set_pause_authority(
+        ctx: Context<SetPauseAuthority>,
+        new_pause_authority: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require_keys_neq!(new_pause_authority, config.admin, GameError::PauseAuthorityNotDistinct);
+
+        let old_pause_authority = config.pause_authority;
+        config.pause_authority = new_pause_authority;
+
+        emit!(PauseAuthorityUpdated {
+            old_pause_authority,
+            new_pause_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Initialize a new game session for a player
+    pub fn initialize_game(
+        ctx: Context<InitializeGame>,
+        initial_balance: u64,
+    ) -> Result<()> {
+        ctx.accounts.config.require_enabled(instruction_flags::INITIALIZE_GAME)?;
+
+        let game_state = &mut ctx.accounts.game_state;
+        
+        ctx.accounts.global_stats.record_player();
+        game_state.start(
+            ctx.accounts.player.key(),
+            initial_balance,
+            ctx.bumps.game_state,
+            Clock::get()?.unix_timestamp,
+        );
+
+        emit!(GameInitialized {
+            player: ctx.accounts.player.key(),
+            initial_balance,
+            timestamp: game_state.created_at,
+        });
+
+        Ok(())
+    }
+
+    /// Record a coin drop (costs 1 JUNK)
+    pub fn drop_coin(
+        ctx: Context<DropCoin>,
+    ) -> Result<()> {
+        ctx.accounts.config.require_enabled(instruction_flags::DROP_COIN)?;
+        ctx.accounts.game_state.require_active(Clock::get()?.unix_timestamp)?;
+
+        // 1 JUNK, less any happy-hour discount
+        let mut amount = 1_000_000u64; // 1 JUNK (6 decimals)
+        let mut cost = 1u64;
+        let mut discount_bps = 0;
+        if let Some(window) = ctx.accounts.schedule.active_window(Clock::get()?.unix_timestamp) {
+            amount = window.apply_drop_discount(amount);
+            cost = window.apply_drop_discount(cost);
+            discount_bps = window.drop_discount_bps;
+        }
+
+        // Split the payment between burn, vault, jackpot and treasury
+        let split = ctx.accounts.config.fee_split.split(amount);
+
+        distribute_junk_payment(
+            &ctx.accounts.token_program,
+            &ctx.accounts.player,
+            &ctx.accounts.player_junk_account,
+            &ctx.accounts.junk_mint,
+            &ctx.accounts.vault_junk_account,
+            ctx.accounts.jackpot_junk_account.as_deref(),
+            ctx.accounts.treasury_junk_account.as_deref(),
+            &split,
+        )?;
+        record_burn(&mut ctx.accounts.global_stats, ctx.accounts.player.key(), split.burn)?;
+        ctx.accounts.global_stats.record_drop(amount);
+
+        if let Some(raffle) = ctx.accounts.raffle.as_mut() {
+            let raffle_key = raffle.key();
+            let raffle_vault = ctx.accounts.raffle_vault.as_ref().ok_or(GameError::InvalidRaffle)?;
+            require_keys_eq!(raffle_vault.owner, raffle_key, GameError::InvalidRaffle);
+            let entry = match (
+                ctx.accounts.raffle_index.as_mut(),
+                ctx.accounts.raffle_tickets.as_mut(),
+            ) {
+                (Some(index), Some(tickets)) => Some((&mut ***index, &mut ***tickets)),
+                _ => None,
+            };
+            let pool_share = credit_raffle_drop(
+                raffle,
+                raffle_key,
+                entry,
+                split.vault,
+                Clock::get()?.unix_timestamp,
+            )?;
+            if pool_share > 0 {
+                fund_raffle_pool(
+                    &ctx.accounts.vault_authority,
+                    ctx.bumps.vault_authority,
+                    &ctx.accounts.vault_junk_account,
+                    raffle_vault,
+                    &ctx.accounts.token_program,
+                    pool_share,
+                )?;
+            }
+        }
+
+        let game_state = &mut ctx.accounts.game_state;
+
+        // Update game state
+        game_state.spend(cost);
+        game_state.net_profit -= cost as i64;
+        game_state.last_updated = Clock::get()?.unix_timestamp;
+
+        record_play(
+            ctx.accounts.play_history.as_ref(),
+            PLAY_ACTION_DROP,
+            cost,
+            game_state.balance,
+        )?;
+
+        emit!(CoinDropped {
+            player: ctx.accounts.player.key(),
+            cost,
+            discount_bps,
+            new_balance: game_state.balance,
+            timestamp: game_state.last_updated,
+        });
+
+        Ok(())
+    }
+
+    /// Record a coin collection (player wins), attested by the game authority
+    pub fn collect_coin(
+        ctx: Context<CollectCoin>,
+        coin_type: u8,
+    ) -> Result<()> {
+        ctx.accounts.config.require_enabled(instruction_flags::COLLECT_COIN)?;
+
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        ctx.accounts.game_state.require_active(now)?;
+
+        let coin = ctx.accounts.config.coin_type(coin_type)?;
+        let is_trashcoin = coin_type == COIN_TYPE_TRASHCOIN;
+        let mut reward = coin.reward;
+        if let Some(tier) = ctx.accounts.stake_position.as_ref()
+            .and_then(|position| position.active_tier(&ctx.accounts.config, now))
+        {
+            reward = tier.apply_reward_multiplier(reward);
+        }
+
+        let mut multiplier_bps = BPS_DENOMINATOR as u16;
+        if let Some(window) = ctx.accounts.schedule.active_window(now) {
+            reward = window.apply_reward_multiplier(reward);
+            multiplier_bps = window.reward_multiplier_bps;
+        }
+
+        if let Some(inventory) = ctx.accounts.inventory.as_mut() {
+            reward = inventory.apply_to_collection(reward, is_trashcoin, clock.slot);
+        }
+
+        // The guild scores the full reward and takes its tithe out of it
+        let mut tithe = 0;
+        if let (Some(guild), Some(membership)) = (
+            ctx.accounts.guild.as_mut(),
+            ctx.accounts.guild_membership.as_mut(),
+        ) {
+            let guild_key = guild.key();
+            tithe = credit_guild_collection(
+                guild,
+                guild_key,
+                membership,
+                ctx.accounts.player.key(),
+                reward,
+                &ctx.accounts.config.guild_season,
+                now,
+            )?;
+        }
+        let credited = reward - tithe;
+
+        let game_state = &mut ctx.accounts.game_state;
+        record_coin_collection(game_state, coin_type, &coin, clock.epoch)?;
+
+        // Update game state
+        game_state.score = game_state.score.saturating_add(reward);
+        game_state.balance = game_state.balance.saturating_add(credited);
+        game_state.net_profit += credited as i64;
+        game_state.total_coins_collected = game_state.total_coins_collected.saturating_add(1);
+        
+        if is_trashcoin {
+            game_state.trashcoins_collected = game_state.trashcoins_collected.saturating_add(1);
+        }
+        
+        game_state.last_updated = now;
+
+        if coin.payout_amount > 0 {
+            pay_coin_payout(
+                &coin,
+                &ctx.accounts.player.key(),
+                ctx.accounts.vault_authority.as_ref(),
+                ctx.bumps.vault_authority,
+                ctx.accounts.vault_payout_account.as_deref(),
+                ctx.accounts.player_payout_account.as_deref(),
+                ctx.accounts.token_program.as_ref(),
+            )?;
+            if coin.payout_mint == ctx.accounts.config.junk_mint {
+                ctx.accounts.global_stats.record_junk_paid_out(coin.payout_amount);
+            }
+        }
+        ctx.accounts.global_stats.record_collection();
+
+        if let (Some(tournament), Some(entry)) = (
+            ctx.accounts.tournament.as_mut(),
+            ctx.accounts.tournament_entry.as_mut(),
+        ) {
+            let tournament_key = tournament.key();
+            credit_tournament_score(
+                tournament,
+                tournament_key,
+                entry,
+                ctx.accounts.player.key(),
+                reward,
+                now,
+            )?;
+        }
+
+        if let Some(challenge) = ctx.accounts.challenge.as_mut() {
+            let challenge_key = challenge.key();
+            credit_challenge_score(
+                challenge,
+                challenge_key,
+                ctx.accounts.player.key(),
+                reward,
+                now,
+            )?;
+        }
+
+        record_play(
+            ctx.accounts.play_history.as_ref(),
+            PLAY_ACTION_COLLECT,
+            reward,
+            game_state.balance,
+        )?;
+
+        emit!(CoinCollected {
+            player: ctx.accounts.player.key(),
+            amount: reward,
+            coin_type,
+            multiplier_bps,
+            new_balance: game_state.balance,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Bump the machine at `strength`, priced by the config's bump curve
+    pub fn bump_machine(
+        ctx: Context<BumpMachine>,
+        strength: u8,
+    ) -> Result<()> {
+        ctx.accounts.config.require_enabled(instruction_flags::BUMP_MACHINE)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.game_state.require_active(now)?;
+
+        // Check for rate limiting (prevent rapid-fire bump spam)
+        require!(
+            now.saturating_sub(ctx.accounts.game_state.last_updated) >= 1,
+            GameError::TooManyRequests
+        );
+
+        // Curve cost for the strength, escalated by earlier bumps in the
+        // window, less any staking discount
+        let curve = ctx.accounts.config.bump_curve;
+        let earlier_bumps = ctx.accounts.game_state
+            .bump_window
+            .record(curve.escalation_window, now);
+        let mut cost = curve.cost(strength, earlier_bumps)?;
+        let mut amount = cost
+            .checked_mul(JUNK_PER_CREDIT)
+            .ok_or(GameError::InvalidAmount)?;
+        if let Some(tier) = ctx.accounts.stake_position.as_ref()
+            .and_then(|position| position.active_tier(&ctx.accounts.config, now))
+        {
+            amount = tier.apply_bump_discount(amount);
+            cost = tier.apply_bump_discount(cost);
+        }
+
+        // Split the payment between burn, vault, jackpot and treasury
+        let split = ctx.accounts.config.fee_split.split(amount);
+
+        distribute_junk_payment(
+            &ctx.accounts.token_program,
+            &ctx.accounts.player,
+            &ctx.accounts.player_junk_account,
+            &ctx.accounts.junk_mint,
+            &ctx.accounts.vault_junk_account,
+            ctx.accounts.jackpot_junk_account.as_deref(),
+            ctx.accounts.treasury_junk_account.as_deref(),
+            &split,
+        )?;
+        record_burn(&mut ctx.accounts.global_stats, ctx.accounts.player.key(), split.burn)?;
+        ctx.accounts.global_stats.record_bump(amount);
+
+        let game_state = &mut ctx.accounts.game_state;
+
+        // Update game state
+        game_state.spend(cost);
+        game_state.net_profit -= cost as i64;
+        game_state.last_updated = now;
+
+        record_play(
+            ctx.accounts.play_history.as_ref(),
+            PLAY_ACTION_BUMP,
+            cost,
+            game_state.balance,
+        )?;
+
+        emit!(MachineBumped {
+            player: ctx.accounts.player.key(),
+            strength,
+            earlier_bumps,
+            cost,
+            new_balance: game_state.balance,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Award TRASHCOIN to player (rare reward), attested by the game
+    /// authority and limited by the emission schedule
+    pub fn award_trashcoin(
+        ctx: Context<AwardTrashcoin>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.config.require_enabled(instruction_flags::AWARD_TRASHCOIN)?;
+        require!(amount > 0, GameError::InvalidAmount);
+
+        let clock = Clock::get()?;
+        ctx.accounts.game_state.require_active(clock.unix_timestamp)?;
+        record_emission(
+            &mut ctx.accounts.config,
+            &mut ctx.accounts.game_state,
+            amount,
+            &clock,
+        )?;
+
+        // Transfer TRASHCOIN from vault to player
+        let seeds = &[
+            b"vault".as_ref(),
+            &[ctx.bumps.vault_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_trashcoin_account.to_account_info(),
+            to: ctx.accounts.player_trashcoin_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.global_stats.record_trashcoin_award(amount);
+
+        record_play(
+            ctx.accounts.play_history.as_ref(),
+            PLAY_ACTION_TRASHCOIN,
+            amount,
+            ctx.accounts.game_state.balance,
+        )?;
+
+        emit!(TrashcoinAwarded {
+            player: ctx.accounts.player.key(),
+            amount,
+            total_emitted: ctx.accounts.config.trashcoin_emission.total_emitted,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Reset game state
+    pub fn reset_game(ctx: Context<ResetGame>) -> Result<()> {
+        ctx.accounts.config.require_enabled(instruction_flags::RESET_GAME)?;
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.game_state.require_active(now)?;
+
+        let game_state = &mut ctx.accounts.game_state;
+        
+        game_state.score = 0;
+        game_state.balance = 100;
+        game_state.promotional_balance = 100;
+        game_state.net_profit = 0;
+        game_state.total_coins_collected = 0;
+        game_state.trashcoins_collected = 0;
+        game_state.coins_collected = [0; MAX_COIN_TYPES];
+        game_state.last_updated = now;
+
+        emit!(GameReset {
+            player: ctx.accounts.player.key(),
+            timestamp: game_state.last_updated,
+        });
+
+        Ok(())
+    }
+
+    /// Replace the staking lock tier table (admin only)
+    pub fn set_stake_tiers(
+        ctx: Context<SetStakeTiers>,
+        tiers: [StakeTier; MAX_STAKE_TIERS],
+    ) -> Result<()> {
+        staking::set_stake_tiers(ctx, tiers)
+    }
+
+    /// Lock JUNK in the player's stake vault for a lock tier
+    pub fn stake(ctx: Context<Stake>, amount: u64, tier: u8) -> Result<()> {
+        staking::stake(ctx, amount, tier)
+    }
+
+    /// Withdraw staked JUNK after the lock has ended
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        staking::unstake(ctx, amount)
+    }
+
+    /// Move a stake to another tier, extending its lock
+    pub fn extend_lock(ctx: Context<ExtendLock>, tier: u8) -> Result<()> {
+        staking::extend_lock(ctx, tier)
+    }
+
+    /// Create a timed tournament with an entry fee and payout table (admin only)
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        tournament_id: u64,
+        entry_fee: u64,
+        start_time: i64,
+        end_time: i64,
+        max_players: u32,
+        rake_bps: u16,
+        payout_bps: [u16; MAX_PAYOUT_RANKS],
+    ) -> Result<()> {
+        tournament::create_tournament(
+            ctx,
+            tournament_id,
+            entry_fee,
+            start_time,
+            end_time,
+            max_players,
+            rake_bps,
+            payout_bps,
+        )
+    }
+
+    /// Pay the entry fee and join a tournament
+    pub fn enter_tournament(ctx: Context<EnterTournament>) -> Result<()> {
+        tournament::enter_tournament(ctx)
+    }
+
+    /// Lock the leaderboard and take the house rake (permissionless)
+    pub fn finalize_tournament(ctx: Context<FinalizeTournament>) -> Result<()> {
+        tournament::finalize_tournament(ctx)
+    }
+
+    /// Claim the prize for a finishing rank
+    pub fn claim_tournament_prize(ctx: Context<ClaimTournamentPrize>, rank: u8) -> Result<()> {
+        tournament::claim_tournament_prize(ctx, rank)
+    }
+
+    /// Replace the power-up item catalogue (admin only)
+    pub fn set_item_catalogue(
+        ctx: Context<SetItemCatalogue>,
+        items: [ItemDef; MAX_ITEMS],
+    ) -> Result<()> {
+        items::set_item_catalogue(ctx, items)
+    }
+
+    /// Buy power-up items with JUNK or TRASHCOIN
+    pub fn buy_item(
+        ctx: Context<BuyItem>,
+        item_id: u8,
+        quantity: u16,
+        payment_token: PaymentToken,
+    ) -> Result<()> {
+        items::buy_item(ctx, item_id, quantity, payment_token)
+    }
+
+    /// Consume an item and start its effect
+    pub fn use_item(ctx: Context<UseItem>, item_id: u8) -> Result<()> {
+        items::use_item(ctx, item_id)
+    }
+
+    /// Replace the coin type reward table (admin only)
+    pub fn set_coin_types(
+        ctx: Context<SetCoinTypes>,
+        coin_types: [CoinType; MAX_COIN_TYPES],
+    ) -> Result<()> {
+        coins::set_coin_types(ctx, coin_types)
+    }
+
+    /// Set the daily gifting cap and minimum account age (admin only)
+    pub fn set_gift_limits(ctx: Context<SetGiftLimits>, limits: GiftLimits) -> Result<()> {
+        gifting::set_gift_limits(ctx, limits)
+    }
+
+    /// Send withdrawable balance to another player
+    pub fn gift_balance(ctx: Context<GiftBalance>, amount: u64) -> Result<()> {
+        gifting::gift_balance(ctx, amount)
+    }
+
+    /// Start writing a playfield snapshot
+    pub fn begin_snapshot(
+        ctx: Context<BeginSnapshot>,
+        session_id: u64,
+        pusher_phase: u16,
+        coin_count: u16,
+    ) -> Result<()> {
+        snapshot::begin_snapshot(ctx, session_id, pusher_phase, coin_count)
+    }
+
+    /// Append a chunk of coins to the snapshot being written
+    pub fn write_snapshot(
+        ctx: Context<WriteSnapshot>,
+        offset: u16,
+        coins: Vec<SnapshotCoin>,
+    ) -> Result<()> {
+        snapshot::write_snapshot(ctx, offset, coins)
+    }
+
+    /// Commit the written snapshot and chain its hash
+    pub fn save_snapshot(ctx: Context<SaveSnapshot>) -> Result<()> {
+        snapshot::save_snapshot(ctx)
+    }
+
+    /// Delete the player's snapshot
+    pub fn close_snapshot(ctx: Context<CloseSnapshot>) -> Result<()> {
+        snapshot::close_snapshot(ctx)
+    }
+
+    /// Set the cash-out caps, queue threshold and delay (admin only)
+    pub fn set_cash_out_limits(
+        ctx: Context<SetCashOutLimits>,
+        limits: CashOutLimits,
+    ) -> Result<()> {
+        cashout::set_cash_out_limits(ctx, limits)
+    }
+
+    /// Convert withdrawable balance into JUNK from the vault
+    pub fn cash_out(ctx: Context<CashOut>, amount: u64) -> Result<()> {
+        cashout::cash_out(ctx, amount)
+    }
+
+    /// Queue a large cash-out behind the withdrawal delay
+    pub fn queue_cash_out(ctx: Context<QueueCashOut>, amount: u64) -> Result<()> {
+        cashout::queue_cash_out(ctx, amount)
+    }
+
+    /// Pay out a queued cash-out once its delay has passed
+    pub fn claim_cash_out(ctx: Context<ClaimCashOut>) -> Result<()> {
+        cashout::claim_cash_out(ctx)
+    }
+
+    /// Freeze or release a queued cash-out (admin only)
+    pub fn set_cash_out_frozen(ctx: Context<SetCashOutFrozen>, frozen: bool) -> Result<()> {
+        cashout::set_cash_out_frozen(ctx, frozen)
+    }
+
+    /// Return a queued cash-out to the player's balance (admin only)
+    pub fn cancel_cash_out(ctx: Context<CancelCashOut>) -> Result<()> {
+        cashout::cancel_cash_out(ctx)
+    }
+
+    /// Replace the happy-hour schedule (admin only)
+    pub fn set_schedule(
+        ctx: Context<SetSchedule>,
+        windows: [ScheduleWindow; MAX_SCHEDULE_WINDOWS],
+    ) -> Result<()> {
+        schedule::set_schedule(ctx, windows)
+    }
+
+    /// Replace the TRASHCOIN emission schedule (admin only)
+    pub fn set_emission_schedule(
+        ctx: Context<SetEmissionSchedule>,
+        schedule: EmissionSchedule,
+    ) -> Result<()> {
+        emission::set_emission_schedule(ctx, schedule)
+    }
+
+    /// Replace the TRASHCOIN redemption shop (admin only)
+    pub fn set_redemption_options(
+        ctx: Context<SetRedemptionOptions>,
+        options: [RedemptionOption; MAX_REDEMPTION_OPTIONS],
+    ) -> Result<()> {
+        redemption::set_redemption_options(ctx, options)
+    }
+
+    /// Spend TRASHCOIN on JUNK, a coin skin or raffle entries
+    pub fn redeem(ctx: Context<Redeem>, option_id: u8, quantity: u32) -> Result<()> {
+        redemption::redeem(ctx, option_id, quantity)
+    }
+
+    /// Set the season guild scores are counted in (admin only)
+    pub fn set_guild_season(ctx: Context<SetGuildSeason>, season: GuildSeason) -> Result<()> {
+        guild::set_guild_season(ctx, season)
+    }
+
+    /// Found a guild and become its leader
+    pub fn create_guild(
+        ctx: Context<CreateGuild>,
+        guild_id: u64,
+        name: [u8; GUILD_NAME_LEN],
+        tithe_bps: u16,
+    ) -> Result<()> {
+        guild::create_guild(ctx, guild_id, name, tithe_bps)
+    }
+
+    /// Join a guild as a member
+    pub fn join_guild(ctx: Context<JoinGuild>) -> Result<()> {
+        guild::join_guild(ctx)
+    }
+
+    /// Leave the player's guild
+    pub fn leave_guild(ctx: Context<LeaveGuild>) -> Result<()> {
+        guild::leave_guild(ctx)
+    }
+
+    /// Remove a member from the guild (officers and leader)
+    pub fn kick_guild_member(ctx: Context<KickGuildMember>) -> Result<()> {
+        guild::kick_guild_member(ctx)
+    }
+
+    /// Change a member's guild role (leader only)
+    pub fn set_guild_role(ctx: Context<SetGuildRole>, role: GuildRole) -> Result<()> {
+        guild::set_guild_role(ctx, role)
+    }
+
+    /// Rename the guild (officers and leader)
+    pub fn rename_guild(ctx: Context<UpdateGuild>, name: [u8; GUILD_NAME_LEN]) -> Result<()> {
+        guild::rename_guild(ctx, name)
+    }
+
+    /// Change the guild's tithe on member collections (leader only)
+    pub fn set_guild_tithe(ctx: Context<UpdateGuild>, tithe_bps: u16) -> Result<()> {
+        guild::set_guild_tithe(ctx, tithe_bps)
+    }
+
+    /// Pay guild treasury credits to a member (leader only)
+    pub fn distribute_guild_treasury(
+        ctx: Context<DistributeGuildTreasury>,
+        amount: u64,
+    ) -> Result<()> {
+        guild::distribute_guild_treasury(ctx, amount)
+    }
+
+    /// Set the challenge rake and limits (admin only)
+    pub fn set_challenge_rules(ctx: Context<SetChallengeRules>, rules: ChallengeRules) -> Result<()> {
+        challenge::set_challenge_rules(ctx, rules)
+    }
+
+    /// Escrow a JUNK stake and challenge another player (or anyone) to a match
+    pub fn create_challenge(
+        ctx: Context<CreateChallenge>,
+        challenge_id: u64,
+        opponent: Pubkey,
+        stake: u64,
+        duration: i64,
+        accept_deadline: i64,
+    ) -> Result<()> {
+        challenge::create_challenge(ctx, challenge_id, opponent, stake, duration, accept_deadline)
+    }
+
+    /// Match a challenge's stake and start the match
+    pub fn accept_challenge(ctx: Context<AcceptChallenge>) -> Result<()> {
+        challenge::accept_challenge(ctx)
+    }
+
+    /// Pay out a finished challenge (permissionless)
+    pub fn settle_challenge(ctx: Context<SettleChallenge>) -> Result<()> {
+        challenge::settle_challenge(ctx)
+    }
+
+    /// Refund an unaccepted challenge after its deadline (permissionless)
+    pub fn refund_challenge(ctx: Context<RefundChallenge>) -> Result<()> {
+        challenge::refund_challenge(ctx)
+    }
+
+    /// Set the raffle pool share, tickets per drop and prizes (admin only)
+    pub fn set_raffle_rules(ctx: Context<SetRaffleRules>, rules: RaffleRules) -> Result<()> {
+        raffle::set_raffle_rules(ctx, rules)
+    }
+
+    /// Open a weekly raffle round with a committed seed hash (admin only)
+    pub fn open_raffle(ctx: Context<OpenRaffle>, round: u64, seed_hash: [u8; 32]) -> Result<()> {
+        raffle::open_raffle(ctx, round, seed_hash)
+    }
+
+    /// Join a raffle round so drops earn tickets
+    pub fn join_raffle(ctx: Context<JoinRaffle>) -> Result<()> {
+        raffle::join_raffle(ctx)
+    }
+
+    /// Spend redeemed raffle entries as tickets in a round
+    pub fn use_raffle_entries(ctx: Context<UseRaffleEntries>, quantity: u32) -> Result<()> {
+        raffle::use_raffle_entries(ctx, quantity)
+    }
+
+    /// Fix the entropy slot of an ended raffle round (permissionless)
+    pub fn close_raffle(ctx: Context<CloseRaffle>) -> Result<()> {
+        raffle::close_raffle(ctx)
+    }
+
+    /// Draw an ended raffle round from its entropy slot and revealed seed
+    pub fn draw_raffle(ctx: Context<DrawRaffle>, seed: [u8; 32]) -> Result<()> {
+        raffle::draw_raffle(ctx, seed)
+    }
+
+    /// Claim a raffle prize won by the caller's slot
+    pub fn claim_raffle_prize(ctx: Context<ClaimRafflePrize>, rank: u8) -> Result<()> {
+        raffle::claim_raffle_prize(ctx, rank)
+    }
+
+    /// Refund the caller's share of a raffle round whose seed was forfeited
+    pub fn refund_raffle_tickets(ctx: Context<RefundRaffleTickets>) -> Result<()> {
+        raffle::refund_raffle_tickets(ctx)
+    }
+
+    /// Create the player's recent play history
+    pub fn initialize_play_history(ctx: Context<InitializePlayHistory>) -> Result<()> {
+        history::initialize_play_history(ctx)
+    }
+
+    /// Copy the global stats into today's `StatsDay`
+    pub fn stats_snapshot(ctx: Context<StatsSnapshot>, day: i64) -> Result<()> {
+        stats::stats_snapshot(ctx, day)
+    }
+
+    /// Set how many new games and how much rent the signer will sponsor
+    pub fn set_sponsor_budget(
+        ctx: Context<SetSponsorBudget>,
+        max_accounts: u32,
+        max_lamports: u64,
+    ) -> Result<()> {
+        sponsor::set_sponsor_budget(ctx, max_accounts, max_lamports)
+    }
+
+    /// Start a game with the rent paid by a sponsor
+    pub fn initialize_sponsored_game(
+        ctx: Context<InitializeSponsoredGame>,
+        initial_balance: u64,
+    ) -> Result<()> {
+        sponsor::initialize_sponsored_game(ctx, initial_balance)
+    }
+
+    /// Close the player's game, returning rent to whoever paid it
+    pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+        sponsor::close_game(ctx)
+    }
+
+    /// Register a partner reward token and its vault (admin only)
+    pub fn register_reward_vault(
+        ctx: Context<RegisterRewardVault>,
+        reward_id: u64,
+        emission_budget: u64,
+    ) -> Result<()> {
+        reward::register_reward_vault(ctx, reward_id, emission_budget)
+    }
+
+    /// Update a reward token's budget and enabled flag (admin only)
+    pub fn set_reward_vault(
+        ctx: Context<SetRewardVault>,
+        emission_budget: u64,
+        enabled: bool,
+    ) -> Result<()> {
+        reward::set_reward_vault(ctx, emission_budget, enabled)
+    }
+
+    /// Award a registered reward token to the player
+    pub fn award_token(ctx: Context<AwardToken>, reward_id: u64, amount: u64) -> Result<()> {
+        reward::award_token(ctx, reward_id, amount)
+    }
+
+    /// Replace the bump cost curve (admin only)
+    pub fn set_bump_curve(ctx: Context<SetBumpCurve>, curve: BumpCurve) -> Result<()> {
+        bump_curve::set_bump_curve(ctx, curve)
+    }
+
+    /// Rotate the moderator (admin only)
+    pub fn set_moderator(ctx: Context<SetModerator>, new_moderator: Pubkey) -> Result<()> {
+        moderation::set_moderator(ctx, new_moderator)
+    }
+
+    /// Freeze, ban or reinstate a player (moderator only)
+    pub fn set_player_status(
+        ctx: Context<SetPlayerStatus>,
+        status: PlayerStatus,
+        reason: u16,
+        expires_at: i64,
+    ) -> Result<()> {
+        moderation::set_player_status(ctx, status, reason, expires_at)
+    }
+
+    /// Post a merkle airdrop and fund its vault (admin only)
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        distribution_id: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_claims: u32,
+        expires_at: i64,
+    ) -> Result<()> {
+        distribution::create_distribution(
+            ctx,
+            distribution_id,
+            merkle_root,
+            total_amount,
+            num_claims,
+            expires_at,
+        )
+    }
+
+    /// Claim the caller's leaf of a distribution with its merkle proof
+    pub fn claim_distribution(
+        ctx: Context<ClaimDistribution>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        distribution::claim_distribution(ctx, index, amount, proof)
+    }
+
+    /// Return an expired distribution's unclaimed tokens (admin only)
+    pub fn clawback_distribution(ctx: Context<ClawbackDistribution>) -> Result<()> {
+        distribution::clawback_distribution(ctx)
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Burn the player's burn share and transfer the rest of a JUNK payment to
+/// the vault, jackpot and treasury accounts.
+#[allow(clippy::too_many_arguments)]
+fn distribute_junk_payment<'info>(
+    token_program: &Program<'info, Token>,
+    player: &Signer<'info>,
+    player_junk_account: &Account<'info, TokenAccount>,
+    junk_mint: &Account<'info, Mint>,
+    vault_junk_account: &Account<'info, TokenAccount>,
+    jackpot_junk_account: Option<&Account<'info, TokenAccount>>,
+    treasury_junk_account: Option<&Account<'info, TokenAccount>>,
+    split: &PaymentSplit,
+) -> Result<()> {
+    if split.burn > 0 {
+        let cpi_accounts = Burn {
+            mint: junk_mint.to_account_info(),
+            from: player_junk_account.to_account_info(),
+            authority: player.to_account_info(),
+        };
+        token::burn(CpiContext::new(token_program.to_account_info(), cpi_accounts), split.burn)?;
+    }
+
+    let destinations = [
+        (Some(vault_junk_account), split.vault),
+        (jackpot_junk_account, split.jackpot),
+        (treasury_junk_account, split.treasury),
+    ];
+    for (destination, amount) in destinations {
+        if amount == 0 {
+            continue;
+        }
+        let destination = destination.ok_or(GameError::MissingFeeAccount)?;
+        let cpi_accounts = Transfer {
+            from: player_junk_account.to_account_info(),
+            to: destination.to_account_info(),
+            authority: player.to_account_info(),
+        };
+        token::transfer(CpiContext::new(token_program.to_account_info(), cpi_accounts), amount)?;
+    }
+
+    Ok(())
+}
+
+/// Add `amount` to the cumulative burn counter and emit `JunkBurned`
+fn record_burn(global_stats: &mut GlobalStats, player: Pubkey, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    global_stats.total_burned = global_stats.total_burned.saturating_add(amount);
+
+    emit!(JunkBurned {
+        player,
+        amount,
+        total_burned: global_stats.total_burned,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<GameConfig>(),
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, GameConfig>,
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ GameError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<GlobalStats>(),
+        seeds = [b"global_stats"],
+        bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<Schedule>(),
+        seeds = [b"schedule"],
+        bump,
+    )]
+    pub schedule: Account<'info, Schedule>,
+    pub junk_mint: Account<'info, Mint>,
+    pub trashcoin_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = pause_authority @ GameError::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+    pub pause_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeSplit<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+    #[account(token::mint = config.junk_mint)]
+    pub jackpot_junk_account: Account<'info, TokenAccount>,
+    #[account(token::mint = config.junk_mint)]
+    pub treasury_junk_account: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGameAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPauseAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGame<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GameConfig>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<GameState>(),
+        seeds = [b"game_state", player.key().as_ref()],
+        bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DropCoin<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GameConfig>,
+    #[account(
+        mut,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"schedule"],
+        bump = schedule.bump,
+    )]
+    pub schedule: Account<'info, Schedule>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(mut, address = config.junk_mint)]
+    pub junk_mint: Box<Account<'info, Mint>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = config.jackpot_junk_account)]
+    pub jackpot_junk_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut, address = config.treasury_junk_account)]
+    pub treasury_junk_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.round.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Option<Box<Account<'info, Raffle>>>,
+    #[account(
+        mut,
+        seeds = [b"raffle_vault", raffle_vault.owner.as_ref()],
+        bump,
+    )]
+    pub raffle_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub raffle_index: Option<Box<Account<'info, RaffleIndex>>>,
+    #[account(
+        mut,
+        seeds = [b"raffle_tickets", raffle_tickets.raffle.as_ref(), player.key().as_ref()],
+        bump = raffle_tickets.bump,
+    )]
+    pub raffle_tickets: Option<Box<Account<'info, RaffleTickets>>>,
+    #[account(
+        mut,
+        seeds = [b"play_history", player.key().as_ref()],
+        bump = play_history.load()?.bump,
+        has_one = player,
+    )]
+    pub play_history: Option<AccountLoader<'info, PlayHistory>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CollectCoin<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        seeds = [b"schedule"],
+        bump = schedule.bump,
+    )]
+    pub schedule: Account<'info, Schedule>,
+    #[account(
+        seeds = [b"stake", player.key().as_ref()],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Option<Account<'info, StakePosition>>,
+    #[account(
+        mut,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump = inventory.bump,
+    )]
+    pub inventory: Option<Box<Account<'info, Inventory>>>,
+    #[account(mut)]
+    pub tournament: Option<Box<Account<'info, Tournament>>>,
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+    #[account(mut)]
+    pub guild: Option<Box<Account<'info, Guild>>>,
+    #[account(
+        mut,
+        seeds = [b"guild_member", player.key().as_ref()],
+        bump = guild_membership.bump,
+    )]
+    pub guild_membership: Option<Box<Account<'info, GuildMember>>>,
+    #[account(mut)]
+    pub challenge: Option<Box<Account<'info, Challenge>>>,
+    #[account(
+        mut,
+        seeds = [b"play_history", player.key().as_ref()],
+        bump = play_history.load()?.bump,
+        has_one = player,
+    )]
+    pub play_history: Option<AccountLoader<'info, PlayHistory>>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault; only needed for coin types with a token payout
+    pub vault_authority: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub vault_payout_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub player_payout_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Option<Program<'info, Token>>,
+    pub player: Signer<'info>,
+    #[account(address = config.game_authority @ GameError::Unauthorized)]
+    pub game_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BumpMachine<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GameConfig>,
+    #[account(
+        mut,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"stake", player.key().as_ref()],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Option<Account<'info, StakePosition>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(mut, address = config.junk_mint)]
+    pub junk_mint: Box<Account<'info, Mint>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = config.jackpot_junk_account)]
+    pub jackpot_junk_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut, address = config.treasury_junk_account)]
+    pub treasury_junk_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"play_history", player.key().as_ref()],
+        bump = play_history.load()?.bump,
+        has_one = player,
+    )]
+    pub play_history: Option<AccountLoader<'info, PlayHistory>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AwardTrashcoin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.trashcoin_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_trashcoin_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub player_trashcoin_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"play_history", player.key().as_ref()],
+        bump = play_history.load()?.bump,
+        has_one = player,
+    )]
+    pub play_history: Option<AccountLoader<'info, PlayHistory>>,
+    pub player: Signer<'info>,
+    #[account(address = config.game_authority @ GameError::Unauthorized)]
+    pub game_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ResetGame<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GameConfig>,
+    #[account(
+        mut,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    pub player: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// Global program configuration. Withdrawal and cancel paths never check
+/// the pause state so players can always exit.
+#[account]
+pub struct GameConfig {
+    pub admin: Pubkey,
+    pub pause_authority: Pubkey,
+    /// Backend key that co-signs (attests) `collect_coin`
+    pub game_authority: Pubkey,
+    /// Sets player statuses (freeze/ban)
+    pub moderator: Pubkey,
+    pub paused: bool,
+    pub disabled_instructions: u64,
+    pub junk_mint: Pubkey,
+    pub trashcoin_mint: Pubkey,
+    pub fee_split: FeeSplit,
+    pub jackpot_junk_account: Pubkey,
+    pub treasury_junk_account: Pubkey,
+    pub stake_tiers: [StakeTier; MAX_STAKE_TIERS],
+    pub item_catalogue: [ItemDef; MAX_ITEMS],
+    pub coin_types: [CoinType; MAX_COIN_TYPES],
+    pub gift_limits: GiftLimits,
+    pub cash_out_limits: CashOutLimits,
+    /// Credits cashed out by all players in the current window
+    pub cash_out_window: CashOutWindow,
+    pub emission_schedule: EmissionSchedule,
+    pub trashcoin_emission: TrashcoinEmission,
+    pub redemption_options: [RedemptionOption; MAX_REDEMPTION_OPTIONS],
+    pub guild_season: GuildSeason,
+    pub challenge_rules: ChallengeRules,
+    pub raffle_rules: RaffleRules,
+    pub bump_curve: BumpCurve,
+    pub bump: u8,
+}
+
+impl GameConfig {
+    /// Fail if the program is paused or `flag` is in the kill bitmask
+    pub fn require_enabled(&self, flag: u64) -> Result<()> {
+        require!(!self.paused, GameError::ProgramPaused);
+        require!(
+            self.disabled_instructions & flag == 0,
+            GameError::InstructionDisabled
+        );
+        Ok(())
+    }
+
+    /// Look up a configured staking tier
+    pub fn stake_tier(&self, tier: u8) -> Result<StakeTier> {
+        let stake_tier = self.stake_tiers
+            .get(tier as usize)
+            .copied()
+            .ok_or(GameError::InvalidStakeTier)?;
+        require!(stake_tier.lock_duration > 0, GameError::InvalidStakeTier);
+        Ok(stake_tier)
+    }
+
+    /// Look up a configured catalogue item
+    pub fn item(&self, item_id: u8) -> Result<ItemDef> {
+        let item = self.item_catalogue
+            .get(item_id as usize)
+            .copied()
+            .ok_or(GameError::InvalidItem)?;
+        require!(item.kind != ItemKind::None, GameError::InvalidItem);
+        Ok(item)
+    }
+
+    /// Look up a configured redemption shop option
+    pub fn redemption_option(&self, option_id: u8) -> Result<RedemptionOption> {
+        let option = self.redemption_options
+            .get(option_id as usize)
+            .copied()
+            .ok_or(GameError::InvalidRedemption)?;
+        require!(option.kind != RedemptionKind::None, GameError::InvalidRedemption);
+        Ok(option)
+    }
+
+    /// Look up a configured coin type
+    pub fn coin_type(&self, coin_type: u8) -> Result<CoinType> {
+        let coin = self.coin_types
+            .get(coin_type as usize)
+            .copied()
+            .ok_or(GameError::InvalidCoinType)?;
+        require!(coin.is_enabled(), GameError::InvalidCoinType);
+        Ok(coin)
+    }
+
+    /// Pick the coin type a uniform `roll` lands on, weighted by spawn weight
+    pub fn roll_coin_type(&self, roll: u64) -> Option<u8> {
+        let total: u64 = self.coin_types.iter().map(|coin| coin.spawn_weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = roll % total;
+        for (index, coin) in self.coin_types.iter().enumerate() {
+            if roll < coin.spawn_weight as u64 {
+                return Some(index as u8);
+            }
+            roll -= coin.spawn_weight as u64;
+        }
+        None
+    }
+}
+
+/// Share of every JUNK payment, in basis points. Must sum to `BPS_DENOMINATOR`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub burn_bps: u16,
+    pub vault_bps: u16,
+    pub jackpot_bps: u16,
+    pub treasury_bps: u16,
+}
+
+impl Default for FeeSplit {
+    /// Everything goes to the house vault
+    fn default() -> Self {
+        Self {
+            burn_bps: 0,
+            vault_bps: BPS_DENOMINATOR as u16,
+            jackpot_bps: 0,
+            treasury_bps: 0,
+        }
+    }
+}
+
+impl FeeSplit {
+    pub fn validate(&self) -> Result<()> {
+        let total = self.burn_bps as u64
+            + self.vault_bps as u64
+            + self.jackpot_bps as u64
+            + self.treasury_bps as u64;
+        require!(total == BPS_DENOMINATOR, GameError::InvalidFeeSplit);
+        Ok(())
+    }
+
+    /// Split `amount`; rounding dust goes to the vault
+    pub fn split(&self, amount: u64) -> PaymentSplit {
+        let share = |bps: u16| (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let burn = share(self.burn_bps);
+        let jackpot = share(self.jackpot_bps);
+        let treasury = share(self.treasury_bps);
+        PaymentSplit {
+            burn,
+            vault: amount - burn - jackpot - treasury,
+            jackpot,
+            treasury,
+        }
+    }
+}
+
+/// A JUNK payment broken down by destination
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaymentSplit {
+    pub burn: u64,
+    pub vault: u64,
+    pub jackpot: u64,
+    pub treasury: u64,
+}
+
+#[account]
+pub struct GlobalStats {
+    pub total_burned: u64,
+    pub total_staked: u64,
+    /// Games ever started
+    pub total_players: u64,
+    pub total_drops: u64,
+    pub total_bumps: u64,
+    pub total_coins_collected: u64,
+    pub total_trashcoin_awarded: u64,
+    /// JUNK spent on drops and bumps
+    pub total_junk_wagered: u64,
+    /// JUNK paid from the house vault: cash-outs, coin payouts, redemptions
+    /// and raffle prizes
+    pub total_junk_paid_out: u64,
+    pub bump: u8,
+}
+
+impl GlobalStats {
+    pub fn record_player(&mut self) {
+        self.total_players = self.total_players.saturating_add(1);
+    }
+
+    pub fn record_drop(&mut self, junk: u64) {
+        self.total_drops = self.total_drops.saturating_add(1);
+        self.total_junk_wagered = self.total_junk_wagered.saturating_add(junk);
+    }
+
+    pub fn record_bump(&mut self, junk: u64) {
+        self.total_bumps = self.total_bumps.saturating_add(1);
+        self.total_junk_wagered = self.total_junk_wagered.saturating_add(junk);
+    }
+
+    pub fn record_collection(&mut self) {
+        self.total_coins_collected = self.total_coins_collected.saturating_add(1);
+    }
+
+    pub fn record_trashcoin_award(&mut self, amount: u64) {
+        self.total_trashcoin_awarded = self.total_trashcoin_awarded.saturating_add(amount);
+    }
+
+    pub fn record_junk_paid_out(&mut self, junk: u64) {
+        self.total_junk_paid_out = self.total_junk_paid_out.saturating_add(junk);
+    }
+}
+
+#[account]
+pub struct GameState {
+    pub player: Pubkey,
+    pub score: u64,
+    /// Total credits, promotional included
+    pub balance: u64,
+    /// Free/demo credits inside `balance`; playable but not transferable
+    pub promotional_balance: u64,
+    pub net_profit: i64,
+    pub total_coins_collected: u64,
+    pub trashcoins_collected: u64,
+    /// Coins collected per coin type since the last reset
+    pub coins_collected: [u64; MAX_COIN_TYPES],
+    /// Epoch that `epoch_coins_collected` refers to
+    pub coin_epoch: u64,
+    pub epoch_coins_collected: [u32; MAX_COIN_TYPES],
+    pub created_at: i64,
+    pub last_updated: i64,
+    /// Day (unix time / 86400) that `gifted_today` refers to
+    pub gift_day: i64,
+    pub gifted_today: u64,
+    pub cash_out_window: CashOutWindow,
+    /// Day (unix time / 86400) that `trashcoin_awarded_today` refers to
+    pub trashcoin_day: i64,
+    pub trashcoin_awarded_today: u64,
+    /// Who paid the account rent, or default if the player did
+    pub sponsor: Pubkey,
+    pub status: PlayerStatus,
+    /// Moderator's reason code for `status`
+    pub status_reason: u16,
+    /// When `status` lapses, or 0 if it doesn't
+    pub status_expires_at: i64,
+    pub bump_window: BumpWindow,
+    pub bump: u8,
+}
+
+impl GameState {
+    /// Fill in a freshly created game with `initial_balance` promotional credits
+    pub fn start(&mut self, player: Pubkey, initial_balance: u64, bump: u8, now: i64) {
+        self.player = player;
+        self.score = 0;
+        self.balance = initial_balance;
+        self.promotional_balance = initial_balance;
+        self.net_profit = 0;
+        self.total_coins_collected = 0;
+        self.trashcoins_collected = 0;
+        self.coins_collected = [0; MAX_COIN_TYPES];
+        self.coin_epoch = 0;
+        self.epoch_coins_collected = [0; MAX_COIN_TYPES];
+        self.gift_day = 0;
+        self.gifted_today = 0;
+        self.cash_out_window = CashOutWindow::default();
+        self.trashcoin_day = 0;
+        self.trashcoin_awarded_today = 0;
+        self.sponsor = Pubkey::default();
+        self.status = PlayerStatus::Active;
+        self.status_reason = 0;
+        self.status_expires_at = 0;
+        self.bump_window = BumpWindow::default();
+        self.bump = bump;
+        self.created_at = now;
+        self.last_updated = now;
+    }
+
+    /// Where the account rent goes when the game is closed
+    pub fn rent_recipient(&self) -> Pubkey {
+        if self.sponsor == Pubkey::default() {
+            self.player
+        } else {
+            self.sponsor
+        }
+    }
+
+    /// Fail if the player is frozen or banned and the status hasn't lapsed
+    pub fn require_active(&self, now: i64) -> Result<()> {
+        if self.status_expires_at != 0 && now >= self.status_expires_at {
+            return Ok(());
+        }
+        match self.status {
+            PlayerStatus::Active => Ok(()),
+            PlayerStatus::Frozen => err!(GameError::PlayerFrozen),
+            PlayerStatus::Banned => err!(GameError::PlayerBanned),
+        }
+    }
+
+    /// Credits that can be gifted or cashed out: everything that isn't promotional
+    pub fn withdrawable_balance(&self) -> u64 {
+        self.balance.saturating_sub(self.promotional_balance)
+    }
+
+    /// Take `cost` credits, promotional ones first
+    pub fn spend(&mut self, cost: u64) {
+        self.balance = self.balance.saturating_sub(cost);
+        self.promotional_balance = self
+            .promotional_balance
+            .saturating_sub(cost)
+            .min(self.balance);
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub pause_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub authority: Pubkey,
+    pub paused: bool,
+    pub disabled_instructions: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeSplitUpdated {
+    pub burn_bps: u16,
+    pub vault_bps: u16,
+    pub jackpot_bps: u16,
+    pub treasury_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JunkBurned {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub total_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GameAuthorityUpdated {
+    pub old_game_authority: Pubkey,
+    pub new_game_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseAuthorityUpdated {
+    pub old_pause_authority: Pubkey,
+    pub new_pause_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GameInitialized {
+    pub player: Pubkey,
+    pub initial_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CoinDropped {
+    pub player: Pubkey,
+    pub cost: u64,
+    /// Happy-hour discount applied to the drop
+    pub discount_bps: u16,
+    pub new_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CoinCollected {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub coin_type: u8,
+    /// Happy-hour multiplier applied to the reward (10_000 = none)
+    pub multiplier_bps: u16,
+    pub new_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MachineBumped {
+    pub player: Pubkey,
+    /// How hard the engine should shove the playfield
+    pub strength: u8,
+    /// Bumps the player made earlier in the current escalation window
+    pub earlier_bumps: u32,
+    pub cost: u64,
+    pub new_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrashcoinAwarded {
+    pub player: Pubkey,
+    pub amount: u64,
+    /// Total TRASHCOIN awarded so far, this award included
+    pub total_emitted: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GameReset {
+    pub player: Pubkey,
+    pub timestamp: i64,
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[error_code]
+pub enum GameError {
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Too many requests - rate limited")]
+    TooManyRequests,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Instruction is disabled")]
+    InstructionDisabled,
+    #[msg("Pause authority must differ from the upgrade authority")]
+    PauseAuthorityNotDistinct,
+    #[msg("Fee split must sum to 10000 basis points")]
+    InvalidFeeSplit,
+    #[msg("Fee destination account missing")]
+    MissingFeeAccount,
+    #[msg("Invalid or unconfigured stake tier")]
+    InvalidStakeTier,
+    #[msg("Stake is below the tier minimum")]
+    StakeBelowTierMinimum,
+    #[msg("Stake is still locked")]
+    StakeLocked,
+    #[msg("Tournament end must be after its start and in the future")]
+    InvalidTournamentWindow,
+    #[msg("Payout table must sum to 10000 basis points")]
+    InvalidPayoutTable,
+    #[msg("Tournament is closed")]
+    TournamentClosed,
+    #[msg("Tournament is full")]
+    TournamentFull,
+    #[msg("Tournament has not ended")]
+    TournamentNotEnded,
+    #[msg("Tournament already finalized")]
+    TournamentFinalized,
+    #[msg("Tournament entry does not match")]
+    InvalidTournamentEntry,
+    #[msg("Invalid rank")]
+    InvalidRank,
+    #[msg("Prize already claimed")]
+    PrizeAlreadyClaimed,
+    #[msg("Invalid or unconfigured item")]
+    InvalidItem,
+    #[msg("Item cannot be bought with this token")]
+    ItemNotForSale,
+    #[msg("Item not in inventory")]
+    ItemNotOwned,
+    #[msg("Too many active effects")]
+    TooManyActiveEffects,
+    #[msg("Invalid token mint")]
+    InvalidMint,
+    #[msg("Cannot gift to yourself")]
+    CannotGiftSelf,
+    #[msg("Account is too new to send gifts")]
+    AccountTooNew,
+    #[msg("Daily gift cap exceeded")]
+    GiftCapExceeded,
+    #[msg("Cash-out cap exceeded")]
+    CashOutCapExceeded,
+    #[msg("Cash-outs this large must be queued")]
+    CashOutQueueRequired,
+    #[msg("Withdrawal is still locked")]
+    WithdrawalLocked,
+    #[msg("Withdrawal is frozen")]
+    WithdrawalFrozen,
+    #[msg("Invalid coin type")]
+    InvalidCoinType,
+    #[msg("Per-epoch cap reached for this coin type")]
+    CoinTypeCapReached,
+    #[msg("Missing account for a coin type token payout")]
+    MissingPayoutAccount,
+    #[msg("Snapshot holds too many coins")]
+    SnapshotTooLarge,
+    #[msg("Snapshot chunk written out of order")]
+    SnapshotOutOfOrder,
+    #[msg("Snapshot is not open for writing")]
+    SnapshotNotWritable,
+    #[msg("Snapshot is missing coins")]
+    SnapshotIncomplete,
+    #[msg("Invalid schedule window")]
+    InvalidSchedule,
+    #[msg("TRASHCOIN emission budget exhausted for this epoch")]
+    EmissionExhausted,
+    #[msg("Daily TRASHCOIN award cap exceeded")]
+    TrashcoinCapExceeded,
+    #[msg("Invalid or unconfigured redemption option")]
+    InvalidRedemption,
+    #[msg("Redemption option is sold out")]
+    RedemptionSoldOut,
+    #[msg("Redemption limit reached for this wallet")]
+    RedemptionLimitReached,
+    #[msg("Guild season must end after it starts")]
+    InvalidGuildSeason,
+    #[msg("Guild name must be non-empty, zero-padded UTF-8")]
+    InvalidGuildName,
+    #[msg("Guild tithe is too high")]
+    InvalidGuildTithe,
+    #[msg("Guild is full")]
+    GuildFull,
+    #[msg("Guild still has members or treasury credits")]
+    GuildNotEmpty,
+    #[msg("Guild membership does not match")]
+    InvalidGuildMember,
+    #[msg("Invalid challenge stake, duration or opponent")]
+    InvalidChallenge,
+    #[msg("Challenge is no longer open")]
+    ChallengeClosed,
+    #[msg("Challenge has not ended yet")]
+    ChallengeNotEnded,
+    #[msg("Player is not in this challenge")]
+    NotChallengeParticipant,
+    #[msg("Invalid raffle rules or accounts")]
+    InvalidRaffle,
+    #[msg("Raffle round is not open")]
+    RaffleClosed,
+    #[msg("Raffle round has no free player slots")]
+    RaffleFull,
+    #[msg("Raffle round has not ended yet")]
+    RaffleNotEnded,
+    #[msg("Raffle round has already been drawn")]
+    RaffleAlreadyDrawn,
+    #[msg("Revealed seed does not match the raffle commitment")]
+    InvalidRaffleReveal,
+    #[msg("Sponsor budget is exhausted")]
+    SponsorBudgetExhausted,
+    #[msg("Game still holds withdrawable credits")]
+    GameNotEmpty,
+    #[msg("Stats can only be snapshotted for the current day")]
+    InvalidStatsDay,
+    #[msg("Accounts do not match the registered reward vault")]
+    InvalidRewardVault,
+    #[msg("Reward token is disabled")]
+    RewardVaultDisabled,
+    #[msg("Reward token emission budget exhausted")]
+    RewardBudgetExhausted,
+    #[msg("Invalid distribution parameters or claim index")]
+    InvalidDistribution,
+    #[msg("Distribution has expired")]
+    DistributionExpired,
+    #[msg("Distribution has not expired yet")]
+    DistributionNotExpired,
+    #[msg("Distribution leaf has already been claimed")]
+    DistributionAlreadyClaimed,
+    #[msg("Merkle proof does not match the distribution root")]
+    InvalidMerkleProof,
+    #[msg("Player is frozen")]
+    PlayerFrozen,
+    #[msg("Player is banned")]
+    PlayerBanned,
+    #[msg("Player status expiry is in the past")]
+    InvalidPlayerStatus,
+    #[msg("Bump curve needs a base cost and a maximum strength")]
+    InvalidBumpCurve,
+    #[msg("Bump strength is out of range")]
+    InvalidBumpStrength,
+    #[msg("Raffle round has not been closed yet")]
+    RaffleNotClosed,
+    #[msg("Raffle seed can still be revealed")]
+    RaffleRevealPending,
+    #[msg("Raffle reveal deadline has passed")]
+    RaffleRevealExpired,
+    #[msg("Raffle entropy slot hash is not available")]
+    RaffleEntropyUnavailable,
+    #[msg("Raffle seed has not been forfeited")]
+    RaffleNotForfeited,
+}
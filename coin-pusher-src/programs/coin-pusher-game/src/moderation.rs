@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+
+use crate::{GameConfig, GameError, GameState};
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Rotate the moderator (admin only)
+pub(crate) fn set_moderator(ctx: Context<SetModerator>, new_moderator: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let old_moderator = config.moderator;
+    config.moderator = new_moderator;
+
+    emit!(ModeratorUpdated {
+        old_moderator,
+        new_moderator,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Freeze, ban or reinstate a player (moderator only). `expires_at` of 0
+/// means the status holds until changed again. A ban forfeits the player's
+/// promotional credits; withdrawable credits can still be cashed out.
+pub(crate) fn set_player_status(
+    ctx: Context<SetPlayerStatus>,
+    status: PlayerStatus,
+    reason: u16,
+    expires_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        status == PlayerStatus::Active || expires_at == 0 || expires_at > now,
+        GameError::InvalidPlayerStatus
+    );
+
+    let game_state = &mut ctx.accounts.game_state;
+    let mut forfeited = 0;
+    if status == PlayerStatus::Banned {
+        forfeited = game_state.promotional_balance;
+        game_state.balance -= forfeited;
+        game_state.promotional_balance = 0;
+    }
+    game_state.status = status;
+    game_state.status_reason = reason;
+    game_state.status_expires_at = if status == PlayerStatus::Active {
+        0
+    } else {
+        expires_at
+    };
+    game_state.last_updated = now;
+
+    emit!(PlayerStatusUpdated {
+        player: game_state.player,
+        moderator: ctx.accounts.moderator.key(),
+        status,
+        reason,
+        expires_at: game_state.status_expires_at,
+        forfeited,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetModerator<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPlayerStatus<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = moderator @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"game_state", game_state.player.as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    pub moderator: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// Moderation status of a player, kept on their `GameState`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayerStatus {
+    #[default]
+    Active,
+    /// Can't play or be awarded, but can still cash out
+    Frozen,
+    /// Frozen, and promotional credits were forfeited
+    Banned,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct ModeratorUpdated {
+    pub old_moderator: Pubkey,
+    pub new_moderator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlayerStatusUpdated {
+    pub player: Pubkey,
+    pub moderator: Pubkey,
+    pub status: PlayerStatus,
+    pub reason: u16,
+    pub expires_at: i64,
+    /// Promotional credits taken by a ban
+    pub forfeited: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,946 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    instruction_flags, GameConfig, GameError, GameState, GlobalStats, Inventory,
+    BPS_DENOMINATOR, SECONDS_PER_DAY,
+};
+
+/// Length of a raffle round; round `n` runs from `n * RAFFLE_ROUND_SECONDS`
+pub const RAFFLE_ROUND_SECONDS: i64 = 7 * SECONDS_PER_DAY;
+
+/// Number of prizes drawn per round
+pub const MAX_RAFFLE_WINNERS: usize = 3;
+
+/// Most players one round can hold (size of the ticket index)
+pub const MAX_RAFFLE_PLAYERS: usize = 512;
+
+/// Slots between closing a round and the slot whose hash draws it
+pub const RAFFLE_ENTROPY_DELAY_SLOTS: u64 = 25;
+
+/// Slots after the entropy slot the admin has to reveal the seed. Kept
+/// under the 512 entries of the SlotHashes sysvar, so the entropy slot's
+/// hash can still be read at the deadline.
+pub const RAFFLE_REVEAL_SLOTS: u64 = 450;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Set the pool share, tickets per drop and prize table for new rounds
+/// (admin only)
+pub(crate) fn set_raffle_rules(ctx: Context<SetRaffleRules>, rules: RaffleRules) -> Result<()> {
+    rules.validate()?;
+    ctx.accounts.config.raffle_rules = rules;
+
+    emit!(RaffleRulesUpdated {
+        pool_bps: rules.pool_bps,
+        tickets_per_drop: rules.tickets_per_drop,
+        payout_bps: rules.payout_bps,
+    });
+
+    Ok(())
+}
+
+/// Open round `round`, committing to the hash of the seed that will draw
+/// it (admin only). The seed is revealed in `draw_raffle`.
+pub(crate) fn open_raffle(ctx: Context<OpenRaffle>, round: u64, seed_hash: [u8; 32]) -> Result<()> {
+    let rules = ctx.accounts.config.raffle_rules;
+    rules.validate()?;
+    require!(rules.tickets_per_drop > 0, GameError::InvalidRaffle);
+
+    let start_time = (round as i64)
+        .checked_mul(RAFFLE_ROUND_SECONDS)
+        .ok_or(GameError::InvalidRaffle)?;
+    let end_time = start_time + RAFFLE_ROUND_SECONDS;
+    require!(
+        end_time > Clock::get()?.unix_timestamp,
+        GameError::RaffleClosed
+    );
+
+    let raffle = &mut ctx.accounts.raffle;
+    raffle.round = round;
+    raffle.start_time = start_time;
+    raffle.end_time = end_time;
+    raffle.seed_hash = seed_hash;
+    raffle.entropy_slot = 0;
+    raffle.seed_forfeited = false;
+    raffle.pool_bps = rules.pool_bps;
+    raffle.tickets_per_drop = rules.tickets_per_drop;
+    raffle.payout_bps = rules.payout_bps;
+    raffle.prize_pool = 0;
+    raffle.total_tickets = 0;
+    raffle.player_count = 0;
+    raffle.winning_slots = [0; MAX_RAFFLE_WINNERS];
+    raffle.prizes = [0; MAX_RAFFLE_WINNERS];
+    raffle.claimed = 0;
+    raffle.drawn = false;
+    raffle.bump = ctx.bumps.raffle;
+
+    let index = &mut ctx.accounts.raffle_index;
+    index.raffle = raffle.key();
+    index.tree = vec![0; MAX_RAFFLE_PLAYERS];
+    index.bump = ctx.bumps.raffle_index;
+
+    emit!(RaffleOpened {
+        raffle: raffle.key(),
+        round,
+        start_time,
+        end_time,
+        seed_hash,
+    });
+
+    Ok(())
+}
+
+/// Take a slot in the round's ticket index. Drops only earn tickets once
+/// the player has joined.
+pub(crate) fn join_raffle(ctx: Context<JoinRaffle>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::RAFFLE)?;
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.game_state.require_active(now)?;
+
+    let raffle = &mut ctx.accounts.raffle;
+    require!(
+        !raffle.drawn && now < raffle.end_time,
+        GameError::RaffleClosed
+    );
+    require!(
+        (raffle.player_count as usize) < MAX_RAFFLE_PLAYERS,
+        GameError::RaffleFull
+    );
+    let slot = raffle.player_count;
+    raffle.player_count += 1;
+
+    let tickets = &mut ctx.accounts.raffle_tickets;
+    tickets.raffle = raffle.key();
+    tickets.player = ctx.accounts.player.key();
+    tickets.slot = slot;
+    tickets.tickets = 0;
+    tickets.bump = ctx.bumps.raffle_tickets;
+
+    emit!(RaffleJoined {
+        raffle: raffle.key(),
+        player: tickets.player,
+        slot,
+    });
+
+    Ok(())
+}
+
+/// Turn raffle entries redeemed in the shop into tickets for this round
+pub(crate) fn use_raffle_entries(ctx: Context<UseRaffleEntries>, quantity: u32) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::RAFFLE)?;
+    require!(quantity > 0, GameError::InvalidAmount);
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.game_state.require_active(now)?;
+
+    let inventory = &mut ctx.accounts.inventory;
+    inventory.raffle_entries = inventory
+        .raffle_entries
+        .checked_sub(quantity)
+        .ok_or(GameError::InsufficientBalance)?;
+
+    let raffle_key = ctx.accounts.raffle.key();
+    require!(ctx.accounts.raffle.is_open(now), GameError::RaffleClosed);
+    credit_raffle_tickets(
+        &mut ctx.accounts.raffle,
+        raffle_key,
+        &mut ctx.accounts.raffle_index,
+        &mut ctx.accounts.raffle_tickets,
+        quantity as u64,
+        now,
+    )
+}
+
+/// Fix the slot whose hash will be mixed into the draw, a few slots after
+/// the round ends so nobody knows it yet. If the admin lets the reveal
+/// deadline pass, closing again forfeits the seed: the round is never drawn
+/// and each entrant takes back their share of the pool with
+/// `refund_raffle_tickets`. Permissionless.
+pub(crate) fn close_raffle(ctx: Context<CloseRaffle>) -> Result<()> {
+    let clock = Clock::get()?;
+    let raffle = &mut ctx.accounts.raffle;
+    require!(!raffle.drawn, GameError::RaffleAlreadyDrawn);
+    require!(
+        clock.unix_timestamp >= raffle.end_time,
+        GameError::RaffleNotEnded
+    );
+    if raffle.entropy_slot == 0 {
+        raffle.entropy_slot = clock.slot + RAFFLE_ENTROPY_DELAY_SLOTS;
+    } else {
+        require!(!raffle.seed_forfeited, GameError::RaffleRevealExpired);
+        require!(
+            clock.slot > raffle.reveal_deadline(),
+            GameError::RaffleRevealPending
+        );
+        raffle.seed_forfeited = true;
+    }
+
+    emit!(RaffleClosed {
+        raffle: raffle.key(),
+        round: raffle.round,
+        entropy_slot: raffle.entropy_slot,
+        seed_forfeited: raffle.seed_forfeited,
+    });
+
+    Ok(())
+}
+
+/// Draw the winners from the hash of the round's entropy slot and the
+/// committed seed, which must be revealed by the deadline. The admin knows
+/// the seed but not the slot hash, and whoever closed the round knew
+/// neither. A forfeited round can't be drawn, so withholding the seed never
+/// buys a second outcome. The pool left after the prizes goes back to the
+/// house vault.
+pub(crate) fn draw_raffle(ctx: Context<DrawRaffle>, seed: [u8; 32]) -> Result<()> {
+    let raffle_key = ctx.accounts.raffle.key();
+    let raffle = &mut ctx.accounts.raffle;
+    require!(!raffle.drawn, GameError::RaffleAlreadyDrawn);
+    require!(raffle.entropy_slot != 0, GameError::RaffleNotClosed);
+    require!(
+        !raffle.seed_forfeited && Clock::get()?.slot <= raffle.reveal_deadline(),
+        GameError::RaffleRevealExpired
+    );
+    require!(
+        hashv(&[&seed]).to_bytes() == raffle.seed_hash,
+        GameError::InvalidRaffleReveal
+    );
+    let slot_hash = slot_hash_at_or_after(&ctx.accounts.slot_hashes, raffle.entropy_slot)?;
+
+    if raffle.total_tickets > 0 {
+        for rank in 0..MAX_RAFFLE_WINNERS {
+            if raffle.payout_bps[rank] == 0 {
+                continue;
+            }
+            let ticket = draw_ticket(
+                &seed,
+                &slot_hash,
+                &raffle_key,
+                rank as u8,
+                raffle.total_tickets,
+            );
+            raffle.winning_slots[rank] = ctx.accounts.raffle_index.find(ticket);
+            raffle.prizes[rank] = (raffle.prize_pool as u128 * raffle.payout_bps[rank] as u128
+                / BPS_DENOMINATOR as u128) as u64;
+        }
+    }
+    raffle.drawn = true;
+    let house_amount = raffle.prize_pool - raffle.prizes.iter().sum::<u64>();
+
+    if house_amount > 0 {
+        let round_bytes = raffle.round.to_le_bytes();
+        let seeds = &[b"raffle".as_ref(), round_bytes.as_ref(), &[raffle.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.raffle_vault.to_account_info(),
+            to: ctx.accounts.vault_junk_account.to_account_info(),
+            authority: ctx.accounts.raffle.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            house_amount,
+        )?;
+    }
+
+    let raffle = &ctx.accounts.raffle;
+    emit!(RaffleDrawn {
+        raffle: raffle_key,
+        round: raffle.round,
+        seed,
+        slot_hash,
+        total_tickets: raffle.total_tickets,
+        prize_pool: raffle.prize_pool,
+        house_amount,
+        winning_slots: raffle.winning_slots,
+        prizes: raffle.prizes,
+    });
+
+    Ok(())
+}
+
+/// Pay the prize for `rank` from the round's pool to the player whose slot
+/// won it. Not pausable.
+pub(crate) fn claim_raffle_prize(ctx: Context<ClaimRafflePrize>, rank: u8) -> Result<()> {
+    ctx.accounts
+        .game_state
+        .require_active(Clock::get()?.unix_timestamp)?;
+
+    let raffle = &ctx.accounts.raffle;
+    require!(raffle.drawn, GameError::RaffleNotEnded);
+
+    let rank_index = rank as usize;
+    require!(rank_index < MAX_RAFFLE_WINNERS, GameError::InvalidRank);
+    let prize = raffle.prizes[rank_index];
+    require!(
+        prize > 0 && raffle.winning_slots[rank_index] == ctx.accounts.raffle_tickets.slot,
+        GameError::Unauthorized
+    );
+    require!(
+        raffle.claimed & (1 << rank_index) == 0,
+        GameError::PrizeAlreadyClaimed
+    );
+
+    let round_bytes = raffle.round.to_le_bytes();
+    let seeds = &[b"raffle".as_ref(), round_bytes.as_ref(), &[raffle.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.raffle_vault.to_account_info(),
+        to: ctx.accounts.player_junk_account.to_account_info(),
+        authority: ctx.accounts.raffle.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+        prize,
+    )?;
+    ctx.accounts.global_stats.record_junk_paid_out(prize);
+
+    let raffle = &mut ctx.accounts.raffle;
+    raffle.claimed |= 1 << rank_index;
+
+    emit!(RafflePrizeClaimed {
+        raffle: raffle.key(),
+        player: ctx.accounts.player.key(),
+        rank,
+        amount: prize,
+    });
+
+    Ok(())
+}
+
+/// Pay a player of a forfeited round their tickets' share of the pool and
+/// close their ticket account. Not pausable.
+pub(crate) fn refund_raffle_tickets(ctx: Context<RefundRaffleTickets>) -> Result<()> {
+    ctx.accounts
+        .game_state
+        .require_active(Clock::get()?.unix_timestamp)?;
+
+    let raffle = &ctx.accounts.raffle;
+    require!(raffle.seed_forfeited, GameError::RaffleNotForfeited);
+
+    let tickets = ctx.accounts.raffle_tickets.tickets;
+    let amount = if raffle.total_tickets == 0 {
+        0
+    } else {
+        (raffle.prize_pool as u128 * tickets as u128 / raffle.total_tickets as u128) as u64
+    };
+
+    if amount > 0 {
+        let round_bytes = raffle.round.to_le_bytes();
+        let seeds = &[b"raffle".as_ref(), round_bytes.as_ref(), &[raffle.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.raffle_vault.to_account_info(),
+            to: ctx.accounts.player_junk_account.to_account_info(),
+            authority: ctx.accounts.raffle.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            amount,
+        )?;
+        ctx.accounts.global_stats.record_junk_paid_out(amount);
+    }
+
+    emit!(RaffleRefunded {
+        raffle: ctx.accounts.raffle.key(),
+        player: ctx.accounts.player.key(),
+        tickets,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Add the round's share of a drop's vault payment to its pool and, if
+/// the player has joined, credit their tickets. Returns the share, which
+/// the caller moves into the round's vault. Called from `drop_coin`.
+pub fn credit_raffle_drop(
+    raffle: &mut Raffle,
+    raffle_key: Pubkey,
+    entry: Option<(&mut RaffleIndex, &mut RaffleTickets)>,
+    vault_share: u64,
+    now: i64,
+) -> Result<u64> {
+    if !raffle.is_open(now) {
+        return Ok(0);
+    }
+    let pool_share =
+        (vault_share as u128 * raffle.pool_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    raffle.prize_pool = raffle.prize_pool.saturating_add(pool_share);
+
+    if let Some((index, tickets)) = entry {
+        let count = raffle.tickets_per_drop as u64;
+        credit_raffle_tickets(raffle, raffle_key, index, tickets, count, now)?;
+    }
+    Ok(pool_share)
+}
+
+/// Move a drop's pool share from the house vault into the round's vault
+pub(crate) fn fund_raffle_pool<'info>(
+    vault_authority: &UncheckedAccount<'info>,
+    vault_bump: u8,
+    vault_junk_account: &Account<'info, TokenAccount>,
+    raffle_vault: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"vault".as_ref(), &[vault_bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: vault_junk_account.to_account_info(),
+        to: raffle_vault.to_account_info(),
+        authority: vault_authority.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer),
+        amount,
+    )
+}
+
+fn credit_raffle_tickets(
+    raffle: &mut Raffle,
+    raffle_key: Pubkey,
+    index: &mut RaffleIndex,
+    tickets: &mut RaffleTickets,
+    count: u64,
+    now: i64,
+) -> Result<()> {
+    require_keys_eq!(index.raffle, raffle_key, GameError::InvalidRaffle);
+    require_keys_eq!(tickets.raffle, raffle_key, GameError::InvalidRaffle);
+
+    index.add(tickets.slot, count);
+    tickets.tickets = tickets.tickets.saturating_add(count);
+    raffle.total_tickets = raffle.total_tickets.saturating_add(count);
+
+    emit!(RaffleTicketsCredited {
+        raffle: raffle_key,
+        player: tickets.player,
+        slot: tickets.slot,
+        tickets: count,
+        player_tickets: tickets.tickets,
+        total_tickets: raffle.total_tickets,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Hash of the earliest slot at or after `slot` in the SlotHashes sysvar.
+/// The sysvar is too large to deserialize on chain, so it is read in
+/// place: a u64 length, then (slot, hash) pairs, newest first.
+fn slot_hash_at_or_after(slot_hashes: &AccountInfo, slot: u64) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+    let len = data
+        .get(..8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(GameError::RaffleEntropyUnavailable)? as usize;
+
+    let mut found = None;
+    for entry in data[8..].chunks_exact(40).take(len) {
+        let entry_slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if entry_slot < slot {
+            // Only trust `found` if it is known to be the first at or after
+            return found.ok_or_else(|| error!(GameError::RaffleEntropyUnavailable));
+        }
+        found = Some(entry[8..].try_into().unwrap());
+    }
+    err!(GameError::RaffleEntropyUnavailable)
+}
+
+/// Ticket number drawn for `rank`, uniform enough over `total_tickets`
+fn draw_ticket(
+    seed: &[u8; 32],
+    slot_hash: &[u8; 32],
+    raffle: &Pubkey,
+    rank: u8,
+    total_tickets: u64,
+) -> u64 {
+    let hash = hashv(&[seed, slot_hash, raffle.as_ref(), &[rank]]).to_bytes();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(bytes) % total_tickets
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetRaffleRules<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct OpenRaffle<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<Raffle>(),
+        seeds = [b"raffle", round.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub raffle: Box<Account<'info, Raffle>>,
+    #[account(
+        init,
+        payer = admin,
+        space = RaffleIndex::SPACE,
+        seeds = [b"raffle_index", raffle.key().as_ref()],
+        bump,
+    )]
+    pub raffle_index: Box<Account<'info, RaffleIndex>>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = junk_mint,
+        token::authority = raffle,
+        seeds = [b"raffle_vault", raffle.key().as_ref()],
+        bump,
+    )]
+    pub raffle_vault: Box<Account<'info, TokenAccount>>,
+    #[account(address = config.junk_mint)]
+    pub junk_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinRaffle<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.round.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Box<Account<'info, Raffle>>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<RaffleTickets>(),
+        seeds = [b"raffle_tickets", raffle.key().as_ref(), player.key().as_ref()],
+        bump,
+    )]
+    pub raffle_tickets: Account<'info, RaffleTickets>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UseRaffleEntries<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.round.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Box<Account<'info, Raffle>>,
+    #[account(
+        mut,
+        seeds = [b"raffle_index", raffle.key().as_ref()],
+        bump = raffle_index.bump,
+    )]
+    pub raffle_index: Box<Account<'info, RaffleIndex>>,
+    #[account(
+        mut,
+        seeds = [b"raffle_tickets", raffle.key().as_ref(), player.key().as_ref()],
+        bump = raffle_tickets.bump,
+    )]
+    pub raffle_tickets: Account<'info, RaffleTickets>,
+    #[account(
+        mut,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump = inventory.bump,
+        has_one = player,
+    )]
+    pub inventory: Box<Account<'info, Inventory>>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRaffle<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.round.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Box<Account<'info, Raffle>>,
+}
+
+#[derive(Accounts)]
+pub struct DrawRaffle<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.round.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Box<Account<'info, Raffle>>,
+    #[account(
+        seeds = [b"raffle_index", raffle.key().as_ref()],
+        bump = raffle_index.bump,
+    )]
+    pub raffle_index: Box<Account<'info, RaffleIndex>>,
+    #[account(
+        mut,
+        seeds = [b"raffle_vault", raffle.key().as_ref()],
+        bump,
+    )]
+    pub raffle_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(address = slot_hashes::ID)]
+    /// CHECK: the SlotHashes sysvar, read in place by the handler
+    pub slot_hashes: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRafflePrize<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.round.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Box<Account<'info, Raffle>>,
+    #[account(
+        seeds = [b"raffle_tickets", raffle.key().as_ref(), player.key().as_ref()],
+        bump = raffle_tickets.bump,
+    )]
+    pub raffle_tickets: Account<'info, RaffleTickets>,
+    #[account(
+        mut,
+        seeds = [b"raffle_vault", raffle.key().as_ref()],
+        bump,
+    )]
+    pub raffle_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RefundRaffleTickets<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"raffle", raffle.round.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+    )]
+    pub raffle: Box<Account<'info, Raffle>>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"raffle_tickets", raffle.key().as_ref(), player.key().as_ref()],
+        bump = raffle_tickets.bump,
+    )]
+    pub raffle_tickets: Account<'info, RaffleTickets>,
+    #[account(
+        mut,
+        seeds = [b"raffle_vault", raffle.key().as_ref()],
+        bump,
+    )]
+    pub raffle_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// Settings copied into each round when it opens
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RaffleRules {
+    /// Share of each drop's vault payment earmarked for the prize pool
+    pub pool_bps: u16,
+    pub tickets_per_drop: u32,
+    /// Share of the pool per prize; sums to 10_000. Zero skips the prize.
+    pub payout_bps: [u16; MAX_RAFFLE_WINNERS],
+}
+
+impl RaffleRules {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.pool_bps as u64 <= BPS_DENOMINATOR,
+            GameError::InvalidRaffle
+        );
+        let payout_total: u64 = self.payout_bps.iter().map(|bps| *bps as u64).sum();
+        require!(payout_total == BPS_DENOMINATOR, GameError::InvalidRaffle);
+        Ok(())
+    }
+}
+
+/// One weekly round. Drops move the prize pool into the round's own JUNK
+/// account (`raffle_vault`), out of reach of house payouts; prizes are paid
+/// from it and the rest goes back to the house vault when drawn.
+#[account]
+pub struct Raffle {
+    pub round: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Hash of the seed revealed in `draw_raffle`
+    pub seed_hash: [u8; 32],
+    /// Slot whose hash (or the next one produced) is mixed into the draw;
+    /// zero until `close_raffle`
+    pub entropy_slot: u64,
+    /// Set when the seed wasn't revealed in time; the round is then
+    /// refunded instead of drawn
+    pub seed_forfeited: bool,
+    pub pool_bps: u16,
+    pub tickets_per_drop: u32,
+    pub payout_bps: [u16; MAX_RAFFLE_WINNERS],
+    pub prize_pool: u64,
+    pub total_tickets: u64,
+    pub player_count: u32,
+    /// Ticket index slot holding each prize, set when drawn
+    pub winning_slots: [u32; MAX_RAFFLE_WINNERS],
+    /// Prize per rank, fixed when drawn
+    pub prizes: [u64; MAX_RAFFLE_WINNERS],
+    /// Bit `rank` set once that prize has been claimed
+    pub claimed: u8,
+    pub drawn: bool,
+    pub bump: u8,
+}
+
+impl Raffle {
+    pub fn is_open(&self, now: i64) -> bool {
+        !self.drawn && self.start_time <= now && now < self.end_time
+    }
+
+    /// Last slot the committed seed can be revealed in
+    pub fn reveal_deadline(&self) -> u64 {
+        self.entropy_slot + RAFFLE_REVEAL_SLOTS
+    }
+}
+
+/// Fenwick tree of ticket counts per player slot, so a drawn ticket number
+/// maps to the slot whose cumulative range holds it
+#[account]
+pub struct RaffleIndex {
+    pub raffle: Pubkey,
+    /// `MAX_RAFFLE_PLAYERS` nodes; node `i` covers slots `(i - lowbit(i), i]`
+    /// (1-based)
+    pub tree: Vec<u64>,
+    pub bump: u8,
+}
+
+impl RaffleIndex {
+    pub const SPACE: usize = 8 + 32 + 4 + MAX_RAFFLE_PLAYERS * 8 + 1;
+
+    /// Add `count` tickets to `slot`
+    pub fn add(&mut self, slot: u32, count: u64) {
+        let mut node = slot as usize + 1;
+        while node <= self.tree.len() {
+            self.tree[node - 1] += count;
+            node += node & node.wrapping_neg();
+        }
+    }
+
+    /// Tickets held by slots `0..slot`
+    pub fn prefix_sum(&self, slot: u32) -> u64 {
+        let mut node = slot as usize;
+        let mut sum = 0;
+        while node > 0 {
+            sum += self.tree[node - 1];
+            node -= node & node.wrapping_neg();
+        }
+        sum
+    }
+
+    /// The slot whose cumulative ticket range holds ticket number `ticket`
+    /// (0-based, below the total)
+    pub fn find(&self, ticket: u64) -> u32 {
+        let mut node = 0;
+        let mut remaining = ticket;
+        let mut step = self.tree.len().next_power_of_two();
+        while step > 0 {
+            let next = node + step;
+            if next <= self.tree.len() && self.tree[next - 1] <= remaining {
+                node = next;
+                remaining -= self.tree[next - 1];
+            }
+            step >>= 1;
+        }
+        node as u32
+    }
+}
+
+#[account]
+pub struct RaffleTickets {
+    pub raffle: Pubkey,
+    pub player: Pubkey,
+    /// The player's slot in the round's `RaffleIndex`
+    pub slot: u32,
+    pub tickets: u64,
+    pub bump: u8,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct RaffleRulesUpdated {
+    pub pool_bps: u16,
+    pub tickets_per_drop: u32,
+    pub payout_bps: [u16; MAX_RAFFLE_WINNERS],
+}
+
+#[event]
+pub struct RaffleOpened {
+    pub raffle: Pubkey,
+    pub round: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub seed_hash: [u8; 32],
+}
+
+#[event]
+pub struct RaffleJoined {
+    pub raffle: Pubkey,
+    pub player: Pubkey,
+    pub slot: u32,
+}
+
+#[event]
+pub struct RaffleTicketsCredited {
+    pub raffle: Pubkey,
+    pub player: Pubkey,
+    pub slot: u32,
+    pub tickets: u64,
+    pub player_tickets: u64,
+    pub total_tickets: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RaffleClosed {
+    pub raffle: Pubkey,
+    pub round: u64,
+    pub entropy_slot: u64,
+    pub seed_forfeited: bool,
+}
+
+#[event]
+pub struct RaffleDrawn {
+    pub raffle: Pubkey,
+    pub round: u64,
+    /// Zero if the seed was forfeited
+    pub seed: [u8; 32],
+    pub slot_hash: [u8; 32],
+    pub total_tickets: u64,
+    pub prize_pool: u64,
+    /// Pool left after the prizes, returned to the house vault
+    pub house_amount: u64,
+    pub winning_slots: [u32; MAX_RAFFLE_WINNERS],
+    pub prizes: [u64; MAX_RAFFLE_WINNERS],
+}
+
+#[event]
+pub struct RafflePrizeClaimed {
+    pub raffle: Pubkey,
+    pub player: Pubkey,
+    pub rank: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RaffleRefunded {
+    pub raffle: Pubkey,
+    pub player: Pubkey,
+    pub tickets: u64,
+    pub amount: u64,
+}
@@ -0,0 +1,284 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+
+use crate::{instruction_flags, GameConfig, GameError, GameState, GlobalStats, Inventory};
+
+/// Number of entries in `GameConfig::redemption_options`
+pub const MAX_REDEMPTION_OPTIONS: usize = 8;
+
+/// Number of distinct coin skins an inventory can record
+pub const MAX_SKINS: u8 = 64;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the redemption shop (admin only). Stock is set to whatever the
+/// new table says; per-wallet counts are kept per slot.
+pub(crate) fn set_redemption_options(
+    ctx: Context<SetRedemptionOptions>,
+    options: [RedemptionOption; MAX_REDEMPTION_OPTIONS],
+) -> Result<()> {
+    for option in options.iter() {
+        option.validate()?;
+    }
+    ctx.accounts.config.redemption_options = options;
+
+    emit!(RedemptionOptionsUpdated {
+        options,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Spend TRASHCOIN on `quantity` of redemption option `option_id`
+pub(crate) fn redeem(ctx: Context<Redeem>, option_id: u8, quantity: u32) -> Result<()> {
+    let config = &ctx.accounts.config;
+    config.require_enabled(instruction_flags::REDEEM)?;
+    require!(quantity > 0, GameError::InvalidAmount);
+    ctx.accounts
+        .game_state
+        .require_active(Clock::get()?.unix_timestamp)?;
+
+    let option = config.redemption_option(option_id)?;
+    require!(option.stock >= quantity, GameError::RedemptionSoldOut);
+
+    let inventory = &mut ctx.accounts.inventory;
+    if inventory.player == Pubkey::default() {
+        inventory.player = ctx.accounts.player.key();
+        inventory.bump = ctx.bumps.inventory;
+    }
+    let redeemed = inventory.redeemed[option_id as usize]
+        .checked_add(quantity)
+        .ok_or(GameError::InvalidAmount)?;
+    require!(
+        option.wallet_limit == 0 || redeemed <= option.wallet_limit,
+        GameError::RedemptionLimitReached
+    );
+
+    let price = option
+        .price_trashcoin
+        .checked_mul(quantity as u64)
+        .ok_or(GameError::InvalidAmount)?;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    if option.burn {
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.trashcoin_mint.to_account_info(),
+            from: ctx.accounts.player_trashcoin_account.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        token::burn(CpiContext::new(token_program.clone(), cpi_accounts), price)?;
+    } else {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.player_trashcoin_account.to_account_info(),
+            to: ctx.accounts.vault_trashcoin_account.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        token::transfer(CpiContext::new(token_program.clone(), cpi_accounts), price)?;
+    }
+
+    let mut junk_paid = 0;
+    match option.kind {
+        RedemptionKind::Junk => {
+            junk_paid = option
+                .junk_amount
+                .checked_mul(quantity as u64)
+                .ok_or(GameError::InvalidAmount)?;
+            let seeds = &[b"vault".as_ref(), &[ctx.bumps.vault_authority]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.vault_junk_account.to_account_info(),
+                to: ctx.accounts.player_junk_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new_with_signer(token_program, cpi_accounts, signer),
+                junk_paid,
+            )?;
+            ctx.accounts.global_stats.record_junk_paid_out(junk_paid);
+        }
+        RedemptionKind::Skin => {
+            require!(
+                quantity == 1 && !inventory.owns_skin(option.skin_id),
+                GameError::RedemptionLimitReached
+            );
+            inventory.skins |= 1 << option.skin_id;
+        }
+        RedemptionKind::RaffleEntry => {
+            inventory.raffle_entries = inventory
+                .raffle_entries
+                .checked_add(quantity)
+                .ok_or(GameError::InvalidAmount)?;
+        }
+        RedemptionKind::None => return err!(GameError::InvalidRedemption),
+    }
+    inventory.redeemed[option_id as usize] = redeemed;
+
+    let stock = &mut ctx.accounts.config.redemption_options[option_id as usize].stock;
+    *stock -= quantity;
+
+    emit!(Redeemed {
+        player: inventory.player,
+        option_id,
+        kind: option.kind,
+        quantity,
+        trashcoin_spent: price,
+        burned: option.burn,
+        junk_paid,
+        skin_id: option.skin_id,
+        stock_remaining: *stock,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetRedemptionOptions<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + std::mem::size_of::<Inventory>(),
+        seeds = [b"inventory", player.key().as_ref()],
+        bump,
+    )]
+    pub inventory: Box<Account<'info, Inventory>>,
+    #[account(mut, address = config.trashcoin_mint)]
+    pub trashcoin_mint: Box<Account<'info, Mint>>,
+    #[account(mut, token::mint = config.trashcoin_mint)]
+    pub player_trashcoin_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.trashcoin_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_trashcoin_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RedemptionKind {
+    /// Empty shop slot
+    #[default]
+    None,
+    /// `junk_amount` JUNK paid from the house vault per unit
+    Junk,
+    /// Cosmetic coin skin `skin_id`, recorded in the inventory
+    Skin,
+    /// One raffle entry per unit, recorded in the inventory
+    RaffleEntry,
+}
+
+/// One row of the config's redemption shop
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RedemptionOption {
+    pub kind: RedemptionKind,
+    /// TRASHCOIN base units per unit
+    pub price_trashcoin: u64,
+    /// Burn the TRASHCOIN spent instead of returning it to the vault
+    pub burn: bool,
+    /// JUNK base units paid per unit, for `Junk`
+    pub junk_amount: u64,
+    /// Skin granted, for `Skin`
+    pub skin_id: u8,
+    /// Units left to redeem across all players
+    pub stock: u32,
+    /// Most units one wallet can ever redeem; 0 = no limit
+    pub wallet_limit: u32,
+}
+
+impl RedemptionOption {
+    pub fn validate(&self) -> Result<()> {
+        match self.kind {
+            RedemptionKind::None => return Ok(()),
+            RedemptionKind::Junk => {
+                require!(self.junk_amount > 0, GameError::InvalidRedemption)
+            }
+            RedemptionKind::Skin => {
+                require!(self.skin_id < MAX_SKINS, GameError::InvalidRedemption)
+            }
+            RedemptionKind::RaffleEntry => {}
+        }
+        require!(self.price_trashcoin > 0, GameError::InvalidRedemption);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct RedemptionOptionsUpdated {
+    pub options: [RedemptionOption; MAX_REDEMPTION_OPTIONS],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Redeemed {
+    pub player: Pubkey,
+    pub option_id: u8,
+    pub kind: RedemptionKind,
+    pub quantity: u32,
+    pub trashcoin_spent: u64,
+    pub burned: bool,
+    pub junk_paid: u64,
+    pub skin_id: u8,
+    pub stock_remaining: u32,
+    pub timestamp: i64,
+}
@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{instruction_flags, GameConfig, GameError, GameState};
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Register a partner token the game can award (admin only). The vault is
+/// the vault PDA's associated token account for the mint, under either the
+/// SPL Token or the Token-2022 program. TRASHCOIN can't be registered, as
+/// its emission is capped separately.
+pub(crate) fn register_reward_vault(
+    ctx: Context<RegisterRewardVault>,
+    reward_id: u64,
+    emission_budget: u64,
+) -> Result<()> {
+    let reward_vault = &mut ctx.accounts.reward_vault;
+    reward_vault.reward_id = reward_id;
+    reward_vault.mint = ctx.accounts.mint.key();
+    reward_vault.vault = ctx.accounts.vault.key();
+    reward_vault.token_program = ctx.accounts.token_program.key();
+    reward_vault.emission_budget = emission_budget;
+    reward_vault.total_emitted = 0;
+    reward_vault.enabled = true;
+    reward_vault.bump = ctx.bumps.reward_vault;
+
+    emit!(RewardVaultRegistered {
+        reward_id,
+        mint: reward_vault.mint,
+        vault: reward_vault.vault,
+        token_program: reward_vault.token_program,
+        emission_budget,
+    });
+
+    Ok(())
+}
+
+/// Change a reward's lifetime budget or turn it on and off (admin only)
+pub(crate) fn set_reward_vault(
+    ctx: Context<SetRewardVault>,
+    emission_budget: u64,
+    enabled: bool,
+) -> Result<()> {
+    let reward_vault = &mut ctx.accounts.reward_vault;
+    reward_vault.emission_budget = emission_budget;
+    reward_vault.enabled = enabled;
+
+    emit!(RewardVaultUpdated {
+        reward_id: reward_vault.reward_id,
+        emission_budget,
+        enabled,
+        total_emitted: reward_vault.total_emitted,
+    });
+
+    Ok(())
+}
+
+/// Pay `amount` of a registered reward token out of its vault, attested by
+/// the game authority and limited by the reward's emission budget
+pub(crate) fn award_token(ctx: Context<AwardToken>, reward_id: u64, amount: u64) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::AWARD_TOKEN)?;
+    require!(amount > 0, GameError::InvalidAmount);
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.game_state.require_active(now)?;
+
+    let reward_vault = &mut ctx.accounts.reward_vault;
+    require!(reward_vault.enabled, GameError::RewardVaultDisabled);
+    reward_vault.record_emission(amount)?;
+
+    let seeds = &[b"vault".as_ref(), &[ctx.bumps.vault_authority]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vault.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.player_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    emit!(TokenAwarded {
+        player: ctx.accounts.player.key(),
+        reward_id,
+        mint: ctx.accounts.mint.key(),
+        amount,
+        total_emitted: ctx.accounts.reward_vault.total_emitted,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(reward_id: u64)]
+pub struct RegisterRewardVault<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<RewardVault>(),
+        seeds = [b"reward_vault", reward_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mint::token_program = token_program,
+        constraint = mint.key() != config.trashcoin_mint @ GameError::InvalidRewardVault,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardVault<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"reward_vault", reward_vault.reward_id.to_le_bytes().as_ref()],
+        bump = reward_vault.bump,
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(reward_id: u64)]
+pub struct AwardToken<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"reward_vault", reward_id.to_le_bytes().as_ref()],
+        bump = reward_vault.bump,
+        has_one = mint @ GameError::InvalidRewardVault,
+        has_one = vault @ GameError::InvalidRewardVault,
+        has_one = token_program @ GameError::InvalidRewardVault,
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub player: Signer<'info>,
+    #[account(address = config.game_authority @ GameError::Unauthorized)]
+    pub game_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// A partner token the game can award, and how much of it may go out
+#[account]
+pub struct RewardVault {
+    pub reward_id: u64,
+    pub mint: Pubkey,
+    /// Vault PDA's associated token account for `mint`
+    pub vault: Pubkey,
+    /// SPL Token or Token-2022, whichever owns `mint`
+    pub token_program: Pubkey,
+    /// Most of the token ever awarded, in base units
+    pub emission_budget: u64,
+    pub total_emitted: u64,
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+impl RewardVault {
+    /// Count `amount` against the emission budget
+    pub fn record_emission(&mut self, amount: u64) -> Result<()> {
+        let total = self
+            .total_emitted
+            .checked_add(amount)
+            .ok_or(GameError::InvalidAmount)?;
+        require!(
+            total <= self.emission_budget,
+            GameError::RewardBudgetExhausted
+        );
+        self.total_emitted = total;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct RewardVaultRegistered {
+    pub reward_id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub token_program: Pubkey,
+    pub emission_budget: u64,
+}
+
+#[event]
+pub struct RewardVaultUpdated {
+    pub reward_id: u64,
+    pub emission_budget: u64,
+    pub enabled: bool,
+    pub total_emitted: u64,
+}
+
+#[event]
+pub struct TokenAwarded {
+    pub player: Pubkey,
+    pub reward_id: u64,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub total_emitted: u64,
+    pub timestamp: i64,
+}
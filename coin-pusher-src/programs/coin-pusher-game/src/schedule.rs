@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+
+use crate::{GameConfig, GameError, BPS_DENOMINATOR};
+
+/// Number of entries in `Schedule::windows`
+pub const MAX_SCHEDULE_WINDOWS: usize = 8;
+
+/// Length of a recurring window's day, in seconds (days are UTC)
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the promotion schedule (admin only)
+pub(crate) fn set_schedule(
+    ctx: Context<SetSchedule>,
+    windows: [ScheduleWindow; MAX_SCHEDULE_WINDOWS],
+) -> Result<()> {
+    for window in windows.iter() {
+        window.validate()?;
+    }
+    ctx.accounts.schedule.windows = windows;
+
+    emit!(ScheduleUpdated {
+        windows,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetSchedule<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"schedule"],
+        bump = schedule.bump,
+    )]
+    pub schedule: Account<'info, Schedule>,
+    pub admin: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// A promotion period, either a fixed time range or a range that recurs
+/// every day. A zero `reward_multiplier_bps` marks the slot as unused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScheduleWindow {
+    /// Start (inclusive): unix time, or seconds past midnight UTC if `daily`
+    pub start: i64,
+    /// End (exclusive), in the same units as `start`. A daily window that
+    /// ends before it starts runs over midnight.
+    pub end: i64,
+    pub daily: bool,
+    /// Multiplier on collected coin rewards (10_000 = 1x)
+    pub reward_multiplier_bps: u16,
+    /// Discount on the cost of dropping a coin
+    pub drop_discount_bps: u16,
+}
+
+impl ScheduleWindow {
+    pub fn validate(&self) -> Result<()> {
+        if !self.is_enabled() {
+            require!(self.drop_discount_bps == 0, GameError::InvalidSchedule);
+            return Ok(());
+        }
+        // Promotions only ever favour the player
+        require!(
+            self.reward_multiplier_bps as u64 >= BPS_DENOMINATOR
+                && self.drop_discount_bps as u64 <= BPS_DENOMINATOR,
+            GameError::InvalidSchedule
+        );
+        if self.daily {
+            require!(
+                (0..SECONDS_PER_DAY).contains(&self.start)
+                    && (0..=SECONDS_PER_DAY).contains(&self.end)
+                    && self.start != self.end,
+                GameError::InvalidSchedule
+            );
+        } else {
+            require!(self.start < self.end, GameError::InvalidSchedule);
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.reward_multiplier_bps > 0
+    }
+
+    pub fn contains(&self, now: i64) -> bool {
+        if !self.daily {
+            return self.start <= now && now < self.end;
+        }
+        let time_of_day = now.rem_euclid(SECONDS_PER_DAY);
+        if self.start < self.end {
+            self.start <= time_of_day && time_of_day < self.end
+        } else {
+            time_of_day >= self.start || time_of_day < self.end
+        }
+    }
+
+    pub fn apply_reward_multiplier(&self, reward: u64) -> u64 {
+        (reward as u128 * self.reward_multiplier_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    pub fn apply_drop_discount(&self, cost: u64) -> u64 {
+        let discount = cost as u128 * self.drop_discount_bps as u128 / BPS_DENOMINATOR as u128;
+        cost - discount as u64
+    }
+}
+
+/// Happy hours and other promotions, created alongside the config
+#[account]
+pub struct Schedule {
+    pub windows: [ScheduleWindow; MAX_SCHEDULE_WINDOWS],
+    pub bump: u8,
+}
+
+impl Schedule {
+    /// The first enabled window containing `now`; earlier slots take
+    /// priority when windows overlap
+    pub fn active_window(&self, now: i64) -> Option<ScheduleWindow> {
+        self.windows
+            .iter()
+            .find(|window| window.is_enabled() && window.contains(now))
+            .copied()
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct ScheduleUpdated {
+    pub windows: [ScheduleWindow; MAX_SCHEDULE_WINDOWS],
+    pub timestamp: i64,
+}
@@ -0,0 +1,275 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::{instruction_flags, GameConfig, GameError, GameState};
+
+/// Most coins a snapshot can hold (matches `PHYSICS.MAX_COINS` in the client)
+pub const MAX_SNAPSHOT_COINS: usize = 800;
+
+/// Fixed-point scale of snapshot coordinates: 1 unit = 1/1000 world units
+pub const SNAPSHOT_POSITION_SCALE: f32 = 1_000.0;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Start writing a new snapshot of `coin_count` coins, replacing the
+/// committed one. Its hash is kept so the next save chains onto it.
+pub(crate) fn begin_snapshot(
+    ctx: Context<BeginSnapshot>,
+    session_id: u64,
+    pusher_phase: u16,
+    coin_count: u16,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::SAVE_SNAPSHOT)?;
+    require!(
+        coin_count as usize <= MAX_SNAPSHOT_COINS,
+        GameError::SnapshotTooLarge
+    );
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    if snapshot.player == Pubkey::default() {
+        snapshot.player = ctx.accounts.player.key();
+        snapshot.bump = ctx.bumps.snapshot;
+    }
+    snapshot.session_id = session_id;
+    snapshot.pusher_phase = pusher_phase;
+    snapshot.coin_count = coin_count;
+    snapshot.committed = false;
+    snapshot.coins.clear();
+    snapshot.updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+/// Append a chunk of coins at `offset`. Chunks must be written in order, so
+/// a retried chunk that already landed is rejected instead of duplicated.
+pub(crate) fn write_snapshot(
+    ctx: Context<WriteSnapshot>,
+    offset: u16,
+    coins: Vec<SnapshotCoin>,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::SAVE_SNAPSHOT)?;
+    let snapshot = &mut ctx.accounts.snapshot;
+    require!(!snapshot.committed, GameError::SnapshotNotWritable);
+    require!(
+        offset as usize == snapshot.coins.len(),
+        GameError::SnapshotOutOfOrder
+    );
+    require!(
+        snapshot.coins.len() + coins.len() <= snapshot.coin_count as usize,
+        GameError::SnapshotTooLarge
+    );
+
+    snapshot.coins.extend(coins);
+    snapshot.updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+/// Commit the written coins, chaining the snapshot hash to the previous one
+pub(crate) fn save_snapshot(ctx: Context<SaveSnapshot>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::SAVE_SNAPSHOT)?;
+    let game_state = &ctx.accounts.game_state;
+    let snapshot = &mut ctx.accounts.snapshot;
+    require!(!snapshot.committed, GameError::SnapshotNotWritable);
+    require!(
+        snapshot.coins.len() == snapshot.coin_count as usize,
+        GameError::SnapshotIncomplete
+    );
+
+    snapshot.sequence = snapshot.sequence.saturating_add(1);
+    snapshot.state_last_updated = game_state.last_updated;
+    snapshot.previous_hash = snapshot.hash;
+    snapshot.hash = snapshot.compute_hash();
+    snapshot.committed = true;
+    snapshot.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(SnapshotSaved {
+        player: snapshot.player,
+        session_id: snapshot.session_id,
+        sequence: snapshot.sequence,
+        coin_count: snapshot.coin_count,
+        state_last_updated: snapshot.state_last_updated,
+        hash: snapshot.hash,
+        timestamp: snapshot.updated_at,
+    });
+
+    Ok(())
+}
+
+/// Delete the player's snapshot and reclaim its rent
+pub(crate) fn close_snapshot(_ctx: Context<CloseSnapshot>) -> Result<()> {
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct BeginSnapshot<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = MachineSnapshot::SPACE,
+        seeds = [b"snapshot", player.key().as_ref()],
+        bump,
+    )]
+    pub snapshot: Box<Account<'info, MachineSnapshot>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteSnapshot<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"snapshot", player.key().as_ref()],
+        bump = snapshot.bump,
+        has_one = player,
+    )]
+    pub snapshot: Box<Account<'info, MachineSnapshot>>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SaveSnapshot<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"snapshot", player.key().as_ref()],
+        bump = snapshot.bump,
+        has_one = player,
+    )]
+    pub snapshot: Box<Account<'info, MachineSnapshot>>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSnapshot<'info> {
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"snapshot", player.key().as_ref()],
+        bump = snapshot.bump,
+        has_one = player,
+    )]
+    pub snapshot: Box<Account<'info, MachineSnapshot>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// One coin on the playfield, in `SNAPSHOT_POSITION_SCALE` fixed point
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotCoin {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+    /// Index into `GameConfig::coin_types`
+    pub coin_type: u8,
+}
+
+impl SnapshotCoin {
+    pub const SIZE: usize = 2 + 2 + 2 + 1;
+}
+
+/// The player's playfield, so a game can be resumed on another device
+#[account]
+pub struct MachineSnapshot {
+    pub player: Pubkey,
+    /// Game session the snapshot was taken in, chosen by the client
+    pub session_id: u64,
+    /// Number of committed snapshots so far
+    pub sequence: u64,
+    /// Pusher position within its period, as a fraction of `u16::MAX`
+    pub pusher_phase: u16,
+    /// Coins the snapshot being written will hold
+    pub coin_count: u16,
+    /// Whether `coins` matches `hash`; false while a write is in progress
+    pub committed: bool,
+    /// `GameState::last_updated` when the snapshot was saved. A newer game
+    /// state means the playfield moved on without being saved.
+    pub state_last_updated: i64,
+    /// Hash of the last committed snapshot, chained to the one before it
+    pub hash: [u8; 32],
+    /// `hash` of the snapshot before this one
+    pub previous_hash: [u8; 32],
+    pub updated_at: i64,
+    pub bump: u8,
+    pub coins: Vec<SnapshotCoin>,
+}
+
+impl MachineSnapshot {
+    /// Fixed-size fields, discriminator excluded
+    const HEADER: usize = 32 + 8 + 8 + 2 + 2 + 1 + 8 + 32 + 32 + 8 + 1;
+    pub const SPACE: usize = 8 + Self::HEADER + 4 + MAX_SNAPSHOT_COINS * SnapshotCoin::SIZE;
+
+    /// Hash of the current contents chained to `previous_hash`. Matches
+    /// `hash` for a committed snapshot.
+    pub fn compute_hash(&self) -> [u8; 32] {
+        let mut coins = Vec::with_capacity(self.coins.len() * SnapshotCoin::SIZE);
+        for coin in &self.coins {
+            coins.extend_from_slice(&coin.x.to_le_bytes());
+            coins.extend_from_slice(&coin.y.to_le_bytes());
+            coins.extend_from_slice(&coin.z.to_le_bytes());
+            coins.push(coin.coin_type);
+        }
+        hashv(&[
+            &self.previous_hash,
+            self.player.as_ref(),
+            &self.session_id.to_le_bytes(),
+            &self.sequence.to_le_bytes(),
+            &self.state_last_updated.to_le_bytes(),
+            &self.pusher_phase.to_le_bytes(),
+            &coins,
+        ])
+        .to_bytes()
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct SnapshotSaved {
+    pub player: Pubkey,
+    pub session_id: u64,
+    pub sequence: u64,
+    pub coin_count: u16,
+    pub state_last_updated: i64,
+    pub hash: [u8; 32],
+    pub timestamp: i64,
+}
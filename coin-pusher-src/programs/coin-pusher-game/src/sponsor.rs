@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+
+use crate::{instruction_flags, GameConfig, GameError, GameInitialized, GameState, GlobalStats};
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Create or update the sponsor's budget. Limits are lifetime totals, so
+/// lowering one below what has already been spent stops new sponsorships.
+pub(crate) fn set_sponsor_budget(
+    ctx: Context<SetSponsorBudget>,
+    max_accounts: u32,
+    max_lamports: u64,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::SPONSOR_BUDGET)?;
+
+    let budget = &mut ctx.accounts.sponsor_budget;
+    if budget.sponsor == Pubkey::default() {
+        budget.sponsor = ctx.accounts.sponsor.key();
+        budget.bump = ctx.bumps.sponsor_budget;
+    }
+    budget.max_accounts = max_accounts;
+    budget.max_lamports = max_lamports;
+
+    emit!(SponsorBudgetUpdated {
+        sponsor: budget.sponsor,
+        max_accounts,
+        max_lamports,
+        accounts_sponsored: budget.accounts_sponsored,
+        lamports_sponsored: budget.lamports_sponsored,
+    });
+
+    Ok(())
+}
+
+/// Start a game whose rent is paid by `sponsor` out of its budget, so
+/// wallets without SOL can play. The rent goes back to the sponsor when
+/// the game is closed.
+pub(crate) fn initialize_sponsored_game(
+    ctx: Context<InitializeSponsoredGame>,
+    initial_balance: u64,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::INITIALIZE_GAME)?;
+
+    let player = ctx.accounts.player.key();
+    let sponsor = ctx.accounts.sponsor.key();
+    let rent = ctx.accounts.game_state.to_account_info().lamports();
+    let budget = &mut ctx.accounts.sponsor_budget;
+    budget.charge(rent)?;
+    ctx.accounts.global_stats.record_player();
+
+    let now = Clock::get()?.unix_timestamp;
+    let game_state = &mut ctx.accounts.game_state;
+    game_state.start(player, initial_balance, ctx.bumps.game_state, now);
+    game_state.sponsor = sponsor;
+
+    emit!(GameInitialized {
+        player,
+        initial_balance,
+        timestamp: now,
+    });
+    emit!(GameSponsored {
+        player,
+        sponsor,
+        rent,
+        accounts_sponsored: budget.accounts_sponsored,
+        lamports_sponsored: budget.lamports_sponsored,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Delete the player's game. Rent goes to whoever paid it: the sponsor for
+/// sponsored games, the player otherwise. Withdrawable credits must be
+/// cashed out or gifted first; promotional credits are forfeited. Like
+/// cashing out, this stays open while the program is paused.
+pub(crate) fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let game_state = &ctx.accounts.game_state;
+    // Closing would clear the status, and a new game brings fresh credits
+    game_state.require_active(now)?;
+    require!(
+        game_state.withdrawable_balance() == 0,
+        GameError::GameNotEmpty
+    );
+
+    emit!(GameClosed {
+        player: game_state.player,
+        rent_recipient: ctx.accounts.rent_recipient.key(),
+        rent: game_state.to_account_info().lamports(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetSponsorBudget<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        space = 8 + std::mem::size_of::<SponsorBudget>(),
+        seeds = [b"sponsor_budget", sponsor.key().as_ref()],
+        bump,
+    )]
+    pub sponsor_budget: Account<'info, SponsorBudget>,
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeSponsoredGame<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        init,
+        payer = sponsor,
+        space = 8 + std::mem::size_of::<GameState>(),
+        seeds = [b"game_state", player.key().as_ref()],
+        bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"sponsor_budget", sponsor.key().as_ref()],
+        bump = sponsor_budget.bump,
+        has_one = sponsor,
+    )]
+    pub sponsor_budget: Account<'info, SponsorBudget>,
+    pub player: Signer<'info>,
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseGame<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        close = rent_recipient,
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+        has_one = player,
+    )]
+    pub game_state: Account<'info, GameState>,
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        address = game_state.rent_recipient() @ GameError::Unauthorized,
+    )]
+    /// CHECK: only receives lamports; must be whoever paid the rent
+    pub rent_recipient: UncheckedAccount<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// How much account rent a sponsor is willing to pay for new players
+#[account]
+pub struct SponsorBudget {
+    pub sponsor: Pubkey,
+    /// Most games the sponsor will pay for, ever
+    pub max_accounts: u32,
+    /// Most lamports of rent the sponsor will pay, ever
+    pub max_lamports: u64,
+    pub accounts_sponsored: u32,
+    pub lamports_sponsored: u64,
+    pub bump: u8,
+}
+
+impl SponsorBudget {
+    /// Record one more sponsored account costing `rent` lamports
+    pub fn charge(&mut self, rent: u64) -> Result<()> {
+        let accounts = self.accounts_sponsored.saturating_add(1);
+        let lamports = self.lamports_sponsored.saturating_add(rent);
+        require!(
+            accounts <= self.max_accounts && lamports <= self.max_lamports,
+            GameError::SponsorBudgetExhausted
+        );
+        self.accounts_sponsored = accounts;
+        self.lamports_sponsored = lamports;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct SponsorBudgetUpdated {
+    pub sponsor: Pubkey,
+    pub max_accounts: u32,
+    pub max_lamports: u64,
+    pub accounts_sponsored: u32,
+    pub lamports_sponsored: u64,
+}
+
+#[event]
+pub struct GameSponsored {
+    pub player: Pubkey,
+    pub sponsor: Pubkey,
+    pub rent: u64,
+    pub accounts_sponsored: u32,
+    pub lamports_sponsored: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GameClosed {
+    pub player: Pubkey,
+    pub rent_recipient: Pubkey,
+    pub rent: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,347 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::{instruction_flags, GameConfig, GameError, GlobalStats, BPS_DENOMINATOR};
+
+/// Number of lock tiers in `GameConfig::stake_tiers`
+pub const MAX_STAKE_TIERS: usize = 4;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Replace the lock tier table (admin only)
+pub(crate) fn set_stake_tiers(
+    ctx: Context<SetStakeTiers>,
+    tiers: [StakeTier; MAX_STAKE_TIERS],
+) -> Result<()> {
+    for tier in tiers.iter() {
+        tier.validate()?;
+    }
+    ctx.accounts.config.stake_tiers = tiers;
+
+    emit!(StakeTiersUpdated {
+        tiers,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Lock `amount` JUNK in the player's stake vault under `tier`
+pub(crate) fn stake(ctx: Context<Stake>, amount: u64, tier: u8) -> Result<()> {
+    ctx.accounts.config.require_enabled(instruction_flags::STAKE)?;
+    require!(amount > 0, GameError::InvalidAmount);
+
+    let stake_tier = ctx.accounts.config.stake_tier(tier)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.player_junk_account.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.player.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+
+    let position = &mut ctx.accounts.stake_position;
+    if position.player == Pubkey::default() {
+        position.player = ctx.accounts.player.key();
+        position.bump = ctx.bumps.stake_position;
+    }
+    position.amount = position.amount.saturating_add(amount);
+    require!(
+        position.amount >= stake_tier.min_amount,
+        GameError::StakeBelowTierMinimum
+    );
+    position.tier = tier;
+    position.lock_end = position.lock_end.max(now.saturating_add(stake_tier.lock_duration));
+    position.last_updated = now;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.total_staked = global_stats.total_staked.saturating_add(amount);
+
+    emit!(Staked {
+        player: position.player,
+        amount,
+        tier,
+        staked_amount: position.amount,
+        lock_end: position.lock_end,
+        total_staked: global_stats.total_staked,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Withdraw `amount` JUNK once the lock has ended. Not pausable.
+pub(crate) fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    require!(amount > 0, GameError::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let position = &ctx.accounts.stake_position;
+    require!(now >= position.lock_end, GameError::StakeLocked);
+    require!(position.amount >= amount, GameError::InsufficientBalance);
+
+    let player_key = ctx.accounts.player.key();
+    let seeds = &[b"stake".as_ref(), player_key.as_ref(), &[position.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.stake_vault.to_account_info(),
+        to: ctx.accounts.player_junk_account.to_account_info(),
+        authority: ctx.accounts.stake_position.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+    let position = &mut ctx.accounts.stake_position;
+    position.amount -= amount;
+    if position.amount == 0 {
+        position.tier = 0;
+    }
+    position.last_updated = now;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.total_staked = global_stats.total_staked.saturating_sub(amount);
+
+    emit!(Unstaked {
+        player: player_key,
+        amount,
+        staked_amount: position.amount,
+        total_staked: global_stats.total_staked,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Move an existing stake to `tier`, pushing the lock end out if needed
+pub(crate) fn extend_lock(ctx: Context<ExtendLock>, tier: u8) -> Result<()> {
+    ctx.accounts.config.require_enabled(instruction_flags::EXTEND_LOCK)?;
+
+    let stake_tier = ctx.accounts.config.stake_tier(tier)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let position = &mut ctx.accounts.stake_position;
+    require!(position.amount > 0, GameError::InsufficientBalance);
+    require!(
+        position.amount >= stake_tier.min_amount,
+        GameError::StakeBelowTierMinimum
+    );
+    position.tier = tier;
+    position.lock_end = position.lock_end.max(now.saturating_add(stake_tier.lock_duration));
+    position.last_updated = now;
+
+    emit!(LockExtended {
+        player: position.player,
+        tier,
+        lock_end: position.lock_end,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetStakeTiers<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + std::mem::size_of::<StakePosition>(),
+        seeds = [b"stake", player.key().as_ref()],
+        bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        token::mint = junk_mint,
+        token::authority = stake_position,
+        seeds = [b"stake_vault", player.key().as_ref()],
+        bump,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+    #[account(address = config.junk_mint)]
+    pub junk_mint: Box<Account<'info, Mint>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"stake", player.key().as_ref()],
+        bump = stake_position.bump,
+        has_one = player,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    #[account(
+        mut,
+        seeds = [b"stake_vault", player.key().as_ref()],
+        bump,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = stake_vault.mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"stake", player.key().as_ref()],
+        bump = stake_position.bump,
+        has_one = player,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    pub player: Signer<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// One row of the config's lock tier table. A zero `lock_duration` marks
+/// the slot as unused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StakeTier {
+    /// Lock length in seconds
+    pub lock_duration: i64,
+    /// Minimum staked JUNK (base units) to qualify
+    pub min_amount: u64,
+    /// `collect_coin` reward multiplier; 10_000 = 1x
+    pub reward_multiplier_bps: u16,
+    /// `bump_machine` cost discount; 10_000 = free
+    pub bump_discount_bps: u16,
+}
+
+impl StakeTier {
+    pub fn validate(&self) -> Result<()> {
+        if self.lock_duration == 0 {
+            return Ok(());
+        }
+        require!(self.lock_duration > 0, GameError::InvalidStakeTier);
+        require!(
+            self.reward_multiplier_bps as u64 >= BPS_DENOMINATOR,
+            GameError::InvalidStakeTier
+        );
+        require!(
+            self.bump_discount_bps as u64 <= BPS_DENOMINATOR,
+            GameError::InvalidStakeTier
+        );
+        Ok(())
+    }
+
+    pub fn apply_reward_multiplier(&self, reward: u64) -> u64 {
+        (reward as u128 * self.reward_multiplier_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    pub fn apply_bump_discount(&self, cost: u64) -> u64 {
+        let discount = cost as u128 * self.bump_discount_bps as u128 / BPS_DENOMINATOR as u128;
+        cost - discount as u64
+    }
+}
+
+#[account]
+pub struct StakePosition {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub tier: u8,
+    pub lock_end: i64,
+    pub last_updated: i64,
+    pub bump: u8,
+}
+
+impl StakePosition {
+    /// The tier whose boosts apply right now, if the stake is still locked
+    pub fn active_tier(&self, config: &GameConfig, now: i64) -> Option<StakeTier> {
+        if self.amount == 0 || now >= self.lock_end {
+            return None;
+        }
+        config.stake_tier(self.tier).ok()
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct Staked {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub tier: u8,
+    pub staked_amount: u64,
+    pub lock_end: i64,
+    pub total_staked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub staked_amount: u64,
+    pub total_staked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LockExtended {
+    pub player: Pubkey,
+    pub tier: u8,
+    pub lock_end: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeTiersUpdated {
+    pub tiers: [StakeTier; MAX_STAKE_TIERS],
+    pub timestamp: i64,
+}
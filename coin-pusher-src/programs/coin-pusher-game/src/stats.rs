@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::{instruction_flags, GameConfig, GameError, GlobalStats, SECONDS_PER_DAY};
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Copy the running totals into today's `StatsDay`. Anyone can call it; a
+/// later call on the same day overwrites the earlier copy, so the last
+/// snapshot of a day is its closing figures.
+pub(crate) fn stats_snapshot(ctx: Context<StatsSnapshot>, day: i64) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_enabled(instruction_flags::STATS_SNAPSHOT)?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        day == now.div_euclid(SECONDS_PER_DAY),
+        GameError::InvalidStatsDay
+    );
+
+    let stats = &ctx.accounts.global_stats;
+    let stats_day = &mut ctx.accounts.stats_day;
+    stats_day.day = day;
+    stats_day.taken_at = now;
+    stats_day.total_players = stats.total_players;
+    stats_day.total_drops = stats.total_drops;
+    stats_day.total_bumps = stats.total_bumps;
+    stats_day.total_coins_collected = stats.total_coins_collected;
+    stats_day.total_trashcoin_awarded = stats.total_trashcoin_awarded;
+    stats_day.total_junk_wagered = stats.total_junk_wagered;
+    stats_day.total_junk_paid_out = stats.total_junk_paid_out;
+    stats_day.total_burned = stats.total_burned;
+    stats_day.total_staked = stats.total_staked;
+    stats_day.bump = ctx.bumps.stats_day;
+
+    emit!(StatsSnapshotTaken {
+        day,
+        total_players: stats.total_players,
+        total_drops: stats.total_drops,
+        total_junk_wagered: stats.total_junk_wagered,
+        total_junk_paid_out: stats.total_junk_paid_out,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(day: i64)]
+pub struct StatsSnapshot<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + std::mem::size_of::<StatsDay>(),
+        seeds = [b"stats_day", day.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub stats_day: Account<'info, StatsDay>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// `GlobalStats` as of the last snapshot taken on `day`
+#[account]
+pub struct StatsDay {
+    /// Day (unix time / 86400) the snapshot belongs to
+    pub day: i64,
+    pub taken_at: i64,
+    pub total_players: u64,
+    pub total_drops: u64,
+    pub total_bumps: u64,
+    pub total_coins_collected: u64,
+    pub total_trashcoin_awarded: u64,
+    pub total_junk_wagered: u64,
+    pub total_junk_paid_out: u64,
+    pub total_burned: u64,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct StatsSnapshotTaken {
+    pub day: i64,
+    pub total_players: u64,
+    pub total_drops: u64,
+    pub total_junk_wagered: u64,
+    pub total_junk_paid_out: u64,
+    pub timestamp: i64,
+}
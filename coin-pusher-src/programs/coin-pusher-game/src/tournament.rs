@@ -0,0 +1,492 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::{instruction_flags, GameConfig, GameError, GameState, BPS_DENOMINATOR};
+
+/// Number of paid ranks (and leaderboard slots) per tournament
+pub const MAX_PAYOUT_RANKS: usize = 10;
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+/// Create a tournament (admin only)
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_tournament(
+    ctx: Context<CreateTournament>,
+    tournament_id: u64,
+    entry_fee: u64,
+    start_time: i64,
+    end_time: i64,
+    max_players: u32,
+    rake_bps: u16,
+    payout_bps: [u16; MAX_PAYOUT_RANKS],
+) -> Result<()> {
+    require!(end_time > start_time, GameError::InvalidTournamentWindow);
+    require!(
+        end_time > Clock::get()?.unix_timestamp,
+        GameError::InvalidTournamentWindow
+    );
+    require!(max_players > 0, GameError::InvalidAmount);
+    require!(rake_bps as u64 <= BPS_DENOMINATOR, GameError::InvalidPayoutTable);
+    let payout_total: u64 = payout_bps.iter().map(|bps| *bps as u64).sum();
+    require!(payout_total == BPS_DENOMINATOR, GameError::InvalidPayoutTable);
+
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.tournament_id = tournament_id;
+    tournament.entry_fee = entry_fee;
+    tournament.start_time = start_time;
+    tournament.end_time = end_time;
+    tournament.max_players = max_players;
+    tournament.player_count = 0;
+    tournament.rake_bps = rake_bps;
+    tournament.payout_bps = payout_bps;
+    tournament.pot = 0;
+    tournament.leaderboard = [LeaderboardEntry::default(); MAX_PAYOUT_RANKS];
+    tournament.prizes = [0; MAX_PAYOUT_RANKS];
+    tournament.claimed = 0;
+    tournament.finalized = false;
+    tournament.bump = ctx.bumps.tournament;
+
+    emit!(TournamentCreated {
+        tournament: tournament.key(),
+        tournament_id,
+        entry_fee,
+        start_time,
+        end_time,
+        max_players,
+        rake_bps,
+    });
+
+    Ok(())
+}
+
+/// Pay the entry fee and open a tournament-scoped score PDA
+pub(crate) fn enter_tournament(ctx: Context<EnterTournament>) -> Result<()> {
+    ctx.accounts.config.require_enabled(instruction_flags::ENTER_TOURNAMENT)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.game_state.require_active(now)?;
+    let tournament = &ctx.accounts.tournament;
+    require!(now < tournament.end_time, GameError::TournamentClosed);
+    require!(
+        tournament.player_count < tournament.max_players,
+        GameError::TournamentFull
+    );
+
+    let entry_fee = tournament.entry_fee;
+    if entry_fee > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.player_junk_account.to_account_info(),
+            to: ctx.accounts.tournament_vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), entry_fee)?;
+    }
+
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.player_count += 1;
+    tournament.pot = tournament.pot.saturating_add(entry_fee);
+
+    let entry = &mut ctx.accounts.tournament_entry;
+    entry.tournament = tournament.key();
+    entry.player = ctx.accounts.player.key();
+    entry.score = 0;
+    entry.bump = ctx.bumps.tournament_entry;
+
+    emit!(TournamentEntered {
+        tournament: tournament.key(),
+        player: entry.player,
+        entry_fee,
+        player_count: tournament.player_count,
+        pot: tournament.pot,
+    });
+
+    Ok(())
+}
+
+/// Lock the leaderboard after the window closes and send the rake (plus
+/// shares of unfilled ranks) to the house vault. Permissionless.
+pub(crate) fn finalize_tournament(ctx: Context<FinalizeTournament>) -> Result<()> {
+    let tournament = &ctx.accounts.tournament;
+    require!(!tournament.finalized, GameError::TournamentFinalized);
+    require!(
+        Clock::get()?.unix_timestamp >= tournament.end_time,
+        GameError::TournamentNotEnded
+    );
+
+    let pot = tournament.pot;
+    let rake = (pot as u128 * tournament.rake_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let distributable = pot - rake;
+
+    let mut prizes = [0u64; MAX_PAYOUT_RANKS];
+    for (rank, prize) in prizes.iter_mut().enumerate() {
+        if tournament.leaderboard[rank].is_filled() {
+            *prize = (distributable as u128 * tournament.payout_bps[rank] as u128
+                / BPS_DENOMINATOR as u128) as u64;
+        }
+    }
+    let house_amount = pot - prizes.iter().sum::<u64>();
+
+    if house_amount > 0 {
+        let id_bytes = tournament.tournament_id.to_le_bytes();
+        let seeds = &[b"tournament".as_ref(), id_bytes.as_ref(), &[tournament.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.tournament_vault.to_account_info(),
+            to: ctx.accounts.vault_junk_account.to_account_info(),
+            authority: ctx.accounts.tournament.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            house_amount,
+        )?;
+    }
+
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.prizes = prizes;
+    tournament.finalized = true;
+
+    emit!(TournamentFinalized {
+        tournament: tournament.key(),
+        pot,
+        house_amount,
+        leaderboard: tournament.leaderboard,
+        prizes,
+    });
+
+    Ok(())
+}
+
+/// Pay out the prize for `rank` to the player holding it. Not pausable.
+pub(crate) fn claim_tournament_prize(ctx: Context<ClaimTournamentPrize>, rank: u8) -> Result<()> {
+    ctx.accounts.game_state.require_active(Clock::get()?.unix_timestamp)?;
+
+    let tournament = &ctx.accounts.tournament;
+    require!(tournament.finalized, GameError::TournamentNotEnded);
+
+    let rank_index = rank as usize;
+    require!(rank_index < MAX_PAYOUT_RANKS, GameError::InvalidRank);
+    require_keys_eq!(
+        tournament.leaderboard[rank_index].player,
+        ctx.accounts.player.key(),
+        GameError::Unauthorized
+    );
+    require!(
+        tournament.claimed & (1 << rank_index) == 0,
+        GameError::PrizeAlreadyClaimed
+    );
+
+    let prize = tournament.prizes[rank_index];
+    if prize > 0 {
+        let id_bytes = tournament.tournament_id.to_le_bytes();
+        let seeds = &[b"tournament".as_ref(), id_bytes.as_ref(), &[tournament.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.tournament_vault.to_account_info(),
+            to: ctx.accounts.player_junk_account.to_account_info(),
+            authority: ctx.accounts.tournament.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), prize)?;
+    }
+
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.claimed |= 1 << rank_index;
+
+    emit!(TournamentPrizeClaimed {
+        tournament: tournament.key(),
+        player: ctx.accounts.player.key(),
+        rank,
+        amount: prize,
+    });
+
+    Ok(())
+}
+
+/// Add an attested collection to a tournament score if the window is open.
+/// Called from `collect_coin`.
+pub fn credit_tournament_score(
+    tournament: &mut Tournament,
+    tournament_key: Pubkey,
+    entry: &mut TournamentEntry,
+    player: Pubkey,
+    points: u64,
+    now: i64,
+) -> Result<()> {
+    require_keys_eq!(entry.tournament, tournament_key, GameError::InvalidTournamentEntry);
+    require_keys_eq!(entry.player, player, GameError::InvalidTournamentEntry);
+    if tournament.finalized || now < tournament.start_time || now >= tournament.end_time {
+        return Ok(());
+    }
+
+    entry.score = entry.score.saturating_add(points);
+    tournament.record_score(player, entry.score);
+
+    emit!(TournamentScoreUpdated {
+        tournament: tournament_key,
+        player,
+        score: entry.score,
+    });
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(tournament_id: u64)]
+pub struct CreateTournament<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ GameError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<Tournament>(),
+        seeds = [b"tournament", tournament_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = junk_mint,
+        token::authority = tournament,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump,
+    )]
+    pub tournament_vault: Box<Account<'info, TokenAccount>>,
+    #[account(address = config.junk_mint)]
+    pub junk_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnterTournament<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.tournament_id.to_le_bytes().as_ref()],
+        bump = tournament.bump,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<TournamentEntry>(),
+        seeds = [b"tournament_entry", tournament.key().as_ref(), player.key().as_ref()],
+        bump,
+    )]
+    pub tournament_entry: Account<'info, TournamentEntry>,
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump,
+    )]
+    pub tournament_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = config.junk_mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTournament<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.tournament_id.to_le_bytes().as_ref()],
+        bump = tournament.bump,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump,
+    )]
+    pub tournament_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"vault"],
+        bump,
+    )]
+    /// CHECK: PDA authority for vault
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = config.junk_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_junk_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTournamentPrize<'info> {
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.tournament_id.to_le_bytes().as_ref()],
+        bump = tournament.bump,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        seeds = [b"game_state", player.key().as_ref()],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump,
+    )]
+    pub tournament_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = tournament_vault.mint)]
+    pub player_junk_account: Box<Account<'info, TokenAccount>>,
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey,
+    pub score: u64,
+}
+
+impl LeaderboardEntry {
+    pub fn is_filled(&self) -> bool {
+        self.player != Pubkey::default()
+    }
+}
+
+#[account]
+pub struct Tournament {
+    pub tournament_id: u64,
+    pub entry_fee: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub max_players: u32,
+    pub player_count: u32,
+    /// House share of the pot; 10_000 = everything
+    pub rake_bps: u16,
+    /// Share of the post-rake pot per rank; sums to 10_000
+    pub payout_bps: [u16; MAX_PAYOUT_RANKS],
+    /// Total entry fees collected
+    pub pot: u64,
+    /// Top scores, highest first. Ties keep the earlier scorer ahead.
+    pub leaderboard: [LeaderboardEntry; MAX_PAYOUT_RANKS],
+    /// Prize per rank, fixed at finalization
+    pub prizes: [u64; MAX_PAYOUT_RANKS],
+    /// Bit `rank` set once that prize has been claimed
+    pub claimed: u16,
+    pub finalized: bool,
+    pub bump: u8,
+}
+
+impl Tournament {
+    /// Insert or move `player` in the leaderboard with their new `score`
+    pub fn record_score(&mut self, player: Pubkey, score: u64) {
+        let board = &mut self.leaderboard;
+        let from = board
+            .iter()
+            .position(|entry| entry.player == player)
+            .unwrap_or(MAX_PAYOUT_RANKS - 1);
+        if board[from].player != player && board[from].is_filled() && board[from].score >= score {
+            return;
+        }
+
+        // Scores only grow, so the player can only move up
+        let mut to = from;
+        while to > 0 && (!board[to - 1].is_filled() || board[to - 1].score < score) {
+            to -= 1;
+        }
+        board[to..=from].rotate_right(1);
+        board[to] = LeaderboardEntry { player, score };
+    }
+}
+
+#[account]
+pub struct TournamentEntry {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub score: u64,
+    pub bump: u8,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct TournamentCreated {
+    pub tournament: Pubkey,
+    pub tournament_id: u64,
+    pub entry_fee: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub max_players: u32,
+    pub rake_bps: u16,
+}
+
+#[event]
+pub struct TournamentEntered {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub entry_fee: u64,
+    pub player_count: u32,
+    pub pot: u64,
+}
+
+#[event]
+pub struct TournamentScoreUpdated {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub score: u64,
+}
+
+#[event]
+pub struct TournamentFinalized {
+    pub tournament: Pubkey,
+    pub pot: u64,
+    pub house_amount: u64,
+    pub leaderboard: [LeaderboardEntry; MAX_PAYOUT_RANKS],
+    pub prizes: [u64; MAX_PAYOUT_RANKS],
+}
+
+#[event]
+pub struct TournamentPrizeClaimed {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub rank: u8,
+    pub amount: u64,
+}
@@ -0,0 +1,172 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::InstructionData;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::BumpOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{instruction, BumpCurve, GameError, GameState};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+const ESCALATING: BumpCurve = BumpCurve {
+    base_cost: 10,
+    max_strength: 3,
+    escalation_window: 60,
+    escalation_bps: 5_000,
+};
+
+struct Bumper {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    player: Pubkey,
+}
+
+impl Bumper {
+    fn new() -> Self {
+        let setup = Setup::builder()
+            .players(1)
+            .player_junk(2_000 * JUNK)
+            .initial_balance(2_000)
+            .build();
+        Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+            player: setup.players[0],
+        }
+    }
+
+    fn set_curve(&mut self, curve: BumpCurve) -> Result<(), ProgramError> {
+        let ix = self.client.set_bump_curve(&self.admin, curve);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    /// Bump a second after the last play, so the rate limit never kicks in
+    fn bump(&mut self, strength: u8) -> Result<(), ProgramError> {
+        self.harness.warp_seconds(1);
+        let ix = self.client.bump_machine_with(
+            &self.player,
+            BumpOptions {
+                strength,
+                ..BumpOptions::default()
+            },
+        );
+        self.harness.process(&ix, &[self.player])
+    }
+
+    /// Credits the last bump cost, from its event
+    fn last_cost(&self) -> (u8, u32, u64) {
+        let events = decode_events(&coin_pusher_game::ID, &self.harness.logs());
+        match events.as_slice() {
+            [GameEvent::MachineBumped(bumped)] => {
+                (bumped.strength, bumped.earlier_bumps, bumped.cost)
+            }
+            _ => panic!("expected a MachineBumped event"),
+        }
+    }
+
+    fn junk_balance(&self) -> u64 {
+        let junk_mint = self.client.deployment.junk_mint;
+        self.harness
+            .token_balance(&get_associated_token_address(&self.player, &junk_mint))
+    }
+
+    fn game_state(&self) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, &self.player).0)
+    }
+}
+
+#[test]
+fn cost_grows_with_the_square_of_strength() {
+    let mut bumper = Bumper::new();
+
+    for (strength, cost) in [(1, 50), (2, 200), (5, 1_250)] {
+        let junk = bumper.junk_balance();
+        bumper.bump(strength).unwrap();
+        assert_eq!(bumper.last_cost(), (strength, 0, cost));
+        assert_eq!(junk - bumper.junk_balance(), cost * JUNK);
+    }
+    assert_eq!(bumper.game_state().balance, 2_000 - 1_500);
+}
+
+#[test]
+fn strength_must_be_on_the_curve() {
+    let mut bumper = Bumper::new();
+
+    assert_eq!(
+        bumper.bump(6),
+        Err(game_error(GameError::InvalidBumpStrength))
+    );
+    // The client maps 0 to 1, so send it raw
+    let mut ix = bumper.client.bump_machine(&bumper.player, false);
+    ix.data = instruction::BumpMachine { strength: 0 }.data();
+    assert_eq!(
+        bumper.harness.process(&ix, &[bumper.player]),
+        Err(game_error(GameError::InvalidBumpStrength))
+    );
+    assert_eq!(bumper.game_state().balance, 2_000);
+}
+
+#[test]
+fn repeated_bumps_escalate_within_the_window() {
+    let mut bumper = Bumper::new();
+    bumper.set_curve(ESCALATING).unwrap();
+
+    for (earlier_bumps, cost) in [(0, 10), (1, 15), (2, 20)] {
+        bumper.bump(1).unwrap();
+        assert_eq!(bumper.last_cost(), (1, earlier_bumps, cost));
+    }
+    // Escalation applies on top of the strength curve
+    bumper.bump(3).unwrap();
+    assert_eq!(bumper.last_cost(), (3, 3, 225));
+
+    // A new window starts the count over
+    bumper.harness.warp_seconds(ESCALATING.escalation_window);
+    bumper.bump(1).unwrap();
+    assert_eq!(bumper.last_cost(), (1, 0, 10));
+    assert_eq!(bumper.game_state().bump_window.bumps, 1);
+}
+
+#[test]
+fn only_the_admin_sets_valid_curves() {
+    let mut bumper = Bumper::new();
+
+    for curve in [
+        BumpCurve {
+            base_cost: 0,
+            ..ESCALATING
+        },
+        BumpCurve {
+            max_strength: 0,
+            ..ESCALATING
+        },
+        BumpCurve {
+            escalation_window: -1,
+            ..ESCALATING
+        },
+    ] {
+        assert_eq!(
+            bumper.set_curve(curve),
+            Err(game_error(GameError::InvalidBumpCurve))
+        );
+    }
+
+    let player = bumper.player;
+    let ix = bumper.client.set_bump_curve(&player, ESCALATING);
+    assert_eq!(
+        bumper.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    bumper.set_curve(ESCALATING).unwrap();
+    let events = decode_events(&coin_pusher_game::ID, &bumper.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::BumpCurveUpdated(updated)] if updated.curve == ESCALATING
+    ));
+}
@@ -0,0 +1,335 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    CashOutLimits, GameError, GameState, PendingWithdrawal, COIN_TYPE_TRASHCOIN, JUNK_PER_CREDIT,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+const DAY: i64 = 86_400;
+const VAULT_JUNK: u64 = 1_000 * JUNK;
+const LIMITS: CashOutLimits = CashOutLimits {
+    player_daily_cap: 30,
+    global_daily_cap: 50,
+    queue_threshold: 20,
+    withdrawal_delay: 3_600,
+};
+
+struct CashOut {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    players: [Pubkey; 2],
+}
+
+impl CashOut {
+    /// Two players with fresh games, a funded JUNK vault and no limits set
+    fn new() -> Self {
+        let setup = Setup::builder().players(2).vault_junk(VAULT_JUNK).build();
+        Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+            players: [setup.players[0], setup.players[1]],
+        }
+    }
+
+    fn with_limits() -> Self {
+        let mut cash_out = Self::new();
+        let ix = cash_out.client.set_cash_out_limits(&cash_out.admin, LIMITS);
+        cash_out.harness.process(&ix, &[cash_out.admin]).unwrap();
+        cash_out
+    }
+
+    fn state(&self, player: usize) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, &self.players[player]).0)
+    }
+
+    fn pending_address(&self, player: usize) -> Pubkey {
+        pda::pending_withdrawal(&coin_pusher_game::ID, &self.players[player]).0
+    }
+
+    fn player_junk(&self, player: usize) -> u64 {
+        let ata =
+            get_associated_token_address(&self.players[player], &self.client.deployment.junk_mint);
+        self.harness.token_balance(&ata)
+    }
+
+    /// Win `coins` trashcoin collections (5 credits each)
+    fn win(&mut self, player: usize, coins: u32) {
+        let key = self.players[player];
+        for _ in 0..coins {
+            let ix = self.client.collect_coin(
+                &key,
+                &self.admin,
+                COIN_TYPE_TRASHCOIN,
+                CollectOptions::default(),
+            );
+            self.harness.process(&ix, &[key, self.admin]).unwrap();
+        }
+    }
+
+    fn cash_out(&mut self, player: usize, amount: u64) -> Result<(), ProgramError> {
+        let key = self.players[player];
+        let ix = self.client.cash_out(&key, amount);
+        self.harness.process(&ix, &[key])
+    }
+
+    fn queue(&mut self, player: usize, amount: u64) -> Result<(), ProgramError> {
+        let key = self.players[player];
+        let ix = self.client.queue_cash_out(&key, amount);
+        self.harness.process(&ix, &[key])
+    }
+
+    fn claim(&mut self, player: usize) -> Result<(), ProgramError> {
+        let key = self.players[player];
+        let ix = self.client.claim_cash_out(&key);
+        self.harness.process(&ix, &[key])
+    }
+
+    fn cancel(&mut self, player: usize) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .cancel_cash_out(&self.admin, &self.players[player]);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    fn set_frozen(&mut self, player: usize, frozen: bool) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .set_cash_out_frozen(&self.admin, &self.players[player], frozen);
+        self.harness.process(&ix, &[self.admin])
+    }
+}
+
+#[test]
+fn cash_out_pays_junk_for_withdrawable_credits() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 2);
+
+    cash_out.cash_out(0, 10).unwrap();
+
+    assert_eq!(cash_out.player_junk(0), 10 * JUNK_PER_CREDIT);
+    assert_eq!(
+        cash_out
+            .harness
+            .token_balance(&cash_out.client.deployment.vault_junk_account),
+        VAULT_JUNK - 10 * JUNK_PER_CREDIT
+    );
+    let state = cash_out.state(0);
+    assert_eq!(state.balance, 100);
+    assert_eq!(state.withdrawable_balance(), 0);
+
+    let events = decode_events(&coin_pusher_game::ID, &cash_out.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::CashedOut(cashed)]
+            if cashed.amount == 10 && cashed.junk_amount == 10 * JUNK && cashed.balance == 100
+    ));
+}
+
+#[test]
+fn cash_out_excludes_promotional_credits() {
+    let mut cash_out = CashOut::with_limits();
+
+    assert_eq!(
+        cash_out.cash_out(0, 1),
+        Err(game_error(GameError::InsufficientBalance))
+    );
+}
+
+#[test]
+fn cash_out_enforces_player_cap() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 8);
+
+    cash_out.cash_out(0, 15).unwrap();
+    cash_out.cash_out(0, 15).unwrap();
+    assert_eq!(
+        cash_out.cash_out(0, 5),
+        Err(game_error(GameError::CashOutCapExceeded))
+    );
+
+    cash_out.harness.warp_seconds(DAY);
+    cash_out.cash_out(0, 5).unwrap();
+}
+
+#[test]
+fn cash_out_cap_window_rolls() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 12);
+
+    cash_out.cash_out(0, 15).unwrap();
+    cash_out.harness.warp_seconds(DAY / 2);
+    cash_out.cash_out(0, 15).unwrap();
+
+    // A day after the first cash-out only it has left the window
+    cash_out.harness.warp_seconds(DAY / 2);
+    assert_eq!(
+        cash_out.cash_out(0, 16),
+        Err(game_error(GameError::CashOutCapExceeded))
+    );
+    cash_out.cash_out(0, 15).unwrap();
+    assert_eq!(cash_out.state(0).cash_out_window.total(), 30);
+}
+
+#[test]
+fn cash_out_enforces_global_cap() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 6);
+    cash_out.win(1, 6);
+
+    cash_out.cash_out(0, 15).unwrap();
+    cash_out.cash_out(0, 15).unwrap();
+    cash_out.cash_out(1, 15).unwrap();
+    // Within the player's own cap, but over the global one
+    assert_eq!(
+        cash_out.cash_out(1, 10),
+        Err(game_error(GameError::CashOutCapExceeded))
+    );
+    cash_out.cash_out(1, 5).unwrap();
+}
+
+#[test]
+fn large_cash_out_is_queued_behind_delay() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 5);
+
+    assert_eq!(
+        cash_out.cash_out(0, 20),
+        Err(game_error(GameError::CashOutQueueRequired))
+    );
+    assert_eq!(
+        cash_out.queue(0, 19),
+        Err(game_error(GameError::InvalidAmount))
+    );
+
+    cash_out.queue(0, 20).unwrap();
+    assert_eq!(cash_out.state(0).balance, 105);
+    let pending: PendingWithdrawal = cash_out.harness.get(&cash_out.pending_address(0));
+    assert_eq!(pending.amount, 20);
+    assert_eq!(
+        pending.release_at,
+        pending.requested_at + LIMITS.withdrawal_delay
+    );
+
+    assert_eq!(
+        cash_out.claim(0),
+        Err(game_error(GameError::WithdrawalLocked))
+    );
+    cash_out.harness.warp_seconds(LIMITS.withdrawal_delay);
+    cash_out.claim(0).unwrap();
+
+    assert_eq!(cash_out.player_junk(0), 20 * JUNK_PER_CREDIT);
+    assert!(cash_out
+        .harness
+        .account(&cash_out.pending_address(0))
+        .is_none());
+}
+
+#[test]
+fn only_one_cash_out_can_be_queued() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 8);
+
+    cash_out.queue(0, 20).unwrap();
+    assert!(cash_out.queue(0, 20).is_err());
+}
+
+#[test]
+fn admin_can_freeze_queued_cash_out() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 4);
+    cash_out.queue(0, 20).unwrap();
+    cash_out.harness.warp_seconds(LIMITS.withdrawal_delay);
+
+    cash_out.set_frozen(0, true).unwrap();
+    assert_eq!(
+        cash_out.claim(0),
+        Err(game_error(GameError::WithdrawalFrozen))
+    );
+
+    cash_out.set_frozen(0, false).unwrap();
+    cash_out.claim(0).unwrap();
+}
+
+#[test]
+fn set_cash_out_frozen_requires_admin() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 4);
+    cash_out.queue(0, 20).unwrap();
+
+    let player = cash_out.players[0];
+    let ix = cash_out.client.set_cash_out_frozen(&player, &player, true);
+    assert_eq!(
+        cash_out.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
+
+#[test]
+fn default_limits_disable_cash_out() {
+    let mut cash_out = CashOut::new();
+    cash_out.win(0, 1);
+
+    assert_eq!(
+        cash_out.cash_out(0, 5),
+        Err(game_error(GameError::CashOutQueueRequired))
+    );
+    assert_eq!(
+        cash_out.queue(0, 5),
+        Err(game_error(GameError::CashOutCapExceeded))
+    );
+}
+
+#[test]
+fn admin_can_cancel_a_frozen_cash_out() {
+    let mut cash_out = CashOut::with_limits();
+    cash_out.win(0, 4);
+    cash_out.queue(0, 20).unwrap();
+    cash_out.set_frozen(0, true).unwrap();
+    let rent = cash_out.harness.lamports(&cash_out.pending_address(0));
+    let player_before = cash_out.harness.lamports(&cash_out.players[0]);
+
+    let player = cash_out.players[0];
+    let ix = cash_out.client.cancel_cash_out(&player, &player);
+    assert_eq!(
+        cash_out.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    cash_out.cancel(0).unwrap();
+    let state = cash_out.state(0);
+    assert_eq!(state.balance, 120);
+    assert_eq!(state.withdrawable_balance(), 20);
+    assert_eq!(state.cash_out_window.total(), 0);
+    assert!(cash_out
+        .harness
+        .account(&cash_out.pending_address(0))
+        .is_none());
+    assert_eq!(
+        cash_out.harness.lamports(&cash_out.players[0]),
+        player_before + rent
+    );
+
+    let events = decode_events(&coin_pusher_game::ID, &cash_out.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::CashOutCancelled(cancelled)]
+            if cancelled.player == player && cancelled.amount == 20 && cancelled.balance == 120
+    ));
+
+    // The credits can be cashed out again
+    cash_out.queue(0, 20).unwrap();
+    assert_eq!(
+        cash_out.claim(0),
+        Err(game_error(GameError::WithdrawalLocked))
+    );
+}
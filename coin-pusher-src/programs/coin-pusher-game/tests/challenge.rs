@@ -0,0 +1,422 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::{self, instruction::transfer};
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    Challenge, ChallengeRules, ChallengeStatus, GameError, PlayerStatus, COIN_TYPE_NORMAL,
+    COIN_TYPE_TRASHCOIN,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, start_game, Setup, INITIAL_BALANCE, JUNK};
+
+const PLAYER_JUNK: u64 = 100 * JUNK;
+const STAKE: u64 = 10 * JUNK;
+const DURATION: i64 = 600;
+const ACCEPT_WINDOW: i64 = 3_600;
+const CHALLENGE_ID: u64 = 1;
+
+const RULES: ChallengeRules = ChallengeRules {
+    rake_bps: 500,
+    min_stake: JUNK,
+    max_duration: 86_400,
+};
+
+struct Duel {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    challenger: Pubkey,
+    opponent: Pubkey,
+}
+
+impl Duel {
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            players,
+            ..
+        } = Setup::builder().players(2).player_junk(PLAYER_JUNK).build();
+        let ix = client.set_challenge_rules(&admin, RULES);
+        harness.process(&ix, &[admin]).unwrap();
+
+        Self {
+            harness,
+            client,
+            admin,
+            challenger: players[0],
+            opponent: players[1],
+        }
+    }
+
+    /// `challenger` has invited `opponent` and they have accepted
+    fn accepted() -> Self {
+        let mut duel = Self::new();
+        let opponent = duel.opponent;
+        duel.create(opponent, STAKE, DURATION).unwrap();
+        duel.accept(opponent).unwrap();
+        duel
+    }
+
+    /// A player holding `PLAYER_JUNK` with a game started
+    fn add_player(&mut self) -> Pubkey {
+        start_game(
+            &mut self.harness,
+            &self.client,
+            PLAYER_JUNK,
+            INITIAL_BALANCE,
+        )
+    }
+
+    fn now(&self) -> i64 {
+        self.harness.clock().unix_timestamp
+    }
+
+    fn create(&mut self, opponent: Pubkey, stake: u64, duration: i64) -> Result<(), ProgramError> {
+        let ix = self.client.create_challenge(
+            &self.challenger,
+            CHALLENGE_ID,
+            &opponent,
+            stake,
+            duration,
+            self.now() + ACCEPT_WINDOW,
+        );
+        self.harness.process(&ix, &[self.challenger])
+    }
+
+    fn accept(&mut self, opponent: Pubkey) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .accept_challenge(&opponent, &self.challenger, CHALLENGE_ID);
+        self.harness.process(&ix, &[opponent])
+    }
+
+    fn collect(&mut self, player: Pubkey, coin_type: u8) -> Result<(), ProgramError> {
+        let ix = self.client.collect_coin(
+            &player,
+            &self.admin,
+            coin_type,
+            CollectOptions {
+                challenge: Some(self.address()),
+                ..CollectOptions::default()
+            },
+        );
+        self.harness.process(&ix, &[player, self.admin])
+    }
+
+    fn settle(&mut self) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .settle_challenge(&self.challenger, CHALLENGE_ID, &self.opponent);
+        let payer = self.harness.new_funded_account();
+        self.harness.process(&ix, &[payer])
+    }
+
+    /// An outsider transfers `amount` JUNK straight into the escrow
+    fn send_to_escrow(&mut self, amount: u64) {
+        let outsider = self.add_player();
+        let ix = transfer(
+            &spl_token::ID,
+            &get_associated_token_address(&outsider, &self.client.deployment.junk_mint),
+            &pda::challenge_vault(&coin_pusher_game::ID, &self.address()).0,
+            &outsider,
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.harness.process(&ix, &[outsider]).unwrap();
+    }
+
+    fn address(&self) -> Pubkey {
+        pda::challenge(&coin_pusher_game::ID, &self.challenger, CHALLENGE_ID).0
+    }
+
+    fn challenge(&self) -> Challenge {
+        self.harness.get(&self.address())
+    }
+
+    fn is_closed(&self) -> bool {
+        let vault = pda::challenge_vault(&coin_pusher_game::ID, &self.address()).0;
+        self.harness.account(&self.address()).is_none() && self.harness.account(&vault).is_none()
+    }
+
+    fn junk_balance(&self, player: &Pubkey) -> u64 {
+        self.harness.token_balance(&get_associated_token_address(
+            player,
+            &self.client.deployment.junk_mint,
+        ))
+    }
+}
+
+#[test]
+fn winner_takes_the_pot_less_rake() {
+    let mut duel = Duel::accepted();
+    let (challenger, opponent) = (duel.challenger, duel.opponent);
+    assert_eq!(duel.junk_balance(&opponent), PLAYER_JUNK - STAKE);
+    assert_eq!(duel.challenge().status, ChallengeStatus::Active);
+
+    duel.collect(challenger, COIN_TYPE_NORMAL).unwrap();
+    duel.collect(opponent, COIN_TYPE_TRASHCOIN).unwrap();
+    let events = decode_events(&coin_pusher_game::ID, &duel.harness.logs());
+    assert!(events.iter().any(|event| matches!(
+        event,
+        GameEvent::ChallengeScoreUpdated(update)
+            if update.player == opponent
+                && update.challenger_score == 1
+                && update.opponent_score == 5
+    )));
+
+    duel.harness.warp_seconds(DURATION);
+    let rent = duel.harness.lamports(&challenger);
+    duel.settle().unwrap();
+
+    let rake = 2 * STAKE * RULES.rake_bps as u64 / 10_000;
+    assert_eq!(duel.junk_balance(&opponent), PLAYER_JUNK + STAKE - rake);
+    assert_eq!(duel.junk_balance(&challenger), PLAYER_JUNK - STAKE);
+    assert_eq!(
+        duel.harness
+            .token_balance(&duel.client.deployment.vault_junk_account),
+        rake
+    );
+    assert!(duel.is_closed());
+    assert!(duel.harness.lamports(&challenger) > rent);
+
+    let events = decode_events(&coin_pusher_game::ID, &duel.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::ChallengeSettled(settled)]
+            if settled.winner == opponent && settled.payout == 2 * STAKE - rake && settled.rake == rake
+    ));
+}
+
+#[test]
+fn tie_refunds_both_stakes() {
+    let mut duel = Duel::accepted();
+    let (challenger, opponent) = (duel.challenger, duel.opponent);
+
+    duel.collect(challenger, COIN_TYPE_TRASHCOIN).unwrap();
+    duel.collect(opponent, COIN_TYPE_TRASHCOIN).unwrap();
+    duel.harness.warp_seconds(DURATION);
+    duel.settle().unwrap();
+
+    assert_eq!(duel.junk_balance(&challenger), PLAYER_JUNK);
+    assert_eq!(duel.junk_balance(&opponent), PLAYER_JUNK);
+    assert_eq!(
+        duel.harness
+            .token_balance(&duel.client.deployment.vault_junk_account),
+        0
+    );
+    assert!(duel.is_closed());
+}
+
+#[test]
+fn only_collections_inside_the_window_count() {
+    let mut duel = Duel::accepted();
+    let (challenger, opponent) = (duel.challenger, duel.opponent);
+
+    duel.collect(opponent, COIN_TYPE_NORMAL).unwrap();
+    assert_eq!(duel.settle(), Err(game_error(GameError::ChallengeNotEnded)));
+
+    duel.harness.warp_seconds(DURATION);
+    duel.collect(challenger, COIN_TYPE_TRASHCOIN).unwrap();
+    let challenge = duel.challenge();
+    assert_eq!(
+        (challenge.challenger_score, challenge.opponent_score),
+        (0, 1)
+    );
+
+    duel.settle().unwrap();
+    assert_eq!(duel.junk_balance(&challenger), PLAYER_JUNK - STAKE);
+}
+
+#[test]
+fn only_the_invited_opponent_can_accept() {
+    let mut duel = Duel::new();
+    let (challenger, opponent) = (duel.challenger, duel.opponent);
+    let stranger = duel.add_player();
+    duel.create(opponent, STAKE, DURATION).unwrap();
+
+    assert_eq!(
+        duel.accept(stranger),
+        Err(game_error(GameError::Unauthorized))
+    );
+    assert_eq!(
+        duel.accept(challenger),
+        Err(game_error(GameError::Unauthorized))
+    );
+    duel.accept(opponent).unwrap();
+    assert_eq!(
+        duel.accept(opponent),
+        Err(game_error(GameError::ChallengeClosed))
+    );
+
+    // Outsiders can't score in someone else's match
+    assert_eq!(
+        duel.collect(stranger, COIN_TYPE_NORMAL),
+        Err(game_error(GameError::NotChallengeParticipant))
+    );
+}
+
+#[test]
+fn open_challenge_can_be_accepted_by_anyone() {
+    let mut duel = Duel::new();
+    let taker = duel.add_player();
+    duel.create(Pubkey::default(), STAKE, DURATION).unwrap();
+
+    // Nobody has joined yet, so nobody but the challenger can score
+    assert_eq!(
+        duel.collect(taker, COIN_TYPE_NORMAL),
+        Err(game_error(GameError::NotChallengeParticipant))
+    );
+    assert_eq!(
+        duel.accept(duel.challenger),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    duel.accept(taker).unwrap();
+    let challenge = duel.challenge();
+    assert_eq!(challenge.opponent, taker);
+    assert_eq!(challenge.end_time, challenge.start_time + DURATION);
+    assert_eq!(duel.junk_balance(&taker), PLAYER_JUNK - STAKE);
+}
+
+#[test]
+fn no_show_is_refunded_after_the_deadline() {
+    let mut duel = Duel::new();
+    let (challenger, opponent) = (duel.challenger, duel.opponent);
+    duel.create(opponent, STAKE, DURATION).unwrap();
+    assert_eq!(duel.junk_balance(&challenger), PLAYER_JUNK - STAKE);
+
+    let ix = duel.client.refund_challenge(&challenger, CHALLENGE_ID);
+    assert_eq!(
+        duel.harness.process(&ix, &[challenger]),
+        Err(game_error(GameError::ChallengeNotEnded))
+    );
+
+    duel.harness.warp_seconds(ACCEPT_WINDOW);
+    assert_eq!(
+        duel.accept(opponent),
+        Err(game_error(GameError::ChallengeClosed))
+    );
+
+    let ix = duel.client.refund_challenge(&challenger, CHALLENGE_ID);
+    duel.harness.process(&ix, &[challenger]).unwrap();
+    assert_eq!(duel.junk_balance(&challenger), PLAYER_JUNK);
+    assert!(duel.is_closed());
+}
+
+#[test]
+fn create_challenge_enforces_rules() {
+    let mut duel = Duel::new();
+    let (challenger, opponent) = (duel.challenger, duel.opponent);
+
+    for (opponent, stake, duration) in [
+        (opponent, RULES.min_stake - 1, DURATION),
+        (opponent, u64::MAX / 2 + 1, DURATION),
+        (opponent, STAKE, 0),
+        (opponent, STAKE, RULES.max_duration + 1),
+        (challenger, STAKE, DURATION),
+    ] {
+        assert_eq!(
+            duel.create(opponent, stake, duration),
+            Err(game_error(GameError::InvalidChallenge))
+        );
+    }
+
+    let ix = duel.client.create_challenge(
+        &challenger,
+        CHALLENGE_ID,
+        &opponent,
+        STAKE,
+        DURATION,
+        duel.now(),
+    );
+    assert_eq!(
+        duel.harness.process(&ix, &[challenger]),
+        Err(game_error(GameError::InvalidChallenge))
+    );
+}
+
+#[test]
+fn set_challenge_rules_requires_admin() {
+    let mut duel = Duel::new();
+    let player = duel.challenger;
+
+    let ix = duel.client.set_challenge_rules(&player, RULES);
+    assert_eq!(
+        duel.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    let rules = ChallengeRules {
+        rake_bps: 10_001,
+        ..RULES
+    };
+    let ix = duel.client.set_challenge_rules(&duel.admin, rules);
+    assert_eq!(
+        duel.harness.process(&ix, &[duel.admin]),
+        Err(game_error(GameError::InvalidChallenge))
+    );
+}
+
+#[test]
+fn frozen_players_cannot_challenge_or_accept() {
+    let mut duel = Duel::new();
+    let (admin, challenger, opponent) = (duel.admin, duel.challenger, duel.opponent);
+    let freeze = |duel: &mut Duel, player: Pubkey, status: PlayerStatus| {
+        let ix = duel.client.set_player_status(&admin, &player, status, 0, 0);
+        duel.harness.process(&ix, &[admin]).unwrap();
+    };
+
+    freeze(&mut duel, challenger, PlayerStatus::Frozen);
+    assert_eq!(
+        duel.create(opponent, STAKE, DURATION),
+        Err(game_error(GameError::PlayerFrozen))
+    );
+    freeze(&mut duel, challenger, PlayerStatus::Active);
+    duel.create(opponent, STAKE, DURATION).unwrap();
+
+    freeze(&mut duel, opponent, PlayerStatus::Banned);
+    assert_eq!(
+        duel.accept(opponent),
+        Err(game_error(GameError::PlayerBanned))
+    );
+    assert_eq!(duel.challenge().status, ChallengeStatus::Open);
+}
+
+#[test]
+fn stray_escrow_deposits_do_not_lock_the_stakes() {
+    let mut duel = Duel::accepted();
+    let (challenger, opponent) = (duel.challenger, duel.opponent);
+    duel.collect(challenger, COIN_TYPE_TRASHCOIN).unwrap();
+    duel.send_to_escrow(1);
+    duel.harness.warp_seconds(DURATION);
+
+    duel.settle().unwrap();
+    let rake = 2 * STAKE * RULES.rake_bps as u64 / 10_000;
+    assert_eq!(duel.junk_balance(&challenger), PLAYER_JUNK + STAKE - rake);
+    assert_eq!(duel.junk_balance(&opponent), PLAYER_JUNK - STAKE);
+    assert_eq!(
+        duel.harness
+            .token_balance(&duel.client.deployment.vault_junk_account),
+        rake + 1
+    );
+    assert!(duel.is_closed());
+
+    // An unaccepted challenge refunds the deposit with the stake
+    let mut duel = Duel::new();
+    let (challenger, opponent) = (duel.challenger, duel.opponent);
+    duel.create(opponent, STAKE, DURATION).unwrap();
+    duel.send_to_escrow(1);
+    duel.harness.warp_seconds(ACCEPT_WINDOW);
+    let ix = duel.client.refund_challenge(&challenger, CHALLENGE_ID);
+    duel.harness.process(&ix, &[challenger]).unwrap();
+    assert_eq!(duel.junk_balance(&challenger), PLAYER_JUNK + 1);
+    assert!(duel.is_closed());
+}
@@ -0,0 +1,419 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::error::TokenError;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    instruction_flags, EmissionSchedule, FeeSplit, GameConfig, GameError, GameState, GlobalStats,
+    COIN_TYPE_NORMAL, COIN_TYPE_TRASHCOIN,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{deploy, game_error, Setup, JUNK, VAULT_TRASHCOIN};
+
+const PLAYER_JUNK: u64 = 1_000 * JUNK;
+
+struct Game {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    pause_authority: Pubkey,
+    player: Pubkey,
+}
+
+impl Game {
+    fn new() -> Self {
+        Self::with_player_junk(PLAYER_JUNK)
+    }
+
+    fn with_player_junk(junk: u64) -> Self {
+        let setup = Setup::builder().players(1).player_junk(junk).build();
+        Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+            pause_authority: setup.pause_authority,
+            player: setup.players[0],
+        }
+    }
+
+    fn run(&mut self, ix: &Instruction, signers: &[Pubkey]) -> Result<(), ProgramError> {
+        self.harness.process(ix, signers)
+    }
+
+    fn play(&mut self, ix: Instruction) -> Result<(), ProgramError> {
+        let player = self.player;
+        self.run(&ix, &[player])
+    }
+
+    fn state(&self) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, &self.player).0)
+    }
+
+    fn config(&self) -> GameConfig {
+        self.harness.get(&pda::config(&coin_pusher_game::ID).0)
+    }
+
+    fn player_junk(&self) -> u64 {
+        let ata = get_associated_token_address(&self.player, &self.client.deployment.junk_mint);
+        self.harness.token_balance(&ata)
+    }
+
+    fn player_trashcoin(&self) -> u64 {
+        let ata =
+            get_associated_token_address(&self.player, &self.client.deployment.trashcoin_mint);
+        self.harness.token_balance(&ata)
+    }
+
+    fn collect(&mut self, coin_type: u8) -> Result<(), ProgramError> {
+        let ix = self.client.collect_coin(
+            &self.player,
+            &self.admin,
+            coin_type,
+            CollectOptions::default(),
+        );
+        let (player, admin) = (self.player, self.admin);
+        self.run(&ix, &[player, admin])
+    }
+
+    fn set_pause(&mut self, paused: bool, disabled_instructions: u64) {
+        let ix = self
+            .client
+            .set_pause(&self.pause_authority, paused, disabled_instructions);
+        let pause_authority = self.pause_authority;
+        self.run(&ix, &[pause_authority]).unwrap();
+    }
+}
+
+#[test]
+fn initialize_config_records_mints_and_authorities() {
+    let game = Game::new();
+    let config = game.config();
+
+    assert_eq!(config.admin, game.admin);
+    assert_eq!(config.pause_authority, game.pause_authority);
+    assert_eq!(config.game_authority, game.admin);
+    assert_eq!(config.junk_mint, game.client.deployment.junk_mint);
+    assert_eq!(config.trashcoin_mint, game.client.deployment.trashcoin_mint);
+    assert_eq!(config.fee_split, FeeSplit::default());
+    assert!(!config.paused);
+}
+
+#[test]
+fn initialize_config_requires_upgrade_authority() {
+    let mut harness = Harness::new();
+    let upgrade_authority = harness.new_funded_account();
+    let admin = harness.new_funded_account();
+    harness.add_program_data(&coin_pusher_game::ID, Some(upgrade_authority));
+    let client = CoinPusherClient::new(deploy(&mut harness, &admin));
+
+    let ix = client.initialize_config(&admin, Pubkey::new_unique());
+    assert_eq!(
+        harness.process(&ix, &[admin]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
+
+#[test]
+fn initialize_config_rejects_admin_as_pause_authority() {
+    let mut harness = Harness::new();
+    let admin = harness.new_funded_account();
+    harness.add_program_data(&coin_pusher_game::ID, Some(admin));
+    let client = CoinPusherClient::new(deploy(&mut harness, &admin));
+
+    let ix = client.initialize_config(&admin, admin);
+    assert_eq!(
+        harness.process(&ix, &[admin]),
+        Err(game_error(GameError::PauseAuthorityNotDistinct))
+    );
+}
+
+#[test]
+fn initialize_game_creates_player_state() {
+    let game = Game::new();
+    let state = game.state();
+
+    assert_eq!(state.player, game.player);
+    assert_eq!(state.balance, 100);
+    assert_eq!(state.score, 0);
+    assert_eq!(state.created_at, game.harness.clock().unix_timestamp);
+}
+
+#[test]
+fn drop_coin_pays_one_junk_into_vault() {
+    let mut game = Game::new();
+
+    let ix = game.client.drop_coin(&game.player);
+    game.play(ix).unwrap();
+
+    assert_eq!(game.player_junk(), PLAYER_JUNK - JUNK);
+    assert_eq!(
+        game.harness
+            .token_balance(&game.client.deployment.vault_junk_account),
+        JUNK
+    );
+    let state = game.state();
+    assert_eq!(state.balance, 99);
+    assert_eq!(state.net_profit, -1);
+
+    let events = decode_events(&coin_pusher_game::ID, &game.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::CoinDropped(dropped)]
+            if dropped.cost == 1 && dropped.new_balance == 99
+    ));
+}
+
+#[test]
+fn drop_coin_splits_payment() {
+    let mut game = Game::new();
+    let junk_mint = game.client.deployment.junk_mint;
+    let jackpot = game
+        .harness
+        .create_token_account(&junk_mint, &Pubkey::new_unique(), 0);
+    let treasury = game
+        .harness
+        .create_token_account(&junk_mint, &Pubkey::new_unique(), 0);
+    let fee_split = FeeSplit {
+        burn_bps: 2_000,
+        vault_bps: 5_000,
+        jackpot_bps: 2_000,
+        treasury_bps: 1_000,
+    };
+    let ix = game
+        .client
+        .set_fee_split(&game.admin, fee_split, jackpot, treasury);
+    let admin = game.admin;
+    game.run(&ix, &[admin]).unwrap();
+    game.client.deployment.jackpot_junk_account = Some(jackpot);
+    game.client.deployment.treasury_junk_account = Some(treasury);
+    let supply_before = game.harness.mint_supply(&junk_mint);
+
+    let ix = game.client.drop_coin(&game.player);
+    game.play(ix).unwrap();
+
+    let vault = game.client.deployment.vault_junk_account;
+    assert_eq!(game.harness.token_balance(&vault), 500_000);
+    assert_eq!(game.harness.token_balance(&jackpot), 200_000);
+    assert_eq!(game.harness.token_balance(&treasury), 100_000);
+    assert_eq!(
+        game.harness.mint_supply(&junk_mint),
+        supply_before - 200_000
+    );
+    let stats: GlobalStats = game
+        .harness
+        .get(&pda::global_stats(&coin_pusher_game::ID).0);
+    assert_eq!(stats.total_burned, 200_000);
+}
+
+#[test]
+fn drop_coin_requires_configured_fee_accounts() {
+    let mut game = Game::new();
+    let junk_mint = game.client.deployment.junk_mint;
+    let jackpot = game
+        .harness
+        .create_token_account(&junk_mint, &Pubkey::new_unique(), 0);
+    let treasury = game
+        .harness
+        .create_token_account(&junk_mint, &Pubkey::new_unique(), 0);
+    let fee_split = FeeSplit {
+        burn_bps: 0,
+        vault_bps: 9_000,
+        jackpot_bps: 1_000,
+        treasury_bps: 0,
+    };
+    let ix = game
+        .client
+        .set_fee_split(&game.admin, fee_split, jackpot, treasury);
+    let admin = game.admin;
+    game.run(&ix, &[admin]).unwrap();
+
+    let ix = game.client.drop_coin(&game.player);
+    assert_eq!(game.play(ix), Err(game_error(GameError::MissingFeeAccount)));
+}
+
+#[test]
+fn set_fee_split_rejects_bad_total() {
+    let mut game = Game::new();
+    let junk_mint = game.client.deployment.junk_mint;
+    let jackpot = game
+        .harness
+        .create_token_account(&junk_mint, &Pubkey::new_unique(), 0);
+    let fee_split = FeeSplit {
+        burn_bps: 5_000,
+        vault_bps: 5_000,
+        jackpot_bps: 1,
+        treasury_bps: 0,
+    };
+
+    let ix = game
+        .client
+        .set_fee_split(&game.admin, fee_split, jackpot, jackpot);
+    let admin = game.admin;
+    assert_eq!(
+        game.run(&ix, &[admin]),
+        Err(game_error(GameError::InvalidFeeSplit))
+    );
+}
+
+#[test]
+fn bump_machine_is_rate_limited() {
+    let mut game = Game::new();
+
+    // Initialization counts as the last update
+    let ix = game.client.bump_machine(&game.player, false);
+    assert_eq!(
+        game.play(ix.clone()),
+        Err(game_error(GameError::TooManyRequests))
+    );
+
+    game.harness.warp_seconds(1);
+    game.play(ix.clone()).unwrap();
+    assert_eq!(
+        game.play(ix.clone()),
+        Err(game_error(GameError::TooManyRequests))
+    );
+
+    game.harness.warp_seconds(1);
+    game.play(ix).unwrap();
+
+    assert_eq!(game.player_junk(), PLAYER_JUNK - 100 * JUNK);
+    assert_eq!(game.state().balance, 0);
+}
+
+#[test]
+fn bump_machine_fails_without_enough_junk() {
+    let mut game = Game::with_player_junk(10 * JUNK);
+    game.harness.warp_seconds(1);
+
+    let ix = game.client.bump_machine(&game.player, false);
+    assert_eq!(
+        game.play(ix),
+        Err(ProgramError::Custom(TokenError::InsufficientFunds as u32))
+    );
+    assert_eq!(game.player_junk(), 10 * JUNK);
+}
+
+#[test]
+fn collect_coin_credits_reward() {
+    let mut game = Game::new();
+
+    game.collect(COIN_TYPE_NORMAL).unwrap();
+    game.collect(COIN_TYPE_TRASHCOIN).unwrap();
+
+    let state = game.state();
+    assert_eq!(state.score, 6);
+    assert_eq!(state.balance, 106);
+    assert_eq!(state.total_coins_collected, 2);
+    assert_eq!(state.trashcoins_collected, 1);
+}
+
+#[test]
+fn collect_coin_requires_game_authority() {
+    let mut game = Game::new();
+
+    let ix = game.client.collect_coin(
+        &game.player,
+        &game.player,
+        COIN_TYPE_NORMAL,
+        CollectOptions::default(),
+    );
+    assert_eq!(game.play(ix), Err(game_error(GameError::Unauthorized)));
+}
+
+#[test]
+fn award_trashcoin_pays_from_vault() {
+    let mut game = Game::new();
+    let schedule = EmissionSchedule {
+        initial_epoch_budget: 10 * JUNK,
+        player_daily_cap: 10 * JUNK,
+        ..EmissionSchedule::default()
+    };
+    let ix = game.client.set_emission_schedule(&game.admin, schedule);
+    game.run(&ix, &[game.admin]).unwrap();
+
+    let ix = game
+        .client
+        .award_trashcoin(&game.player, &game.admin, 5 * JUNK);
+    game.run(&ix, &[game.player, game.admin]).unwrap();
+
+    assert_eq!(game.player_trashcoin(), 5 * JUNK);
+    assert_eq!(
+        game.harness
+            .token_balance(&game.client.deployment.vault_trashcoin_account),
+        VAULT_TRASHCOIN - 5 * JUNK
+    );
+}
+
+#[test]
+fn award_trashcoin_rejects_zero_amount() {
+    let mut game = Game::new();
+
+    let ix = game.client.award_trashcoin(&game.player, &game.admin, 0);
+    assert_eq!(
+        game.run(&ix, &[game.player, game.admin]),
+        Err(game_error(GameError::InvalidAmount))
+    );
+}
+
+#[test]
+fn reset_game_restores_defaults() {
+    let mut game = Game::new();
+    game.collect(COIN_TYPE_TRASHCOIN).unwrap();
+
+    let ix = game.client.reset_game(&game.player);
+    game.play(ix).unwrap();
+
+    let state = game.state();
+    assert_eq!(state.score, 0);
+    assert_eq!(state.balance, 100);
+    assert_eq!(state.total_coins_collected, 0);
+    assert_eq!(state.trashcoins_collected, 0);
+}
+
+#[test]
+fn pause_blocks_gameplay() {
+    let mut game = Game::new();
+    game.set_pause(true, 0);
+
+    let ix = game.client.drop_coin(&game.player);
+    assert_eq!(game.play(ix), Err(game_error(GameError::ProgramPaused)));
+    assert_eq!(
+        game.collect(COIN_TYPE_NORMAL),
+        Err(game_error(GameError::ProgramPaused))
+    );
+
+    game.set_pause(false, 0);
+    let ix = game.client.drop_coin(&game.player);
+    game.play(ix).unwrap();
+}
+
+#[test]
+fn kill_flag_disables_single_instruction() {
+    let mut game = Game::new();
+    game.set_pause(false, instruction_flags::DROP_COIN);
+
+    let ix = game.client.drop_coin(&game.player);
+    assert_eq!(
+        game.play(ix),
+        Err(game_error(GameError::InstructionDisabled))
+    );
+    game.collect(COIN_TYPE_NORMAL).unwrap();
+}
+
+#[test]
+fn set_pause_requires_pause_authority() {
+    let mut game = Game::new();
+
+    let ix = game.client.set_pause(&game.admin, true, 0);
+    let admin = game.admin;
+    assert_eq!(
+        game.run(&ix, &[admin]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
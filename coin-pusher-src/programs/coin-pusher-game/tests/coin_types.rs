@@ -0,0 +1,268 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    CoinType, GameConfig, GameError, GameState, COIN_TYPE_NORMAL, COIN_TYPE_TRASHCOIN,
+    MAX_COIN_TYPES,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+const GORBY: u8 = 2;
+const BONUS_BALL: u8 = 3;
+const VAULT_BONUS: u64 = 500 * JUNK;
+
+struct Coins {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    player: Pubkey,
+    bonus_mint: Pubkey,
+}
+
+impl Coins {
+    /// A player and a partner mint whose vault account is funded
+    fn new() -> Self {
+        let mut setup = Setup::builder().players(1).build();
+        let bonus_mint = setup.harness.create_mint(&setup.admin, 6);
+        let vault = pda::vault(&coin_pusher_game::ID).0;
+        setup.harness.create_token_account_at(
+            get_associated_token_address(&vault, &bonus_mint),
+            &bonus_mint,
+            &vault,
+            VAULT_BONUS,
+        );
+        Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+            player: setup.players[0],
+            bonus_mint,
+        }
+    }
+
+    /// The default table plus a capped GORBY coin and a bonus ball that
+    /// pays the partner mint
+    fn with_custom_types() -> Self {
+        let mut coins = Self::new();
+        let table = coins.custom_table();
+        coins.set_coin_types(table).unwrap();
+        coins
+    }
+
+    fn custom_table(&self) -> [CoinType; MAX_COIN_TYPES] {
+        let mut table = CoinType::default_table();
+        table[GORBY as usize] = CoinType {
+            reward: 10,
+            spawn_weight: 2,
+            epoch_cap: 2,
+            ..CoinType::default()
+        };
+        table[BONUS_BALL as usize] = CoinType {
+            reward: 1,
+            spawn_weight: 1,
+            payout_mint: self.bonus_mint,
+            payout_amount: 7,
+            ..CoinType::default()
+        };
+        table
+    }
+
+    fn set_coin_types(&mut self, table: [CoinType; MAX_COIN_TYPES]) -> Result<(), ProgramError> {
+        let ix = self.client.set_coin_types(&self.admin, table);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    fn state(&self) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, &self.player).0)
+    }
+
+    fn collect_with(&mut self, coin_type: u8, options: CollectOptions) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .collect_coin(&self.player, &self.admin, coin_type, options);
+        self.harness.process(&ix, &[self.player, self.admin])
+    }
+
+    fn collect(&mut self, coin_type: u8) -> Result<(), ProgramError> {
+        self.collect_with(coin_type, CollectOptions::default())
+    }
+
+    fn next_epoch(&mut self) {
+        let mut clock = self.harness.clock();
+        clock.epoch += 1;
+        self.harness.set_clock(clock);
+    }
+}
+
+#[test]
+fn default_table_keeps_normal_and_trashcoin_rewards() {
+    let coins = Coins::new();
+    let config: GameConfig = coins.harness.get(&pda::config(&coin_pusher_game::ID).0);
+
+    assert_eq!(config.coin_types, CoinType::default_table());
+    assert_eq!(config.coin_types[COIN_TYPE_NORMAL as usize].reward, 1);
+    assert_eq!(config.coin_types[COIN_TYPE_TRASHCOIN as usize].reward, 5);
+}
+
+#[test]
+fn collect_rejects_unknown_coin_type() {
+    let mut coins = Coins::new();
+
+    assert_eq!(
+        coins.collect(GORBY),
+        Err(game_error(GameError::InvalidCoinType))
+    );
+    assert_eq!(
+        coins.collect(MAX_COIN_TYPES as u8),
+        Err(game_error(GameError::InvalidCoinType))
+    );
+}
+
+#[test]
+fn custom_coin_type_credits_reward_and_counters() {
+    let mut coins = Coins::with_custom_types();
+
+    coins.collect(GORBY).unwrap();
+    coins.collect(COIN_TYPE_NORMAL).unwrap();
+
+    let state = coins.state();
+    assert_eq!(state.balance, 111);
+    assert_eq!(state.total_coins_collected, 2);
+    assert_eq!(state.trashcoins_collected, 0);
+    assert_eq!(state.coins_collected[GORBY as usize], 1);
+    assert_eq!(state.coins_collected[COIN_TYPE_NORMAL as usize], 1);
+
+    let events = decode_events(&coin_pusher_game::ID, &coins.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::CoinCollected(collected)]
+            if collected.coin_type == COIN_TYPE_NORMAL && collected.amount == 1
+    ));
+}
+
+#[test]
+fn coin_type_epoch_cap_resets_each_epoch() {
+    let mut coins = Coins::with_custom_types();
+
+    coins.collect(GORBY).unwrap();
+    coins.collect(GORBY).unwrap();
+    assert_eq!(
+        coins.collect(GORBY),
+        Err(game_error(GameError::CoinTypeCapReached))
+    );
+    // Uncapped types are unaffected
+    coins.collect(COIN_TYPE_NORMAL).unwrap();
+
+    coins.next_epoch();
+    coins.collect(GORBY).unwrap();
+
+    let state = coins.state();
+    assert_eq!(state.coins_collected[GORBY as usize], 3);
+    assert_eq!(state.epoch_coins_collected[GORBY as usize], 1);
+}
+
+#[test]
+fn coin_type_pays_token_bonus_from_vault() {
+    let mut coins = Coins::with_custom_types();
+    let bonus_mint = coins.bonus_mint;
+    let player_account = get_associated_token_address(&coins.player, &bonus_mint);
+    coins
+        .harness
+        .create_token_account_at(player_account, &bonus_mint, &coins.player, 0);
+
+    assert_eq!(
+        coins.collect(BONUS_BALL),
+        Err(game_error(GameError::MissingPayoutAccount))
+    );
+
+    let options = CollectOptions {
+        payout_mint: Some(bonus_mint),
+        ..CollectOptions::default()
+    };
+    coins.collect_with(BONUS_BALL, options).unwrap();
+
+    assert_eq!(coins.harness.token_balance(&player_account), 7);
+    let vault = pda::vault(&coin_pusher_game::ID).0;
+    assert_eq!(
+        coins
+            .harness
+            .token_balance(&get_associated_token_address(&vault, &bonus_mint)),
+        VAULT_BONUS - 7
+    );
+    assert_eq!(coins.state().balance, 101);
+}
+
+#[test]
+fn set_coin_types_validates_entries() {
+    let mut coins = Coins::new();
+
+    let mut table = coins.custom_table();
+    table[BONUS_BALL as usize].payout_mint = Pubkey::default();
+    assert_eq!(
+        coins.set_coin_types(table),
+        Err(game_error(GameError::InvalidCoinType))
+    );
+
+    // TRASHCOIN is only minted against the emission cap
+    let mut table = coins.custom_table();
+    table[BONUS_BALL as usize].payout_mint = coins.client.deployment.trashcoin_mint;
+    assert_eq!(
+        coins.set_coin_types(table),
+        Err(game_error(GameError::InvalidCoinType))
+    );
+
+    let mut table = coins.custom_table();
+    table[7].spawn_weight = 1;
+    assert_eq!(
+        coins.set_coin_types(table),
+        Err(game_error(GameError::InvalidCoinType))
+    );
+}
+
+#[test]
+fn set_coin_types_requires_admin() {
+    let mut coins = Coins::new();
+
+    let ix = coins
+        .client
+        .set_coin_types(&coins.player, CoinType::default_table());
+    assert_eq!(
+        coins.harness.process(&ix, &[coins.player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
+
+#[test]
+fn roll_coin_type_follows_spawn_weights() {
+    let coins = Coins::with_custom_types();
+    let config: GameConfig = coins.harness.get(&pda::config(&coin_pusher_game::ID).0);
+
+    // Weights: normal 95, trashcoin 5, GORBY 2, bonus ball 1
+    assert_eq!(config.roll_coin_type(0), Some(COIN_TYPE_NORMAL));
+    assert_eq!(config.roll_coin_type(94), Some(COIN_TYPE_NORMAL));
+    assert_eq!(config.roll_coin_type(95), Some(COIN_TYPE_TRASHCOIN));
+    assert_eq!(config.roll_coin_type(100), Some(GORBY));
+    assert_eq!(config.roll_coin_type(102), Some(BONUS_BALL));
+    assert_eq!(config.roll_coin_type(103), Some(COIN_TYPE_NORMAL));
+}
+
+#[test]
+fn reset_game_clears_coin_counters() {
+    let mut coins = Coins::with_custom_types();
+    coins.collect(GORBY).unwrap();
+
+    let ix = coins.client.reset_game(&coins.player);
+    coins.harness.process(&ix, &[coins.player]).unwrap();
+
+    let state = coins.state();
+    assert_eq!(state.coins_collected, [0; MAX_COIN_TYPES]);
+    // The epoch cap still counts coins collected before the reset
+    assert_eq!(state.epoch_coins_collected[GORBY as usize], 1);
+}
@@ -0,0 +1,168 @@
+//! Deployment setup shared by the integration and invariant tests
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::{pda, CoinPusherClient, Deployment};
+use coin_pusher_game::GameError;
+use pusher_test_harness::Harness;
+
+pub const JUNK: u64 = 1_000_000;
+pub const VAULT_TRASHCOIN: u64 = 1_000 * JUNK;
+
+/// Credits a game starts with unless the suite asks for another amount
+pub const INITIAL_BALANCE: u64 = 100;
+
+pub fn game_error(err: GameError) -> ProgramError {
+    ProgramError::Custom(err.into())
+}
+
+/// Mints and vault token accounts for a fresh deployment
+pub fn deploy(harness: &mut Harness, admin: &Pubkey) -> Deployment {
+    let program_id = coin_pusher_game::ID;
+    harness.add_program(program_id, coin_pusher_game::entry);
+
+    let junk_mint = harness.create_mint(admin, 6);
+    let trashcoin_mint = harness.create_mint(admin, 6);
+    let vault = pda::vault(&program_id).0;
+    Deployment {
+        program_id,
+        junk_mint,
+        trashcoin_mint,
+        vault_junk_account: harness.create_token_account(&junk_mint, &vault, 0),
+        vault_trashcoin_account: harness.create_token_account(
+            &trashcoin_mint,
+            &vault,
+            VAULT_TRASHCOIN,
+        ),
+        jackpot_junk_account: None,
+        treasury_junk_account: None,
+    }
+}
+
+/// A funded player with JUNK and TRASHCOIN associated token accounts
+pub fn add_player(harness: &mut Harness, deployment: &Deployment, junk: u64) -> Pubkey {
+    let player = harness.new_funded_account();
+    harness.create_token_account_at(
+        get_associated_token_address(&player, &deployment.junk_mint),
+        &deployment.junk_mint,
+        &player,
+        junk,
+    );
+    harness.create_token_account_at(
+        get_associated_token_address(&player, &deployment.trashcoin_mint),
+        &deployment.trashcoin_mint,
+        &player,
+        0,
+    );
+    player
+}
+
+/// A player from `add_player` with a game started on `initial_balance`
+pub fn start_game(
+    harness: &mut Harness,
+    client: &CoinPusherClient,
+    junk: u64,
+    initial_balance: u64,
+) -> Pubkey {
+    let player = add_player(harness, &client.deployment, junk);
+    let ix = client.initialize_game(&player, initial_balance);
+    harness.process(&ix, &[player]).unwrap();
+    player
+}
+
+/// A deployment with its config initialized, the base every suite's
+/// fixture adds its own setup to
+pub struct Setup {
+    pub harness: Harness,
+    pub client: CoinPusherClient,
+    pub admin: Pubkey,
+    pub pause_authority: Pubkey,
+    /// Players with games started, in creation order
+    pub players: Vec<Pubkey>,
+}
+
+impl Setup {
+    pub fn builder() -> SetupBuilder {
+        SetupBuilder {
+            players: 0,
+            player_junk: 0,
+            initial_balance: INITIAL_BALANCE,
+            vault_junk: 0,
+        }
+    }
+}
+
+pub struct SetupBuilder {
+    players: usize,
+    player_junk: u64,
+    initial_balance: u64,
+    vault_junk: u64,
+}
+
+impl SetupBuilder {
+    /// Start `count` games
+    pub fn players(mut self, count: usize) -> Self {
+        self.players = count;
+        self
+    }
+
+    /// JUNK in each player's associated token account
+    pub fn player_junk(mut self, junk: u64) -> Self {
+        self.player_junk = junk;
+        self
+    }
+
+    /// Credits each game starts with
+    pub fn initial_balance(mut self, credits: u64) -> Self {
+        self.initial_balance = credits;
+        self
+    }
+
+    /// JUNK in the vault, for suites that pay JUNK out of it
+    pub fn vault_junk(mut self, junk: u64) -> Self {
+        self.vault_junk = junk;
+        self
+    }
+
+    pub fn build(self) -> Setup {
+        let mut harness = Harness::new();
+        let admin = harness.new_funded_account();
+        harness.add_program_data(&coin_pusher_game::ID, Some(admin));
+        let client = CoinPusherClient::new(deploy(&mut harness, &admin));
+        let pause_authority = harness.new_funded_account();
+        let ix = client.initialize_config(&admin, pause_authority);
+        harness.process(&ix, &[admin]).unwrap();
+
+        if self.vault_junk > 0 {
+            let deployment = &client.deployment;
+            harness.create_token_account_at(
+                deployment.vault_junk_account,
+                &deployment.junk_mint,
+                &pda::vault(&coin_pusher_game::ID).0,
+                self.vault_junk,
+            );
+        }
+
+        let players = (0..self.players)
+            .map(|_| {
+                start_game(
+                    &mut harness,
+                    &client,
+                    self.player_junk,
+                    self.initial_balance,
+                )
+            })
+            .collect();
+
+        Setup {
+            harness,
+            client,
+            admin,
+            pause_authority,
+            players,
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::DistributionParams;
+use coin_pusher_client::merkle::{Claim, MerkleTree};
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    distribution_leaf, instruction_flags, verify_merkle_proof, Distribution, DistributionClaims,
+    GameError, PlayerStatus,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+const SEASON: u64 = 12;
+const NOW: i64 = 1_700_000_000;
+const EXPIRES_AT: i64 = NOW + 30 * 86_400;
+
+struct Airdrop {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    pause_authority: Pubkey,
+    mint: Pubkey,
+    claims: Vec<Claim>,
+    tree: MerkleTree,
+}
+
+impl Airdrop {
+    /// A posted distribution paying `amounts` to as many new wallets, funded
+    /// with exactly their sum
+    fn new(amounts: &[u64]) -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            pause_authority,
+            ..
+        } = Setup::builder().build();
+        let mut clock = harness.clock();
+        clock.unix_timestamp = NOW;
+        harness.set_clock(clock);
+
+        let mint = harness.create_mint(&admin, 6);
+        let total: u64 = amounts.iter().sum();
+        harness.create_token_account_at(
+            get_associated_token_address(&admin, &mint),
+            &mint,
+            &admin,
+            total,
+        );
+        let claims: Vec<Claim> = amounts
+            .iter()
+            .map(|&amount| {
+                let claimant = harness.new_funded_account();
+                harness.create_token_account_at(
+                    get_associated_token_address(&claimant, &mint),
+                    &mint,
+                    &claimant,
+                    0,
+                );
+                Claim { claimant, amount }
+            })
+            .collect();
+        let tree = MerkleTree::new(&claims);
+
+        let mut airdrop = Self {
+            harness,
+            client,
+            admin,
+            pause_authority,
+            mint,
+            claims,
+            tree,
+        };
+        let params = airdrop.params(total);
+        airdrop.create(params).unwrap();
+        airdrop
+    }
+
+    fn params(&self, total_amount: u64) -> DistributionParams {
+        DistributionParams {
+            distribution_id: SEASON,
+            mint: self.mint,
+            merkle_root: self.tree.root(),
+            total_amount,
+            num_claims: self.claims.len() as u32,
+            expires_at: EXPIRES_AT,
+        }
+    }
+
+    fn create(&mut self, params: DistributionParams) -> Result<(), ProgramError> {
+        let ix = self.client.create_distribution(&self.admin, params);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    fn claim_as(
+        &mut self,
+        claimant: Pubkey,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .claim_distribution(&claimant, SEASON, &self.mint, index, amount, proof);
+        self.harness.process(&ix, &[claimant])
+    }
+
+    /// Claim leaf `index` the honest way
+    fn claim(&mut self, index: usize) -> Result<(), ProgramError> {
+        let Claim { claimant, amount } = self.claims[index];
+        let proof = self.tree.proof(index);
+        self.claim_as(claimant, index as u32, amount, proof)
+    }
+
+    fn clawback(&mut self) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .clawback_distribution(&self.admin, SEASON, &self.mint);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    fn balance(&self, owner: &Pubkey) -> u64 {
+        self.harness
+            .token_balance(&get_associated_token_address(owner, &self.mint))
+    }
+
+    fn distribution_address(&self) -> Pubkey {
+        pda::distribution(&coin_pusher_game::ID, SEASON).0
+    }
+
+    fn distribution(&self) -> Distribution {
+        self.harness.get(&self.distribution_address())
+    }
+
+    fn vault_balance(&self) -> u64 {
+        let vault = pda::distribution_vault(&coin_pusher_game::ID, &self.distribution_address()).0;
+        self.harness.token_balance(&vault)
+    }
+}
+
+#[test]
+fn posting_funds_the_vault() {
+    let airdrop = Airdrop::new(&[5 * JUNK, 7 * JUNK, 9 * JUNK]);
+
+    let distribution = airdrop.distribution();
+    assert_eq!(distribution.mint, airdrop.mint);
+    assert_eq!(distribution.merkle_root, airdrop.tree.root());
+    assert_eq!(distribution.total_amount, 21 * JUNK);
+    assert_eq!(distribution.num_claims, 3);
+    assert_eq!(airdrop.vault_balance(), 21 * JUNK);
+    assert_eq!(airdrop.balance(&airdrop.admin), 0);
+
+    let claims: DistributionClaims = airdrop
+        .harness
+        .get(&pda::distribution_claims(&coin_pusher_game::ID, &airdrop.distribution_address()).0);
+    assert_eq!(claims.bits, vec![0]);
+}
+
+#[test]
+fn players_claim_their_leaves() {
+    let amounts = [5 * JUNK, 7 * JUNK, 9 * JUNK, 2 * JUNK, 4 * JUNK];
+    let mut airdrop = Airdrop::new(&amounts);
+
+    for (index, amount) in amounts.into_iter().enumerate() {
+        airdrop.claim(index).unwrap();
+        let claimant = airdrop.claims[index].claimant;
+        assert_eq!(airdrop.balance(&claimant), amount);
+    }
+
+    let distribution = airdrop.distribution();
+    assert_eq!(distribution.total_claimed, 27 * JUNK);
+    assert_eq!(distribution.claim_count, 5);
+    assert_eq!(airdrop.vault_balance(), 0);
+
+    let events = decode_events(&coin_pusher_game::ID, &airdrop.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::DistributionClaimed(claimed)]
+            if claimed.index == 4 && claimed.amount == 4 * JUNK
+    ));
+}
+
+#[test]
+fn leaves_are_claimed_once() {
+    let mut airdrop = Airdrop::new(&[5 * JUNK, 7 * JUNK]);
+
+    airdrop.claim(1).unwrap();
+    airdrop.harness.warp_seconds(1);
+    assert_eq!(
+        airdrop.claim(1),
+        Err(game_error(GameError::DistributionAlreadyClaimed))
+    );
+    assert_eq!(airdrop.balance(&airdrop.claims[1].claimant), 7 * JUNK);
+    assert_eq!(airdrop.distribution().claim_count, 1);
+}
+
+#[test]
+fn claims_must_match_the_tree() {
+    let mut airdrop = Airdrop::new(&[5 * JUNK, 7 * JUNK, 9 * JUNK]);
+    let Claim { claimant, amount } = airdrop.claims[0];
+    let proof = airdrop.tree.proof(0);
+
+    // More than the leaf says
+    assert_eq!(
+        airdrop.claim_as(claimant, 0, amount + 1, proof.clone()),
+        Err(game_error(GameError::InvalidMerkleProof))
+    );
+    // Someone else's leaf
+    let thief = airdrop.claims[1].claimant;
+    assert_eq!(
+        airdrop.claim_as(thief, 0, amount, proof.clone()),
+        Err(game_error(GameError::InvalidMerkleProof))
+    );
+    // Past the end of the claim list
+    assert_eq!(
+        airdrop.claim_as(claimant, 3, amount, proof),
+        Err(game_error(GameError::InvalidDistribution))
+    );
+    assert_eq!(airdrop.vault_balance(), 21 * JUNK);
+}
+
+#[test]
+fn expired_distributions_are_clawed_back() {
+    let mut airdrop = Airdrop::new(&[5 * JUNK, 7 * JUNK]);
+    airdrop.claim(0).unwrap();
+
+    assert_eq!(
+        airdrop.clawback(),
+        Err(game_error(GameError::DistributionNotExpired))
+    );
+
+    airdrop.harness.warp_seconds(EXPIRES_AT - NOW);
+    assert_eq!(
+        airdrop.claim(1),
+        Err(game_error(GameError::DistributionExpired))
+    );
+    airdrop.clawback().unwrap();
+    assert_eq!(airdrop.balance(&airdrop.admin), 7 * JUNK);
+    assert_eq!(airdrop.vault_balance(), 0);
+    assert!(airdrop.distribution().clawed_back);
+
+    airdrop.harness.warp_seconds(1);
+    assert_eq!(
+        airdrop.clawback(),
+        Err(game_error(GameError::InvalidDistribution))
+    );
+}
+
+#[test]
+fn claims_can_be_disabled() {
+    let mut airdrop = Airdrop::new(&[5 * JUNK]);
+    let pause_authority = airdrop.pause_authority;
+
+    let ix = airdrop.client.set_pause(
+        &pause_authority,
+        false,
+        instruction_flags::CLAIM_DISTRIBUTION,
+    );
+    airdrop.harness.process(&ix, &[pause_authority]).unwrap();
+    assert_eq!(
+        airdrop.claim(0),
+        Err(game_error(GameError::InstructionDisabled))
+    );
+}
+
+#[test]
+fn only_the_admin_posts_valid_distributions() {
+    let mut airdrop = Airdrop::new(&[5 * JUNK]);
+    let mut params = airdrop.params(0);
+    params.distribution_id = SEASON + 1;
+
+    // Nothing to distribute
+    assert_eq!(
+        airdrop.create(params),
+        Err(game_error(GameError::InvalidDistribution))
+    );
+    // Already expired
+    params.total_amount = JUNK;
+    params.expires_at = NOW;
+    assert_eq!(
+        airdrop.create(params),
+        Err(game_error(GameError::InvalidDistribution))
+    );
+
+    params.expires_at = EXPIRES_AT;
+    let intruder = airdrop.claims[0].claimant;
+    let ix = airdrop.client.create_distribution(&intruder, params);
+    assert_eq!(
+        airdrop.harness.process(&ix, &[intruder]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
+
+#[test]
+fn tree_proofs_verify_for_uneven_claim_lists() {
+    for len in 1..=9u64 {
+        let claims: Vec<Claim> = (0..len)
+            .map(|amount| Claim {
+                claimant: Pubkey::new_unique(),
+                amount,
+            })
+            .collect();
+        let tree = MerkleTree::new(&claims);
+        for (index, claim) in claims.iter().enumerate() {
+            let leaf = distribution_leaf(index as u32, &claim.claimant, claim.amount);
+            assert!(verify_merkle_proof(&tree.proof(index), &tree.root(), leaf));
+            let wrong = distribution_leaf(index as u32, &claim.claimant, claim.amount + 1);
+            assert!(!verify_merkle_proof(
+                &tree.proof(index),
+                &tree.root(),
+                wrong
+            ));
+        }
+    }
+}
+
+#[test]
+fn frozen_players_cannot_claim() {
+    let mut airdrop = Airdrop::new(&[5 * JUNK, 7 * JUNK]);
+    let admin = airdrop.admin;
+    let player = airdrop.claims[0].claimant;
+    let ix = airdrop.client.initialize_game(&player, 0);
+    airdrop.harness.process(&ix, &[player]).unwrap();
+    let ix = airdrop
+        .client
+        .set_player_status(&admin, &player, PlayerStatus::Frozen, 0, 0);
+    airdrop.harness.process(&ix, &[admin]).unwrap();
+
+    assert_eq!(airdrop.claim(0), Err(game_error(GameError::PlayerFrozen)));
+    // Claimants who never played need no game
+    airdrop.claim(1).unwrap();
+    assert_eq!(airdrop.vault_balance(), 5 * JUNK);
+}
@@ -0,0 +1,182 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{EmissionSchedule, GameConfig, GameError, SECONDS_PER_DAY};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+const SCHEDULE: EmissionSchedule = EmissionSchedule {
+    initial_epoch_budget: 100 * JUNK,
+    start_epoch: 10,
+    halving_interval: 5,
+    player_daily_cap: 60 * JUNK,
+};
+
+struct Emission {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    players: [Pubkey; 2],
+}
+
+impl Emission {
+    fn new() -> Self {
+        let setup = Setup::builder().players(2).build();
+        let mut emission = Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+            players: [setup.players[0], setup.players[1]],
+        };
+        emission.set_epoch(SCHEDULE.start_epoch);
+        emission
+    }
+
+    fn with_schedule() -> Self {
+        let mut emission = Self::new();
+        emission.set_schedule(SCHEDULE).unwrap();
+        emission
+    }
+
+    fn set_schedule(&mut self, schedule: EmissionSchedule) -> Result<(), ProgramError> {
+        let ix = self.client.set_emission_schedule(&self.admin, schedule);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    fn set_epoch(&mut self, epoch: u64) {
+        let mut clock = self.harness.clock();
+        clock.epoch = epoch;
+        self.harness.set_clock(clock);
+    }
+
+    fn award(&mut self, player: usize, amount: u64) -> Result<(), ProgramError> {
+        let player = self.players[player];
+        let ix = self.client.award_trashcoin(&player, &self.admin, amount);
+        self.harness.process(&ix, &[player, self.admin])
+    }
+
+    fn config(&self) -> GameConfig {
+        self.harness.get(&pda::config(&coin_pusher_game::ID).0)
+    }
+}
+
+#[test]
+fn default_schedule_emits_nothing() {
+    let mut emission = Emission::new();
+
+    assert_eq!(
+        emission.award(0, 1),
+        Err(game_error(GameError::EmissionExhausted))
+    );
+}
+
+#[test]
+fn epoch_budget_is_shared_and_refills_next_epoch() {
+    let mut emission = Emission::with_schedule();
+
+    emission.award(0, 60 * JUNK).unwrap();
+    emission.award(1, 40 * JUNK).unwrap();
+    assert_eq!(
+        emission.award(1, 1),
+        Err(game_error(GameError::EmissionExhausted))
+    );
+
+    emission.set_epoch(SCHEDULE.start_epoch + 1);
+    emission.award(1, 20 * JUNK).unwrap();
+
+    let emitted = emission.config().trashcoin_emission;
+    assert_eq!(emitted.epoch, SCHEDULE.start_epoch + 1);
+    assert_eq!(emitted.epoch_emitted, 20 * JUNK);
+    assert_eq!(emitted.total_emitted, 120 * JUNK);
+
+    let events = decode_events(&coin_pusher_game::ID, &emission.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::TrashcoinAwarded(awarded)]
+            if awarded.amount == 20 * JUNK && awarded.total_emitted == 120 * JUNK
+    ));
+}
+
+#[test]
+fn budget_halves_on_schedule() {
+    assert_eq!(SCHEDULE.epoch_budget(SCHEDULE.start_epoch - 1), 0);
+    assert_eq!(SCHEDULE.epoch_budget(SCHEDULE.start_epoch + 4), 100 * JUNK);
+    assert_eq!(SCHEDULE.epoch_budget(SCHEDULE.start_epoch + 5), 50 * JUNK);
+    assert_eq!(SCHEDULE.epoch_budget(SCHEDULE.start_epoch + 12), 25 * JUNK);
+    assert_eq!(SCHEDULE.epoch_budget(u64::MAX), 0);
+
+    let mut emission = Emission::with_schedule();
+    emission.set_epoch(SCHEDULE.start_epoch + 5);
+    emission.award(0, 50 * JUNK).unwrap();
+    assert_eq!(
+        emission.award(1, 1),
+        Err(game_error(GameError::EmissionExhausted))
+    );
+}
+
+#[test]
+fn nothing_is_emitted_before_start_epoch() {
+    let mut emission = Emission::with_schedule();
+    emission.set_epoch(SCHEDULE.start_epoch - 1);
+
+    assert_eq!(
+        emission.award(0, 1),
+        Err(game_error(GameError::EmissionExhausted))
+    );
+}
+
+#[test]
+fn player_daily_cap_resets_next_day() {
+    let mut emission = Emission::with_schedule();
+
+    emission.award(0, 60 * JUNK).unwrap();
+    assert_eq!(
+        emission.award(0, 1),
+        Err(game_error(GameError::TrashcoinCapExceeded))
+    );
+    // Other players still have their own allowance
+    emission.award(1, 10 * JUNK).unwrap();
+
+    emission.harness.warp_seconds(SECONDS_PER_DAY);
+    emission.set_epoch(SCHEDULE.start_epoch + 1);
+    emission.award(0, 60 * JUNK).unwrap();
+}
+
+#[test]
+fn rejected_award_is_not_counted() {
+    let mut emission = Emission::with_schedule();
+
+    assert_eq!(
+        emission.award(0, 61 * JUNK),
+        Err(game_error(GameError::TrashcoinCapExceeded))
+    );
+    assert_eq!(emission.config().trashcoin_emission.epoch_emitted, 0);
+    emission.award(0, 60 * JUNK).unwrap();
+}
+
+#[test]
+fn award_requires_game_authority() {
+    let mut emission = Emission::with_schedule();
+    let player = emission.players[0];
+
+    let ix = emission.client.award_trashcoin(&player, &player, JUNK);
+    assert_eq!(
+        emission.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
+
+#[test]
+fn set_emission_schedule_requires_admin() {
+    let mut emission = Emission::new();
+    let player = emission.players[0];
+
+    let ix = emission.client.set_emission_schedule(&player, SCHEDULE);
+    assert_eq!(
+        emission.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
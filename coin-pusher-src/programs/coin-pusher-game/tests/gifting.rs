@@ -0,0 +1,236 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    instruction_flags, GameError, GameState, GiftLimits, PlayerStatus, COIN_TYPE_TRASHCOIN,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+const DAY: i64 = 86_400;
+const LIMITS: GiftLimits = GiftLimits {
+    daily_cap: 20,
+    min_account_age: 3_600,
+};
+
+struct Gifting {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    pause_authority: Pubkey,
+    sender: Pubkey,
+    recipient: Pubkey,
+}
+
+impl Gifting {
+    /// Two players with fresh games; no limits configured yet
+    fn new() -> Self {
+        let setup = Setup::builder().players(2).player_junk(100 * JUNK).build();
+        Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+            pause_authority: setup.pause_authority,
+            sender: setup.players[0],
+            recipient: setup.players[1],
+        }
+    }
+
+    /// Limits set and the sender's account old enough to gift
+    fn ready() -> Self {
+        let mut gifting = Self::new();
+        gifting.set_limits(LIMITS).unwrap();
+        gifting.harness.warp_seconds(LIMITS.min_account_age);
+        gifting
+    }
+
+    fn set_limits(&mut self, limits: GiftLimits) -> Result<(), ProgramError> {
+        let ix = self.client.set_gift_limits(&self.admin, limits);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    fn state(&self, player: &Pubkey) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, player).0)
+    }
+
+    /// Win `coins` trashcoin collections (5 credits each) for the sender
+    fn win(&mut self, coins: u32) {
+        for _ in 0..coins {
+            let ix = self.client.collect_coin(
+                &self.sender,
+                &self.admin,
+                COIN_TYPE_TRASHCOIN,
+                CollectOptions::default(),
+            );
+            self.harness
+                .process(&ix, &[self.sender, self.admin])
+                .unwrap();
+        }
+    }
+
+    fn gift(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .gift_balance(&self.sender, &self.recipient, amount);
+        self.harness.process(&ix, &[self.sender])
+    }
+}
+
+#[test]
+fn gift_moves_withdrawable_credits() {
+    let mut gifting = Gifting::ready();
+    gifting.win(2);
+
+    gifting.gift(7).unwrap();
+
+    let sender = gifting.state(&gifting.sender);
+    assert_eq!(sender.balance, 103);
+    assert_eq!(sender.withdrawable_balance(), 3);
+    assert_eq!(sender.gifted_today, 7);
+    let recipient = gifting.state(&gifting.recipient);
+    assert_eq!(recipient.balance, 107);
+    assert_eq!(recipient.promotional_balance, 100);
+    assert_eq!(recipient.withdrawable_balance(), 7);
+
+    let events = decode_events(&coin_pusher_game::ID, &gifting.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::BalanceGifted(gifted)]
+            if gifted.from == gifting.sender
+                && gifted.to == gifting.recipient
+                && gifted.amount == 7
+                && gifted.sender_balance == 103
+                && gifted.recipient_balance == 107
+    ));
+}
+
+#[test]
+fn promotional_credits_are_not_giftable() {
+    let mut gifting = Gifting::ready();
+
+    assert_eq!(
+        gifting.gift(1),
+        Err(game_error(GameError::InsufficientBalance))
+    );
+}
+
+#[test]
+fn play_spends_promotional_credits_first() {
+    let mut gifting = Gifting::ready();
+    gifting.win(1);
+
+    let ix = gifting.client.drop_coin(&gifting.sender);
+    gifting.harness.process(&ix, &[gifting.sender]).unwrap();
+
+    let state = gifting.state(&gifting.sender);
+    assert_eq!(state.balance, 104);
+    assert_eq!(state.promotional_balance, 99);
+    assert_eq!(state.withdrawable_balance(), 5);
+}
+
+#[test]
+fn gift_respects_daily_cap() {
+    let mut gifting = Gifting::ready();
+    gifting.win(6);
+
+    gifting.gift(15).unwrap();
+    assert_eq!(gifting.gift(6), Err(game_error(GameError::GiftCapExceeded)));
+    gifting.gift(5).unwrap();
+
+    // The cap resets at the next day boundary
+    gifting.harness.warp_seconds(DAY);
+    gifting.gift(10).unwrap();
+    assert_eq!(gifting.state(&gifting.sender).gifted_today, 10);
+}
+
+#[test]
+fn gift_requires_account_age() {
+    let mut gifting = Gifting::new();
+    gifting.set_limits(LIMITS).unwrap();
+    gifting.win(1);
+
+    assert_eq!(gifting.gift(1), Err(game_error(GameError::AccountTooNew)));
+    gifting.harness.warp_seconds(LIMITS.min_account_age);
+    gifting.gift(1).unwrap();
+}
+
+#[test]
+fn gift_rejects_self_and_zero_amount() {
+    let mut gifting = Gifting::ready();
+    gifting.win(1);
+
+    let ix = gifting
+        .client
+        .gift_balance(&gifting.sender, &gifting.sender, 1);
+    assert_eq!(
+        gifting.harness.process(&ix, &[gifting.sender]),
+        Err(game_error(GameError::CannotGiftSelf))
+    );
+    assert_eq!(gifting.gift(0), Err(game_error(GameError::InvalidAmount)));
+}
+
+#[test]
+fn default_limits_disable_gifting() {
+    let mut gifting = Gifting::new();
+    gifting.harness.warp_seconds(DAY);
+    gifting.win(1);
+
+    assert_eq!(gifting.gift(1), Err(game_error(GameError::GiftCapExceeded)));
+}
+
+#[test]
+fn set_gift_limits_requires_admin() {
+    let mut gifting = Gifting::new();
+
+    let ix = gifting.client.set_gift_limits(&gifting.sender, LIMITS);
+    assert_eq!(
+        gifting.harness.process(&ix, &[gifting.sender]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
+
+#[test]
+fn kill_flag_disables_gifting() {
+    let mut gifting = Gifting::ready();
+    gifting.win(1);
+
+    let ix = gifting.client.set_pause(
+        &gifting.pause_authority,
+        false,
+        instruction_flags::GIFT_BALANCE,
+    );
+    gifting
+        .harness
+        .process(&ix, &[gifting.pause_authority])
+        .unwrap();
+
+    assert_eq!(
+        gifting.gift(1),
+        Err(game_error(GameError::InstructionDisabled))
+    );
+}
+
+#[test]
+fn frozen_players_neither_send_nor_receive_gifts() {
+    let mut gifting = Gifting::ready();
+    gifting.win(2);
+    let admin = gifting.admin;
+
+    for player in [gifting.sender, gifting.recipient] {
+        let ix = gifting
+            .client
+            .set_player_status(&admin, &player, PlayerStatus::Frozen, 0, 0);
+        gifting.harness.process(&ix, &[admin]).unwrap();
+        assert_eq!(gifting.gift(7), Err(game_error(GameError::PlayerFrozen)));
+        let ix = gifting
+            .client
+            .set_player_status(&admin, &player, PlayerStatus::Active, 0, 0);
+        gifting.harness.process(&ix, &[admin]).unwrap();
+    }
+    gifting.gift(7).unwrap();
+}
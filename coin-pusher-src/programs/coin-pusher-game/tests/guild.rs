@@ -0,0 +1,562 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use coin_pusher_client::instructions::{guild_name, CollectOptions};
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    instruction_flags, CoinType, GameError, GameState, GiftLimits, Guild, GuildMember, GuildRole,
+    GuildSeason, PlayerStatus, COIN_TYPE_NORMAL, GUILD_NAME_LEN, MAX_GUILD_TITHE_BPS,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, start_game, Setup, INITIAL_BALANCE};
+
+const GUILD_ID: u64 = 7;
+const TITHE_BPS: u16 = 1_000;
+const REWARD: u64 = 100;
+const GIFT_LIMITS: GiftLimits = GiftLimits {
+    daily_cap: 40,
+    min_account_age: 0,
+};
+
+/// 2024-01-01
+const SEASON_START: i64 = 1_704_067_200;
+const SEASON_END: i64 = SEASON_START + 30 * 86_400;
+const SEASON: GuildSeason = GuildSeason {
+    season: 1,
+    start: SEASON_START,
+    end: SEASON_END,
+};
+
+struct Guilds {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    pause_authority: Pubkey,
+    leader: Pubkey,
+}
+
+impl Guilds {
+    /// A deployment with `leader`'s guild `GUILD_ID` founded, during `SEASON`
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            pause_authority,
+            ..
+        } = Setup::builder().build();
+        let mut coin_types = CoinType::default_table();
+        coin_types[COIN_TYPE_NORMAL as usize].reward = REWARD;
+        let ix = client.set_coin_types(&admin, coin_types);
+        harness.process(&ix, &[admin]).unwrap();
+        let ix = client.set_guild_season(&admin, SEASON);
+        harness.process(&ix, &[admin]).unwrap();
+        let ix = client.set_gift_limits(&admin, GIFT_LIMITS);
+        harness.process(&ix, &[admin]).unwrap();
+
+        let mut guilds = Self {
+            harness,
+            client,
+            admin,
+            pause_authority,
+            leader: Pubkey::default(),
+        };
+        guilds.set_time(SEASON_START);
+        guilds.leader = guilds.add_player();
+        guilds
+            .create(guilds.leader, GUILD_ID, "Scrap Barons", TITHE_BPS)
+            .unwrap();
+        guilds
+    }
+
+    fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock = self.harness.clock();
+        clock.unix_timestamp = unix_timestamp;
+        self.harness.set_clock(clock);
+    }
+
+    /// A player with a game started
+    fn add_player(&mut self) -> Pubkey {
+        start_game(&mut self.harness, &self.client, 0, INITIAL_BALANCE)
+    }
+
+    /// A player who has joined guild `GUILD_ID`
+    fn add_member(&mut self) -> Pubkey {
+        let player = self.add_player();
+        self.join(player, GUILD_ID).unwrap();
+        player
+    }
+
+    fn create(
+        &mut self,
+        player: Pubkey,
+        guild_id: u64,
+        name: &str,
+        tithe_bps: u16,
+    ) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .create_guild(&player, guild_id, guild_name(name).unwrap(), tithe_bps);
+        self.harness.process(&ix, &[player])
+    }
+
+    fn join(&mut self, player: Pubkey, guild_id: u64) -> Result<(), ProgramError> {
+        let ix = self.client.join_guild(&player, guild_id);
+        self.harness.process(&ix, &[player])
+    }
+
+    fn collect(&mut self, player: Pubkey, guild_id: u64) -> Result<(), ProgramError> {
+        let ix = self.client.collect_coin(
+            &player,
+            &self.admin,
+            COIN_TYPE_NORMAL,
+            CollectOptions {
+                guild_id: Some(guild_id),
+                ..CollectOptions::default()
+            },
+        );
+        self.harness.process(&ix, &[player, self.admin])
+    }
+
+    fn kick(&mut self, officer: Pubkey, player: Pubkey) -> Result<(), ProgramError> {
+        let ix = self.client.kick_guild_member(&officer, GUILD_ID, &player);
+        self.harness.process(&ix, &[officer])
+    }
+
+    fn set_role(
+        &mut self,
+        leader: Pubkey,
+        player: Pubkey,
+        role: GuildRole,
+    ) -> Result<(), ProgramError> {
+        let ix = self.client.set_guild_role(&leader, GUILD_ID, &player, role);
+        self.harness.process(&ix, &[leader])
+    }
+
+    fn set_pause(&mut self, paused: bool, disabled_instructions: u64) {
+        let ix = self
+            .client
+            .set_pause(&self.pause_authority, paused, disabled_instructions);
+        self.harness.process(&ix, &[self.pause_authority]).unwrap();
+    }
+
+    fn guild(&self) -> Guild {
+        self.harness
+            .get(&pda::guild(&coin_pusher_game::ID, GUILD_ID).0)
+    }
+
+    fn member(&self, player: &Pubkey) -> GuildMember {
+        self.harness
+            .get(&pda::guild_member(&coin_pusher_game::ID, player).0)
+    }
+
+    fn state(&self, player: &Pubkey) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, player).0)
+    }
+}
+
+#[test]
+fn founder_leads_and_players_join_one_guild() {
+    let mut guilds = Guilds::new();
+    let member = guilds.add_member();
+
+    let guild = guilds.guild();
+    assert_eq!(guild.leader, guilds.leader);
+    assert_eq!(guild.member_count, 2);
+    assert_eq!(guild.name, guild_name("Scrap Barons").unwrap());
+    assert_eq!(guilds.member(&guilds.leader).role, GuildRole::Leader);
+    assert_eq!(guilds.member(&member).role, GuildRole::Member);
+
+    let events = decode_events(&coin_pusher_game::ID, &guilds.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::GuildJoined(joined)] if joined.player == member && joined.member_count == 2
+    ));
+
+    // The membership is seeded by the player, so a second guild is refused
+    let other = guilds.add_player();
+    guilds
+        .create(other, GUILD_ID + 1, "Rust Bucket", 0)
+        .unwrap();
+    assert!(guilds.join(member, GUILD_ID + 1).is_err());
+    assert!(guilds.create(member, GUILD_ID + 2, "Tin Men", 0).is_err());
+}
+
+#[test]
+fn collections_pay_tithe_and_score_the_season() {
+    let mut guilds = Guilds::new();
+    let member = guilds.add_member();
+
+    guilds.collect(member, GUILD_ID).unwrap();
+    let events = decode_events(&coin_pusher_game::ID, &guilds.harness.logs());
+    assert!(events.iter().any(|event| matches!(
+        event,
+        GameEvent::GuildScoreUpdated(update)
+            if update.player == member
+                && update.season == SEASON.season
+                && update.season_score == REWARD
+                && update.tithe == REWARD / 10
+    )));
+    guilds.collect(guilds.leader, GUILD_ID).unwrap();
+
+    // The player keeps the reward less the tithe but scores all of it
+    let state = guilds.state(&member);
+    assert_eq!(state.balance, 100 + REWARD - REWARD / 10);
+    assert_eq!(state.score, REWARD);
+
+    let guild = guilds.guild();
+    assert_eq!(guild.treasury, 2 * (REWARD / 10));
+    assert_eq!(guild.season_score, 2 * REWARD);
+    assert_eq!(guild.total_score, 2 * REWARD);
+    assert_eq!(guilds.member(&member).season_score, REWARD);
+}
+
+#[test]
+fn scores_only_count_inside_a_season() {
+    let mut guilds = Guilds::new();
+    guilds.collect(guilds.leader, GUILD_ID).unwrap();
+
+    // Between seasons the tithe is still taken but nothing is scored
+    guilds.set_time(SEASON_END);
+    guilds.collect(guilds.leader, GUILD_ID).unwrap();
+    let guild = guilds.guild();
+    assert_eq!(guild.season_score, REWARD);
+    assert_eq!(guild.treasury, 2 * (REWARD / 10));
+
+    // The next season starts from zero
+    let next = GuildSeason {
+        season: 2,
+        start: SEASON_END,
+        end: SEASON_END + 86_400,
+    };
+    let ix = guilds.client.set_guild_season(&guilds.admin, next);
+    guilds.harness.process(&ix, &[guilds.admin]).unwrap();
+    guilds.collect(guilds.leader, GUILD_ID).unwrap();
+
+    let guild = guilds.guild();
+    assert_eq!(guild.season, 2);
+    assert_eq!(guild.season_score, REWARD);
+    assert_eq!(guild.total_score, 2 * REWARD);
+    let leader = guilds.member(&guilds.leader);
+    assert_eq!((leader.season, leader.season_score), (2, REWARD));
+}
+
+#[test]
+fn collection_must_use_the_players_own_guild() {
+    let mut guilds = Guilds::new();
+    let other = guilds.add_player();
+    guilds
+        .create(other, GUILD_ID + 1, "Rust Bucket", 0)
+        .unwrap();
+
+    assert_eq!(
+        guilds.collect(guilds.leader, GUILD_ID + 1),
+        Err(game_error(GameError::InvalidGuildMember))
+    );
+}
+
+#[test]
+fn officers_can_only_kick_plain_members() {
+    let mut guilds = Guilds::new();
+    let leader = guilds.leader;
+    let [officer, other_officer, member] = [(); 3].map(|_| guilds.add_member());
+    guilds
+        .set_role(leader, officer, GuildRole::Officer)
+        .unwrap();
+    guilds
+        .set_role(leader, other_officer, GuildRole::Officer)
+        .unwrap();
+
+    assert_eq!(
+        guilds.kick(member, officer),
+        Err(game_error(GameError::Unauthorized))
+    );
+    assert_eq!(
+        guilds.kick(officer, other_officer),
+        Err(game_error(GameError::Unauthorized))
+    );
+    assert_eq!(
+        guilds.kick(officer, leader),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    guilds.kick(officer, member).unwrap();
+    guilds.kick(leader, other_officer).unwrap();
+    assert_eq!(guilds.guild().member_count, 2);
+    assert!(guilds
+        .harness
+        .account(&pda::guild_member(&coin_pusher_game::ID, &member).0)
+        .is_none());
+
+    // A removed player is free to join again
+    guilds.join(member, GUILD_ID).unwrap();
+}
+
+#[test]
+fn leader_hands_over_and_can_only_leave_an_empty_guild() {
+    let mut guilds = Guilds::new();
+    let leader = guilds.leader;
+    let member = guilds.add_member();
+
+    let ix = guilds.client.leave_guild(&leader, GUILD_ID);
+    assert_eq!(
+        guilds.harness.process(&ix, &[leader]),
+        Err(game_error(GameError::GuildNotEmpty))
+    );
+
+    guilds.set_role(leader, member, GuildRole::Leader).unwrap();
+    assert_eq!(guilds.guild().leader, member);
+    assert_eq!(guilds.member(&leader).role, GuildRole::Officer);
+    assert_eq!(
+        guilds.set_role(leader, member, GuildRole::Member),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    let ix = guilds.client.leave_guild(&leader, GUILD_ID);
+    guilds.harness.process(&ix, &[leader]).unwrap();
+    let ix = guilds.client.leave_guild(&member, GUILD_ID);
+    guilds.harness.process(&ix, &[member]).unwrap();
+    assert!(guilds
+        .harness
+        .account(&pda::guild(&coin_pusher_game::ID, GUILD_ID).0)
+        .is_none());
+}
+
+#[test]
+fn leader_distributes_the_treasury() {
+    let mut guilds = Guilds::new();
+    let leader = guilds.leader;
+    let member = guilds.add_member();
+    for _ in 0..3 {
+        guilds.collect(member, GUILD_ID).unwrap();
+    }
+    let balance = guilds.state(&member).balance;
+
+    let ix = guilds
+        .client
+        .distribute_guild_treasury(&member, GUILD_ID, &member, 10);
+    assert_eq!(
+        guilds.harness.process(&ix, &[member]),
+        Err(game_error(GameError::Unauthorized))
+    );
+    let ix = guilds
+        .client
+        .distribute_guild_treasury(&leader, GUILD_ID, &member, 31);
+    assert_eq!(
+        guilds.harness.process(&ix, &[leader]),
+        Err(game_error(GameError::InsufficientBalance))
+    );
+
+    let ix = guilds
+        .client
+        .distribute_guild_treasury(&leader, GUILD_ID, &member, 25);
+    guilds.harness.process(&ix, &[leader]).unwrap();
+    assert_eq!(guilds.state(&member).balance, balance + 25);
+    assert_eq!(guilds.guild().treasury, 5);
+
+    // The treasury must be paid out before the last member can close it
+    let ix = guilds.client.kick_guild_member(&leader, GUILD_ID, &member);
+    guilds.harness.process(&ix, &[leader]).unwrap();
+    let ix = guilds.client.leave_guild(&leader, GUILD_ID);
+    assert_eq!(
+        guilds.harness.process(&ix, &[leader]),
+        Err(game_error(GameError::GuildNotEmpty))
+    );
+}
+
+#[test]
+fn guild_settings_are_validated() {
+    let mut guilds = Guilds::new();
+    let leader = guilds.leader;
+    let member = guilds.add_member();
+    let player = guilds.add_player();
+
+    assert_eq!(
+        guilds.create(player, GUILD_ID + 1, "", 0),
+        Err(game_error(GameError::InvalidGuildName))
+    );
+    assert_eq!(
+        guilds.create(player, GUILD_ID + 1, "Greedy", MAX_GUILD_TITHE_BPS + 1),
+        Err(game_error(GameError::InvalidGuildTithe))
+    );
+    let mut gap = [0; GUILD_NAME_LEN];
+    gap[1] = b'x';
+    let ix = guilds.client.create_guild(&player, GUILD_ID + 1, gap, 0);
+    assert_eq!(
+        guilds.harness.process(&ix, &[player]),
+        Err(game_error(GameError::InvalidGuildName))
+    );
+
+    // Members can't rename; officers can't change the tithe
+    let name = guild_name("Junk Lords").unwrap();
+    let ix = guilds.client.rename_guild(&member, GUILD_ID, name);
+    assert_eq!(
+        guilds.harness.process(&ix, &[member]),
+        Err(game_error(GameError::Unauthorized))
+    );
+    guilds.set_role(leader, member, GuildRole::Officer).unwrap();
+    let ix = guilds.client.rename_guild(&member, GUILD_ID, name);
+    guilds.harness.process(&ix, &[member]).unwrap();
+    assert_eq!(guilds.guild().name, name);
+
+    let ix = guilds.client.set_guild_tithe(&member, GUILD_ID, 500);
+    assert_eq!(
+        guilds.harness.process(&ix, &[member]),
+        Err(game_error(GameError::Unauthorized))
+    );
+    let ix = guilds.client.set_guild_tithe(&leader, GUILD_ID, 500);
+    guilds.harness.process(&ix, &[leader]).unwrap();
+    assert_eq!(guilds.guild().tithe_bps, 500);
+}
+
+#[test]
+fn set_guild_season_requires_admin() {
+    let mut guilds = Guilds::new();
+    let leader = guilds.leader;
+
+    let ix = guilds.client.set_guild_season(&leader, SEASON);
+    assert_eq!(
+        guilds.harness.process(&ix, &[leader]),
+        Err(game_error(GameError::Unauthorized))
+    );
+    let backwards = GuildSeason {
+        start: SEASON_END,
+        end: SEASON_START,
+        ..SEASON
+    };
+    let ix = guilds.client.set_guild_season(&guilds.admin, backwards);
+    assert_eq!(
+        guilds.harness.process(&ix, &[guilds.admin]),
+        Err(game_error(GameError::InvalidGuildSeason))
+    );
+}
+
+#[test]
+fn frozen_members_are_not_paid_from_the_treasury() {
+    let mut guilds = Guilds::new();
+    let (admin, leader) = (guilds.admin, guilds.leader);
+    let member = guilds.add_member();
+    guilds.collect(member, GUILD_ID).unwrap();
+
+    let ix = guilds
+        .client
+        .set_player_status(&admin, &member, PlayerStatus::Frozen, 0, 0);
+    guilds.harness.process(&ix, &[admin]).unwrap();
+    let ix = guilds
+        .client
+        .distribute_guild_treasury(&leader, GUILD_ID, &member, 10);
+    assert_eq!(
+        guilds.harness.process(&ix, &[leader]),
+        Err(game_error(GameError::PlayerFrozen))
+    );
+    assert_eq!(guilds.guild().treasury, 10);
+}
+
+#[test]
+fn guild_management_stops_while_paused() {
+    let mut guilds = Guilds::new();
+    let leader = guilds.leader;
+    let member = guilds.add_member();
+    guilds.collect(member, GUILD_ID).unwrap();
+
+    guilds.set_pause(true, 0);
+    let name = guild_name("Junk Lords").unwrap();
+    let instructions = [
+        (guilds.client.leave_guild(&member, GUILD_ID), member),
+        (
+            guilds.client.kick_guild_member(&leader, GUILD_ID, &member),
+            leader,
+        ),
+        (
+            guilds
+                .client
+                .set_guild_role(&leader, GUILD_ID, &member, GuildRole::Officer),
+            leader,
+        ),
+        (guilds.client.rename_guild(&leader, GUILD_ID, name), leader),
+        (
+            guilds.client.set_guild_tithe(&leader, GUILD_ID, 500),
+            leader,
+        ),
+        (
+            guilds
+                .client
+                .distribute_guild_treasury(&leader, GUILD_ID, &member, 1),
+            leader,
+        ),
+    ];
+    for (ix, signer) in &instructions {
+        assert_eq!(
+            guilds.harness.process(ix, &[*signer]),
+            Err(game_error(GameError::ProgramPaused))
+        );
+    }
+
+    // Management and payouts can be switched off separately
+    guilds.set_pause(false, instruction_flags::MANAGE_GUILD);
+    let (ix, signer) = &instructions[3];
+    assert_eq!(
+        guilds.harness.process(ix, &[*signer]),
+        Err(game_error(GameError::InstructionDisabled))
+    );
+    let (ix, signer) = &instructions[5];
+    guilds.harness.process(ix, &[*signer]).unwrap();
+    guilds.set_pause(false, instruction_flags::DISTRIBUTE_GUILD_TREASURY);
+    assert_eq!(
+        guilds.harness.process(ix, &[*signer]),
+        Err(game_error(GameError::InstructionDisabled))
+    );
+    let (ix, signer) = &instructions[0];
+    guilds.harness.process(ix, &[*signer]).unwrap();
+}
+
+#[test]
+fn treasury_payouts_follow_the_gift_limits() {
+    let mut guilds = Guilds::new();
+    let (admin, leader) = (guilds.admin, guilds.leader);
+    let member = guilds.add_member();
+    for _ in 0..6 {
+        guilds.collect(member, GUILD_ID).unwrap();
+    }
+    assert_eq!(guilds.guild().treasury, 60);
+
+    let ix = guilds
+        .client
+        .distribute_guild_treasury(&leader, GUILD_ID, &member, 30);
+    guilds.harness.process(&ix, &[leader]).unwrap();
+    let ix = guilds
+        .client
+        .distribute_guild_treasury(&leader, GUILD_ID, &leader, 11);
+    assert_eq!(
+        guilds.harness.process(&ix, &[leader]),
+        Err(game_error(GameError::GiftCapExceeded))
+    );
+
+    // The cap is per guild and UTC day
+    guilds.set_time(SEASON_START + 86_400);
+    guilds.harness.process(&ix, &[leader]).unwrap();
+    assert_eq!(guilds.guild().paid_out_today, 11);
+
+    // New games have to age before they can be paid
+    let limits = GiftLimits {
+        min_account_age: 86_400,
+        ..GIFT_LIMITS
+    };
+    let ix = guilds.client.set_gift_limits(&admin, limits);
+    guilds.harness.process(&ix, &[admin]).unwrap();
+    let newcomer = guilds.add_member();
+    let ix = guilds
+        .client
+        .distribute_guild_treasury(&leader, GUILD_ID, &newcomer, 1);
+    assert_eq!(
+        guilds.harness.process(&ix, &[leader]),
+        Err(game_error(GameError::AccountTooNew))
+    );
+    let ix = guilds
+        .client
+        .distribute_guild_treasury(&leader, GUILD_ID, &member, 1);
+    guilds.harness.process(&ix, &[leader]).unwrap();
+}
@@ -0,0 +1,184 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use coin_pusher_client::accounts::deserialize_zero_copy;
+use coin_pusher_client::instructions::{BumpOptions, CollectOptions, DropOptions};
+use coin_pusher_client::{pda, CoinPusherClient};
+use coin_pusher_game::{
+    EmissionSchedule, PlayEntry, PlayHistory, COIN_TYPE_TRASHCOIN, PLAY_ACTION_BUMP,
+    PLAY_ACTION_COLLECT, PLAY_ACTION_DROP, PLAY_ACTION_TRASHCOIN, PLAY_HISTORY_LEN,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{start_game, Setup, INITIAL_BALANCE, JUNK};
+
+const RECORDED: DropOptions = DropOptions {
+    raffle_round: None,
+    raffle_tickets: false,
+    play_history: true,
+};
+
+struct Recorder {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    player: Pubkey,
+}
+
+impl Recorder {
+    fn new() -> Self {
+        let setup = Setup::builder().build();
+        let mut recorder = Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+            player: Pubkey::default(),
+        };
+        recorder.player = recorder.add_player();
+        recorder
+    }
+
+    /// A player with a game and an empty play history
+    fn add_player(&mut self) -> Pubkey {
+        let player = start_game(&mut self.harness, &self.client, 200 * JUNK, INITIAL_BALANCE);
+        let ix = self.client.initialize_play_history(&player);
+        self.harness.process(&ix, &[player]).unwrap();
+        player
+    }
+
+    fn drop(&mut self) {
+        let ix = self.client.drop_coin_with(&self.player, RECORDED);
+        self.harness.process(&ix, &[self.player]).unwrap();
+    }
+
+    fn history(&self) -> PlayHistory {
+        let address = pda::play_history(&coin_pusher_game::ID, &self.player).0;
+        deserialize_zero_copy(&self.harness.account(&address).unwrap().data).unwrap()
+    }
+}
+
+#[test]
+fn gameplay_instructions_record_plays() {
+    let mut recorder = Recorder::new();
+    let (player, admin) = (recorder.player, recorder.admin);
+    let dropped = recorder.harness.clock().slot;
+
+    recorder.drop();
+    recorder.harness.warp_seconds(1);
+    let slot = recorder.harness.clock().slot;
+    let ix = recorder.client.bump_machine_with(
+        &player,
+        BumpOptions {
+            play_history: true,
+            ..BumpOptions::default()
+        },
+    );
+    recorder.harness.process(&ix, &[player]).unwrap();
+    let ix = recorder.client.collect_coin(
+        &player,
+        &admin,
+        COIN_TYPE_TRASHCOIN,
+        CollectOptions {
+            play_history: true,
+            ..CollectOptions::default()
+        },
+    );
+    recorder.harness.process(&ix, &[player, admin]).unwrap();
+
+    let schedule = EmissionSchedule {
+        initial_epoch_budget: 100 * JUNK,
+        start_epoch: 0,
+        halving_interval: 5,
+        player_daily_cap: 60 * JUNK,
+    };
+    let ix = recorder.client.set_emission_schedule(&admin, schedule);
+    recorder.harness.process(&ix, &[admin]).unwrap();
+    let ix = recorder
+        .client
+        .award_trashcoin_with(&player, &admin, 3 * JUNK, true);
+    recorder.harness.process(&ix, &[player, admin]).unwrap();
+
+    let history = recorder.history();
+    assert_eq!(history.player, player);
+    assert_eq!(history.total_plays, 4);
+    let entry = |slot, action, amount, balance| PlayEntry {
+        slot,
+        amount,
+        balance,
+        action,
+        _padding: [0; 7],
+    };
+    let recent: Vec<PlayEntry> = history.recent().copied().collect();
+    assert_eq!(
+        recent,
+        [
+            entry(slot, PLAY_ACTION_TRASHCOIN, 3 * JUNK, 54),
+            entry(slot, PLAY_ACTION_COLLECT, 5, 54),
+            entry(slot, PLAY_ACTION_BUMP, 50, 49),
+            entry(dropped, PLAY_ACTION_DROP, 1, 99),
+        ]
+    );
+}
+
+#[test]
+fn history_keeps_only_the_latest_plays() {
+    let mut recorder = Recorder::new();
+    for _ in 0..PLAY_HISTORY_LEN + 6 {
+        recorder.drop();
+    }
+
+    let history = recorder.history();
+    assert_eq!(history.total_plays, PLAY_HISTORY_LEN as u64 + 6);
+    let balances: Vec<u64> = history.recent().map(|entry| entry.balance).collect();
+    assert_eq!(balances.len(), PLAY_HISTORY_LEN);
+    assert_eq!(balances[0], 100 - PLAY_HISTORY_LEN as u64 - 6);
+    assert_eq!(balances[PLAY_HISTORY_LEN - 1], 100 - 7);
+    // The oldest entries were overwritten in place
+    assert_eq!(history.entries[5].balance, balances[0]);
+}
+
+#[test]
+fn plays_without_the_history_account_are_not_recorded() {
+    let mut recorder = Recorder::new();
+    let player = recorder.player;
+
+    let ix = recorder.client.drop_coin(&player);
+    recorder.harness.process(&ix, &[player]).unwrap();
+
+    let history = recorder.history();
+    assert_eq!(history.total_plays, 0);
+    assert_eq!(history.recent().count(), 0);
+}
+
+#[test]
+fn history_belongs_to_its_player() {
+    let mut recorder = Recorder::new();
+    let player = recorder.player;
+    let other = recorder.add_player();
+
+    // Record into someone else's history
+    let mut ix = recorder.client.drop_coin_with(&player, RECORDED);
+    let theirs = pda::play_history(&coin_pusher_game::ID, &other).0;
+    let ours = pda::play_history(&coin_pusher_game::ID, &player).0;
+    for meta in &mut ix.accounts {
+        if meta.pubkey == ours {
+            meta.pubkey = theirs;
+        }
+    }
+    assert_eq!(
+        recorder.harness.process(&ix, &[player]),
+        Err(ProgramError::Custom(ErrorCode::ConstraintSeeds.into()))
+    );
+    assert_eq!(recorder.history().total_plays, 0);
+}
+
+#[test]
+fn zero_copy_accounts_check_the_discriminator() {
+    let recorder = Recorder::new();
+    let game_state = pda::game_state(&coin_pusher_game::ID, &recorder.player).0;
+    let data = &recorder.harness.account(&game_state).unwrap().data;
+
+    assert!(deserialize_zero_copy::<PlayHistory>(data).is_err());
+}
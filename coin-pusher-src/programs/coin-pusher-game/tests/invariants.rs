@@ -0,0 +1,417 @@
+//! Property test of the game economy: random sequences of gameplay
+//! instructions from several players, checked against a model after every
+//! step.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::error::TokenError;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{pda, CoinPusherClient};
+use coin_pusher_game::{
+    EmissionSchedule, GameConfig, GameError, GameState, COIN_TYPE_NORMAL, COIN_TYPE_TRASHCOIN,
+    SECONDS_PER_DAY,
+};
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK, VAULT_TRASHCOIN};
+
+const PLAYERS: usize = 4;
+const PLAYER_JUNK: u64 = 200 * JUNK;
+const DROP_COST: u64 = JUNK;
+const BUMP_COST: u64 = 50 * JUNK;
+/// Awards can run into the player cap, the epoch budget or the vault balance
+const EMISSION: EmissionSchedule = EmissionSchedule {
+    initial_epoch_budget: 1_200 * JUNK,
+    start_epoch: 0,
+    halving_interval: 0,
+    player_daily_cap: 400 * JUNK,
+};
+
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    Drop,
+    Bump,
+    /// Types 0 and 1 are the built-in coins; 2 is an unused table slot
+    Collect {
+        coin_type: u8,
+    },
+    Reset,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Play {
+        player: usize,
+        action: Action,
+    },
+    Award {
+        player: usize,
+        amount: u64,
+    },
+    Warp {
+        seconds: i64,
+    },
+    /// `attacker` signs an instruction aimed at `victim`'s game state
+    Intrude {
+        attacker: usize,
+        victim: usize,
+        action: Action,
+    },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        Just(Action::Drop),
+        Just(Action::Bump),
+        (0..3u8).prop_map(|coin_type| Action::Collect { coin_type }),
+        Just(Action::Reset),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        8 => (0..PLAYERS, action()).prop_map(|(player, action)| Op::Play { player, action }),
+        2 => (0..PLAYERS, 0..=300 * JUNK).prop_map(|(player, amount)| Op::Award { player, amount }),
+        3 => (0..3i64).prop_map(|seconds| Op::Warp { seconds }),
+        1 => (0..PLAYERS, 0..PLAYERS, action())
+            .prop_filter("attacker must differ from victim", |(a, v, _)| a != v)
+            .prop_map(|(attacker, victim, action)| Op::Intrude { attacker, victim, action }),
+    ]
+}
+
+/// What the program should hold for one player
+#[derive(Clone, Copy, Debug, Default)]
+struct PlayerModel {
+    score: u64,
+    balance: u64,
+    promotional_balance: u64,
+    total_coins_collected: u64,
+    trashcoins_collected: u64,
+    last_updated: i64,
+    /// Credits won and wagered since the last reset
+    wins: i64,
+    wagers: i64,
+    junk: u64,
+    trashcoin: u64,
+    trashcoin_day: i64,
+    trashcoin_awarded_today: u64,
+}
+
+impl PlayerModel {
+    fn new(now: i64) -> Self {
+        Self {
+            balance: 100,
+            promotional_balance: 100,
+            last_updated: now,
+            junk: PLAYER_JUNK,
+            ..Self::default()
+        }
+    }
+
+    fn wager(&mut self, credits: u64, now: i64) {
+        self.balance = self.balance.saturating_sub(credits);
+        self.promotional_balance = self
+            .promotional_balance
+            .saturating_sub(credits)
+            .min(self.balance);
+        self.wagers += credits as i64;
+        self.last_updated = now;
+    }
+}
+
+struct Sim {
+    harness: Harness,
+    client: CoinPusherClient,
+    game_authority: Pubkey,
+    players: Vec<Pubkey>,
+    model: Vec<PlayerModel>,
+    vault_junk: u64,
+    vault_trashcoin: u64,
+    /// TRASHCOIN awarded in the current epoch
+    epoch_emitted: u64,
+}
+
+impl Sim {
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            players,
+            ..
+        } = Setup::builder()
+            .players(PLAYERS)
+            .player_junk(PLAYER_JUNK)
+            .build();
+        let ix = client.set_emission_schedule(&admin, EMISSION);
+        harness.process(&ix, &[admin]).unwrap();
+        let now = harness.clock().unix_timestamp;
+
+        Self {
+            harness,
+            client,
+            game_authority: admin,
+            players,
+            model: vec![PlayerModel::new(now); PLAYERS],
+            vault_junk: 0,
+            vault_trashcoin: VAULT_TRASHCOIN,
+            epoch_emitted: 0,
+        }
+    }
+
+    fn game_state_address(&self, player: usize) -> Pubkey {
+        pda::game_state(&coin_pusher_game::ID, &self.players[player]).0
+    }
+
+    fn game_state(&self, player: usize) -> GameState {
+        self.harness.get(&self.game_state_address(player))
+    }
+
+    fn instruction(&self, player: usize, action: Action) -> (Instruction, Vec<Pubkey>) {
+        let key = &self.players[player];
+        let ix = match action {
+            Action::Drop => self.client.drop_coin(key),
+            Action::Bump => self.client.bump_machine(key, false),
+            Action::Collect { coin_type } => self.client.collect_coin(
+                key,
+                &self.game_authority,
+                coin_type,
+                CollectOptions::default(),
+            ),
+            Action::Reset => self.client.reset_game(key),
+        };
+        let mut signers = vec![*key];
+        if matches!(action, Action::Collect { .. }) {
+            signers.push(self.game_authority);
+        }
+        (ix, signers)
+    }
+
+    /// Expected outcome of `action`, applied to the model if it succeeds
+    fn expect(&mut self, player: usize, action: Action) -> Result<(), ProgramError> {
+        let now = self.harness.clock().unix_timestamp;
+        let model = &mut self.model[player];
+        match action {
+            Action::Drop => {
+                if model.junk < DROP_COST {
+                    return Err(ProgramError::Custom(TokenError::InsufficientFunds as u32));
+                }
+                model.junk -= DROP_COST;
+                self.vault_junk += DROP_COST;
+                model.wager(1, now);
+            }
+            Action::Bump => {
+                if now - model.last_updated < 1 {
+                    return Err(game_error(GameError::TooManyRequests));
+                }
+                if model.junk < BUMP_COST {
+                    return Err(ProgramError::Custom(TokenError::InsufficientFunds as u32));
+                }
+                model.junk -= BUMP_COST;
+                self.vault_junk += BUMP_COST;
+                model.wager(50, now);
+            }
+            Action::Collect { coin_type } => {
+                let reward = match coin_type {
+                    COIN_TYPE_NORMAL => 1,
+                    COIN_TYPE_TRASHCOIN => 5,
+                    _ => return Err(game_error(GameError::InvalidCoinType)),
+                };
+                let is_trashcoin = coin_type == COIN_TYPE_TRASHCOIN;
+                model.score += reward;
+                model.balance += reward;
+                model.wins += reward as i64;
+                model.total_coins_collected += 1;
+                model.trashcoins_collected += is_trashcoin as u64;
+                model.last_updated = now;
+            }
+            Action::Reset => {
+                *model = PlayerModel {
+                    balance: 100,
+                    promotional_balance: 100,
+                    last_updated: now,
+                    junk: model.junk,
+                    trashcoin: model.trashcoin,
+                    trashcoin_day: model.trashcoin_day,
+                    trashcoin_awarded_today: model.trashcoin_awarded_today,
+                    ..PlayerModel::default()
+                };
+            }
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, op: Op) -> Result<(), TestCaseError> {
+        let collected_before: Vec<u64> = (0..PLAYERS)
+            .map(|player| self.game_state(player).total_coins_collected)
+            .collect();
+
+        match op {
+            Op::Play { player, action } => {
+                let (ix, signers) = self.instruction(player, action);
+                let expected = self.expect(player, action);
+                let result = self.harness.process(&ix, &signers);
+                prop_assert_eq!(result, expected, "{:?}", op);
+            }
+            Op::Award { player, amount } => {
+                let ix = self.client.award_trashcoin(
+                    &self.players[player],
+                    &self.game_authority,
+                    amount,
+                );
+                let expected = self.expect_award(player, amount);
+                let result = self
+                    .harness
+                    .process(&ix, &[self.players[player], self.game_authority]);
+                prop_assert_eq!(result, expected, "{:?}", op);
+            }
+            Op::Warp { seconds } => self.harness.warp_seconds(seconds),
+            Op::Intrude {
+                attacker,
+                victim,
+                action,
+            } => {
+                let (mut ix, signers) = self.instruction(attacker, action);
+                let own = self.game_state_address(attacker);
+                let target = self.game_state_address(victim);
+                for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == own) {
+                    meta.pubkey = target;
+                }
+                // The victim's model is untouched, so `check_player` below
+                // also proves their state didn't change
+                prop_assert!(self.harness.process(&ix, &signers).is_err(), "{:?}", op);
+            }
+        }
+
+        for (player, collected_before) in collected_before.into_iter().enumerate() {
+            let reset = matches!(op, Op::Play { player: p, action: Action::Reset } if p == player);
+            self.check_player(player, collected_before, reset)?;
+        }
+        self.check_tokens()
+    }
+
+    /// Expected outcome of awarding `amount` TRASHCOIN, applied to the model
+    /// if it succeeds
+    fn expect_award(&mut self, player: usize, amount: u64) -> Result<(), ProgramError> {
+        let today = self
+            .harness
+            .clock()
+            .unix_timestamp
+            .div_euclid(SECONDS_PER_DAY);
+        let model = &mut self.model[player];
+        let awarded_today = if model.trashcoin_day == today {
+            model.trashcoin_awarded_today
+        } else {
+            0
+        };
+
+        if amount == 0 {
+            return Err(game_error(GameError::InvalidAmount));
+        }
+        if self.epoch_emitted + amount > EMISSION.initial_epoch_budget {
+            return Err(game_error(GameError::EmissionExhausted));
+        }
+        if awarded_today + amount > EMISSION.player_daily_cap {
+            return Err(game_error(GameError::TrashcoinCapExceeded));
+        }
+        if self.vault_trashcoin < amount {
+            return Err(ProgramError::Custom(TokenError::InsufficientFunds as u32));
+        }
+        self.vault_trashcoin -= amount;
+        self.epoch_emitted += amount;
+        model.trashcoin += amount;
+        model.trashcoin_day = today;
+        model.trashcoin_awarded_today = awarded_today + amount;
+        Ok(())
+    }
+
+    fn check_player(
+        &self,
+        player: usize,
+        collected_before: u64,
+        reset: bool,
+    ) -> Result<(), TestCaseError> {
+        let state = self.game_state(player);
+        let model = &self.model[player];
+
+        prop_assert_eq!(state.player, self.players[player]);
+        prop_assert_eq!(state.score, model.score);
+        prop_assert_eq!(state.balance, model.balance);
+        prop_assert_eq!(state.promotional_balance, model.promotional_balance);
+        prop_assert!(state.promotional_balance <= state.balance);
+        prop_assert_eq!(state.trashcoins_collected, model.trashcoins_collected);
+        prop_assert_eq!(state.last_updated, model.last_updated);
+        prop_assert_eq!(
+            state.net_profit,
+            model.wins - model.wagers,
+            "net_profit != wins - wagers"
+        );
+        prop_assert_eq!(state.total_coins_collected, model.total_coins_collected);
+        if model.trashcoin_awarded_today > 0 {
+            prop_assert_eq!(state.trashcoin_day, model.trashcoin_day);
+            prop_assert_eq!(state.trashcoin_awarded_today, model.trashcoin_awarded_today);
+        }
+        if !reset {
+            prop_assert!(
+                state.total_coins_collected >= collected_before,
+                "total_coins_collected decreased"
+            );
+        }
+        Ok(())
+    }
+
+    fn check_tokens(&self) -> Result<(), TestCaseError> {
+        let deployment = &self.client.deployment;
+        let vault_junk = self.harness.token_balance(&deployment.vault_junk_account);
+        let vault_trashcoin = self
+            .harness
+            .token_balance(&deployment.vault_trashcoin_account);
+
+        let mut player_junk = 0;
+        let mut player_trashcoin = 0;
+        for (player, model) in self.players.iter().zip(&self.model) {
+            let junk = self
+                .harness
+                .token_balance(&get_associated_token_address(player, &deployment.junk_mint));
+            let trashcoin = self.harness.token_balance(&get_associated_token_address(
+                player,
+                &deployment.trashcoin_mint,
+            ));
+            prop_assert_eq!(junk, model.junk);
+            prop_assert_eq!(trashcoin, model.trashcoin);
+            player_junk += junk;
+            player_trashcoin += trashcoin;
+        }
+
+        // The vault holds every wager and covers every award it has paid
+        prop_assert_eq!(vault_junk, self.vault_junk);
+        prop_assert_eq!(vault_junk + player_junk, PLAYERS as u64 * PLAYER_JUNK);
+        prop_assert_eq!(vault_trashcoin, self.vault_trashcoin);
+        prop_assert_eq!(vault_trashcoin + player_trashcoin, VAULT_TRASHCOIN);
+
+        // Every TRASHCOIN paid out was counted against the emission budget
+        let config: GameConfig = self.harness.get(&pda::config(&coin_pusher_game::ID).0);
+        prop_assert_eq!(config.trashcoin_emission.total_emitted, player_trashcoin);
+        prop_assert_eq!(config.trashcoin_emission.epoch_emitted, self.epoch_emitted);
+        prop_assert!(self.epoch_emitted <= EMISSION.initial_epoch_budget);
+        Ok(())
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn economy_invariants_hold(ops in prop::collection::vec(op(), 1..80)) {
+        let mut sim = Sim::new();
+        for op in ops {
+            sim.step(op)?;
+        }
+    }
+}
@@ -0,0 +1,392 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    ActiveEffect, CoinType, GameError, Inventory, ItemDef, ItemKind, PaymentToken, PlayerStatus,
+    COIN_TYPE_NORMAL, COIN_TYPE_TRASHCOIN, MAX_ACTIVE_EFFECTS, MAX_ITEMS,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK, VAULT_TRASHCOIN};
+
+const PLAYER_JUNK: u64 = 100 * JUNK;
+const PLAYER_TRASHCOIN: u64 = 100 * JUNK;
+/// Credits a normal coin pays before any effect
+const REWARD: u64 = 100;
+
+const WALLS: u8 = 0;
+const DOUBLER: u8 = 1;
+const BOOSTER: u8 = 2;
+const DROP: u8 = 3;
+const FREE: u8 = 4;
+const SHORT_WALLS: u8 = 5;
+const EMPTY: u8 = 6;
+
+fn catalogue() -> [ItemDef; MAX_ITEMS] {
+    let mut items = [ItemDef::default(); MAX_ITEMS];
+    items[WALLS as usize] = ItemDef {
+        kind: ItemKind::SideWalls,
+        price_junk: 5 * JUNK,
+        price_trashcoin: 0,
+        duration_slots: 100,
+        multiplier_bps: 0,
+    };
+    items[DOUBLER as usize] = ItemDef {
+        kind: ItemKind::CollectMultiplier,
+        price_junk: 10 * JUNK,
+        price_trashcoin: 2 * JUNK,
+        duration_slots: 100,
+        multiplier_bps: 20_000,
+    };
+    items[BOOSTER as usize] = ItemDef {
+        kind: ItemKind::CollectMultiplier,
+        price_junk: 5 * JUNK,
+        price_trashcoin: JUNK,
+        duration_slots: 50,
+        multiplier_bps: 15_000,
+    };
+    items[DROP as usize] = ItemDef {
+        kind: ItemKind::TrashcoinDrop,
+        price_junk: 0,
+        price_trashcoin: JUNK,
+        duration_slots: 1_000,
+        multiplier_bps: 0,
+    };
+    items[FREE as usize] = ItemDef {
+        price_junk: 0,
+        price_trashcoin: 0,
+        ..items[WALLS as usize]
+    };
+    items[SHORT_WALLS as usize] = ItemDef {
+        price_junk: JUNK,
+        duration_slots: 10,
+        ..items[WALLS as usize]
+    };
+    items
+}
+
+struct Shop {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    player: Pubkey,
+}
+
+impl Shop {
+    /// A player with a game, `PLAYER_JUNK` and `PLAYER_TRASHCOIN`, and the
+    /// catalogue set
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            players,
+            ..
+        } = Setup::builder().players(1).player_junk(PLAYER_JUNK).build();
+        let ix = client.set_item_catalogue(&admin, catalogue());
+        harness.process(&ix, &[admin]).unwrap();
+        let mut coin_types = CoinType::default_table();
+        coin_types[COIN_TYPE_NORMAL as usize].reward = REWARD;
+        let ix = client.set_coin_types(&admin, coin_types);
+        harness.process(&ix, &[admin]).unwrap();
+
+        let player = players[0];
+        let trashcoin_mint = client.deployment.trashcoin_mint;
+        harness.create_token_account_at(
+            get_associated_token_address(&player, &trashcoin_mint),
+            &trashcoin_mint,
+            &player,
+            PLAYER_TRASHCOIN,
+        );
+        Self {
+            harness,
+            client,
+            admin,
+            player,
+        }
+    }
+
+    fn buy(
+        &mut self,
+        item_id: u8,
+        quantity: u16,
+        payment_token: PaymentToken,
+    ) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .buy_item(&self.player, item_id, quantity, payment_token);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn use_item(&mut self, item_id: u8) -> Result<(), ProgramError> {
+        let ix = self.client.use_item(&self.player, item_id);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    /// Buy one of `item_id` with JUNK (or TRASHCOIN if it has no JUNK
+    /// price) and use it
+    fn activate(&mut self, item_id: u8) {
+        let payment_token = if catalogue()[item_id as usize].price_junk > 0 {
+            PaymentToken::Junk
+        } else {
+            PaymentToken::Trashcoin
+        };
+        self.buy(item_id, 1, payment_token).unwrap();
+        self.use_item(item_id).unwrap();
+    }
+
+    /// Credits paid for a coin collected with the inventory attached
+    fn collect(&mut self, coin_type: u8) -> u64 {
+        let ix = self.client.collect_coin(
+            &self.player,
+            &self.admin,
+            coin_type,
+            CollectOptions {
+                inventory: true,
+                ..CollectOptions::default()
+            },
+        );
+        self.harness
+            .process(&ix, &[self.player, self.admin])
+            .unwrap();
+        let events = decode_events(&coin_pusher_game::ID, &self.harness.logs());
+        match events.as_slice() {
+            [GameEvent::CoinCollected(collected)] => collected.amount,
+            _ => panic!("expected a CoinCollected event"),
+        }
+    }
+
+    fn set_status(&mut self, status: PlayerStatus) {
+        let ix = self
+            .client
+            .set_player_status(&self.admin, &self.player, status, 0, 0);
+        self.harness.process(&ix, &[self.admin]).unwrap();
+    }
+
+    fn inventory(&self) -> Inventory {
+        self.harness
+            .get(&pda::inventory(&coin_pusher_game::ID, &self.player).0)
+    }
+
+    /// The item ids and expiry slots of the running effects
+    fn effects(&self) -> Vec<(u8, u64)> {
+        let slot = self.slot();
+        self.inventory()
+            .active_effects
+            .iter()
+            .filter(|effect| effect.is_active(slot))
+            .map(|effect| (effect.item_id, effect.expires_slot))
+            .collect()
+    }
+
+    fn slot(&self) -> u64 {
+        self.harness.clock().slot
+    }
+
+    fn balance(&self, mint: Pubkey) -> u64 {
+        self.harness
+            .token_balance(&get_associated_token_address(&self.player, &mint))
+    }
+}
+
+#[test]
+fn items_are_bought_with_either_token() {
+    let mut shop = Shop::new();
+    let deployment = shop.client.deployment.clone();
+
+    shop.buy(DOUBLER, 2, PaymentToken::Junk).unwrap();
+    assert_eq!(shop.balance(deployment.junk_mint), PLAYER_JUNK - 20 * JUNK);
+    assert_eq!(
+        shop.harness.token_balance(&deployment.vault_junk_account),
+        20 * JUNK
+    );
+
+    shop.buy(DOUBLER, 3, PaymentToken::Trashcoin).unwrap();
+    let events = decode_events(&coin_pusher_game::ID, &shop.harness.logs());
+    match events.as_slice() {
+        [GameEvent::ItemPurchased(purchased)] => {
+            assert_eq!(purchased.payment_token, PaymentToken::Trashcoin);
+            assert_eq!(purchased.price, 6 * JUNK);
+            assert_eq!(purchased.owned, 5);
+        }
+        _ => panic!("expected an ItemPurchased event"),
+    }
+    assert_eq!(
+        shop.balance(deployment.trashcoin_mint),
+        PLAYER_TRASHCOIN - 6 * JUNK
+    );
+    assert_eq!(
+        shop.harness
+            .token_balance(&deployment.vault_trashcoin_account),
+        VAULT_TRASHCOIN + 6 * JUNK
+    );
+
+    let inventory = shop.inventory();
+    assert_eq!(inventory.player, shop.player);
+    assert_eq!(inventory.items[DOUBLER as usize], 5);
+}
+
+#[test]
+fn items_without_a_price_are_not_for_sale() {
+    let mut shop = Shop::new();
+
+    for (item_id, payment_token) in [
+        (WALLS, PaymentToken::Trashcoin),
+        (DROP, PaymentToken::Junk),
+        (FREE, PaymentToken::Junk),
+        (FREE, PaymentToken::Trashcoin),
+    ] {
+        assert_eq!(
+            shop.buy(item_id, 1, payment_token),
+            Err(game_error(GameError::ItemNotForSale))
+        );
+    }
+    assert_eq!(
+        shop.buy(EMPTY, 1, PaymentToken::Junk),
+        Err(game_error(GameError::InvalidItem))
+    );
+    assert_eq!(
+        shop.buy(WALLS, 0, PaymentToken::Junk),
+        Err(game_error(GameError::InvalidAmount))
+    );
+    assert_eq!(shop.balance(shop.client.deployment.junk_mint), PLAYER_JUNK);
+}
+
+#[test]
+fn using_an_active_item_extends_it() {
+    let mut shop = Shop::new();
+    shop.buy(WALLS, 2, PaymentToken::Junk).unwrap();
+    let used_at = shop.slot();
+
+    shop.use_item(WALLS).unwrap();
+    assert_eq!(shop.effects(), vec![(WALLS, used_at + 100)]);
+    shop.harness.warp_slots(40);
+    shop.use_item(WALLS).unwrap();
+    assert_eq!(shop.effects(), vec![(WALLS, used_at + 200)]);
+
+    assert_eq!(
+        shop.use_item(WALLS),
+        Err(game_error(GameError::ItemNotOwned))
+    );
+    assert_eq!(shop.inventory().items[WALLS as usize], 0);
+}
+
+#[test]
+fn only_four_effects_run_at_once() {
+    let mut shop = Shop::new();
+    for item_id in [WALLS, DOUBLER, BOOSTER, SHORT_WALLS] {
+        shop.activate(item_id);
+    }
+    assert_eq!(shop.effects().len(), MAX_ACTIVE_EFFECTS);
+
+    shop.buy(DROP, 1, PaymentToken::Trashcoin).unwrap();
+    assert_eq!(
+        shop.use_item(DROP),
+        Err(game_error(GameError::TooManyActiveEffects))
+    );
+    assert_eq!(shop.inventory().items[DROP as usize], 1);
+
+    // Once one runs out its slot is reused
+    shop.harness.warp_slots(10);
+    shop.use_item(DROP).unwrap();
+    let effects = shop.effects();
+    assert_eq!(effects.len(), MAX_ACTIVE_EFFECTS);
+    assert!(!effects.iter().any(|(item_id, _)| *item_id == SHORT_WALLS));
+}
+
+#[test]
+fn collect_multipliers_stack_until_they_expire() {
+    let mut shop = Shop::new();
+    // Owning an item opens the inventory; only used items count
+    shop.buy(WALLS, 1, PaymentToken::Junk).unwrap();
+    assert_eq!(shop.collect(COIN_TYPE_NORMAL), REWARD);
+
+    shop.activate(DOUBLER);
+    assert_eq!(shop.collect(COIN_TYPE_NORMAL), 2 * REWARD);
+    shop.activate(BOOSTER);
+    assert_eq!(shop.collect(COIN_TYPE_NORMAL), 3 * REWARD);
+    // 5 * 2 * 1.5, and 5 * 1.5 rounds down
+    assert_eq!(shop.collect(COIN_TYPE_TRASHCOIN), 15);
+
+    shop.harness.warp_slots(50);
+    assert_eq!(shop.collect(COIN_TYPE_NORMAL), 2 * REWARD);
+    shop.harness.warp_slots(50);
+    assert_eq!(shop.collect(COIN_TYPE_NORMAL), REWARD);
+
+    shop.activate(BOOSTER);
+    assert_eq!(shop.collect(COIN_TYPE_TRASHCOIN), 7);
+}
+
+#[test]
+fn trashcoin_drops_are_used_up_by_one_trashcoin() {
+    let mut shop = Shop::new();
+    shop.activate(DROP);
+    let expires_slot = shop.effects()[0].1;
+
+    // Normal coins leave it running
+    assert_eq!(shop.collect(COIN_TYPE_NORMAL), REWARD);
+    assert_eq!(shop.effects(), vec![(DROP, expires_slot)]);
+
+    assert_eq!(shop.collect(COIN_TYPE_TRASHCOIN), 5);
+    assert!(shop.effects().is_empty());
+    assert_eq!(
+        shop.inventory().active_effects,
+        [ActiveEffect::default(); MAX_ACTIVE_EFFECTS]
+    );
+    assert_eq!(shop.collect(COIN_TYPE_TRASHCOIN), 5);
+}
+
+#[test]
+fn frozen_players_cannot_buy_or_use_items() {
+    let mut shop = Shop::new();
+    shop.buy(WALLS, 1, PaymentToken::Junk).unwrap();
+
+    shop.set_status(PlayerStatus::Frozen);
+    assert_eq!(
+        shop.buy(WALLS, 1, PaymentToken::Junk),
+        Err(game_error(GameError::PlayerFrozen))
+    );
+    assert_eq!(
+        shop.use_item(WALLS),
+        Err(game_error(GameError::PlayerFrozen))
+    );
+
+    shop.set_status(PlayerStatus::Active);
+    shop.use_item(WALLS).unwrap();
+}
+
+#[test]
+fn only_the_admin_sets_valid_items() {
+    let mut shop = Shop::new();
+    let admin = shop.admin;
+
+    for item in [
+        ItemDef {
+            duration_slots: 0,
+            ..catalogue()[WALLS as usize]
+        },
+        ItemDef {
+            multiplier_bps: 9_999,
+            ..catalogue()[DOUBLER as usize]
+        },
+    ] {
+        let mut items = catalogue();
+        items[EMPTY as usize] = item;
+        let ix = shop.client.set_item_catalogue(&admin, items);
+        assert_eq!(
+            shop.harness.process(&ix, &[admin]),
+            Err(game_error(GameError::InvalidItem))
+        );
+    }
+
+    let player = shop.player;
+    let ix = shop.client.set_item_catalogue(&player, catalogue());
+    assert_eq!(
+        shop.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
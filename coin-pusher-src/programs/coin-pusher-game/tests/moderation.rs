@@ -0,0 +1,242 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    CashOutLimits, EmissionSchedule, GameError, GameState, PlayerStatus, COIN_TYPE_TRASHCOIN,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+/// Reason code the tests file statuses under
+const BOT: u16 = 7;
+
+struct Moderation {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    player: Pubkey,
+}
+
+impl Moderation {
+    /// A player with 95 promotional and 5 withdrawable credits
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            players,
+            ..
+        } = Setup::builder().players(1).player_junk(100 * JUNK).build();
+        let limits = CashOutLimits {
+            player_daily_cap: 30,
+            global_daily_cap: 50,
+            queue_threshold: 20,
+            withdrawal_delay: 3_600,
+        };
+        let ix = client.set_cash_out_limits(&admin, limits);
+        harness.process(&ix, &[admin]).unwrap();
+        let schedule = EmissionSchedule {
+            initial_epoch_budget: 100 * JUNK,
+            start_epoch: 0,
+            halving_interval: 5,
+            player_daily_cap: 60 * JUNK,
+        };
+        let ix = client.set_emission_schedule(&admin, schedule);
+        harness.process(&ix, &[admin]).unwrap();
+
+        let mut moderation = Self {
+            harness,
+            client,
+            admin,
+            player: players[0],
+        };
+        for _ in 0..5 {
+            moderation.drop().unwrap();
+        }
+        moderation.collect().unwrap();
+        moderation
+    }
+
+    fn set_status(&mut self, status: PlayerStatus, expires_at: i64) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .set_player_status(&self.admin, &self.player, status, BOT, expires_at);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    fn drop(&mut self) -> Result<(), ProgramError> {
+        let ix = self.client.drop_coin(&self.player);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn bump(&mut self) -> Result<(), ProgramError> {
+        self.harness.warp_seconds(1);
+        let ix = self.client.bump_machine(&self.player, false);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn collect(&mut self) -> Result<(), ProgramError> {
+        let ix = self.client.collect_coin(
+            &self.player,
+            &self.admin,
+            COIN_TYPE_TRASHCOIN,
+            CollectOptions::default(),
+        );
+        self.harness.process(&ix, &[self.player, self.admin])
+    }
+
+    fn award(&mut self) -> Result<(), ProgramError> {
+        let ix = self.client.award_trashcoin(&self.player, &self.admin, JUNK);
+        self.harness.process(&ix, &[self.player, self.admin])
+    }
+
+    fn reset(&mut self) -> Result<(), ProgramError> {
+        let ix = self.client.reset_game(&self.player);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn cash_out(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let ix = self.client.cash_out(&self.player, amount);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn game_state(&self) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, &self.player).0)
+    }
+}
+
+#[test]
+fn frozen_players_cannot_play_but_can_cash_out() {
+    let mut moderation = Moderation::new();
+    moderation.set_status(PlayerStatus::Frozen, 0).unwrap();
+
+    let frozen = Err(game_error(GameError::PlayerFrozen));
+    assert_eq!(moderation.drop(), frozen);
+    assert_eq!(moderation.bump(), frozen);
+    assert_eq!(moderation.collect(), frozen);
+    assert_eq!(moderation.award(), frozen);
+    assert_eq!(moderation.reset(), frozen);
+
+    moderation.cash_out(5).unwrap();
+    let game_state = moderation.game_state();
+    assert_eq!(game_state.balance, 95);
+    assert_eq!(game_state.promotional_balance, 95);
+    assert_eq!(game_state.status, PlayerStatus::Frozen);
+    assert_eq!(game_state.status_reason, BOT);
+}
+
+#[test]
+fn bans_forfeit_only_promotional_credits() {
+    let mut moderation = Moderation::new();
+    let (player, admin) = (moderation.player, moderation.admin);
+    moderation.set_status(PlayerStatus::Banned, 0).unwrap();
+
+    let game_state = moderation.game_state();
+    assert_eq!(game_state.balance, 5);
+    assert_eq!(game_state.promotional_balance, 0);
+    let events = decode_events(&coin_pusher_game::ID, &moderation.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::PlayerStatusUpdated(updated)]
+            if updated.player == player
+                && updated.moderator == admin
+                && updated.status == PlayerStatus::Banned
+                && updated.reason == BOT
+                && updated.forfeited == 95
+    ));
+
+    assert_eq!(moderation.drop(), Err(game_error(GameError::PlayerBanned)));
+    assert_eq!(moderation.reset(), Err(game_error(GameError::PlayerBanned)));
+    moderation.cash_out(5).unwrap();
+    assert_eq!(moderation.game_state().balance, 0);
+
+    // Starting over would clear the ban and bring new credits
+    let ix = moderation.client.close_game(&player, &player);
+    assert_eq!(
+        moderation.harness.process(&ix, &[player]),
+        Err(game_error(GameError::PlayerBanned))
+    );
+}
+
+#[test]
+fn statuses_lapse_at_expiry() {
+    let mut moderation = Moderation::new();
+    let expires_at = moderation.harness.clock().unix_timestamp + 3_600;
+    moderation
+        .set_status(PlayerStatus::Frozen, expires_at)
+        .unwrap();
+    assert_eq!(moderation.drop(), Err(game_error(GameError::PlayerFrozen)));
+
+    moderation.harness.warp_seconds(3_600);
+    moderation.drop().unwrap();
+    assert_eq!(moderation.game_state().status_expires_at, expires_at);
+}
+
+#[test]
+fn reinstated_players_play_again() {
+    let mut moderation = Moderation::new();
+    let expires_at = moderation.harness.clock().unix_timestamp + 3_600;
+    moderation
+        .set_status(PlayerStatus::Banned, expires_at)
+        .unwrap();
+
+    moderation.set_status(PlayerStatus::Active, 0).unwrap();
+    moderation.drop().unwrap();
+    moderation.bump().unwrap();
+    let game_state = moderation.game_state();
+    assert_eq!(game_state.status, PlayerStatus::Active);
+    assert_eq!(game_state.status_expires_at, 0);
+}
+
+#[test]
+fn expiry_must_be_in_the_future() {
+    let mut moderation = Moderation::new();
+    let now = moderation.harness.clock().unix_timestamp;
+
+    assert_eq!(
+        moderation.set_status(PlayerStatus::Frozen, now),
+        Err(game_error(GameError::InvalidPlayerStatus))
+    );
+    assert_eq!(moderation.game_state().status, PlayerStatus::Active);
+}
+
+#[test]
+fn only_the_moderator_sets_statuses() {
+    let mut moderation = Moderation::new();
+    let (player, admin) = (moderation.player, moderation.admin);
+    let moderator = moderation.harness.new_funded_account();
+
+    // Only the admin rotates the moderator
+    let ix = moderation.client.set_moderator(&moderator, moderator);
+    assert_eq!(
+        moderation.harness.process(&ix, &[moderator]),
+        Err(game_error(GameError::Unauthorized))
+    );
+    let ix = moderation.client.set_moderator(&admin, moderator);
+    moderation.harness.process(&ix, &[admin]).unwrap();
+
+    // The admin is no longer the moderator
+    assert_eq!(
+        moderation.set_status(PlayerStatus::Frozen, 0),
+        Err(game_error(GameError::Unauthorized))
+    );
+    let ix = moderation
+        .client
+        .set_player_status(&moderator, &player, PlayerStatus::Frozen, BOT, 0);
+    moderation.harness.process(&ix, &[moderator]).unwrap();
+    assert_eq!(moderation.game_state().status, PlayerStatus::Frozen);
+
+    // Players can't lift their own status
+    let ix = moderation
+        .client
+        .set_player_status(&player, &player, PlayerStatus::Active, 0, 0);
+    assert_eq!(
+        moderation.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
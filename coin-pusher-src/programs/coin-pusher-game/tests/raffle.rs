@@ -0,0 +1,586 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::DropOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    GameError, Inventory, PlayerStatus, Raffle, RaffleIndex, RaffleRules, RaffleTickets,
+    RedemptionKind, RedemptionOption, MAX_RAFFLE_PLAYERS, MAX_REDEMPTION_OPTIONS,
+    RAFFLE_ENTROPY_DELAY_SLOTS, RAFFLE_REVEAL_SLOTS, RAFFLE_ROUND_SECONDS,
+};
+use pusher_test_harness::{slot_hash, Harness};
+
+mod common;
+
+use common::{game_error, start_game, Setup, INITIAL_BALANCE, JUNK};
+
+const PLAYER_JUNK: u64 = 100 * JUNK;
+/// A week in early 2024
+const ROUND: u64 = 2_818;
+const SEED: [u8; 32] = [7; 32];
+
+const RULES: RaffleRules = RaffleRules {
+    pool_bps: 5_000,
+    tickets_per_drop: 2,
+    payout_bps: [6_000, 4_000, 0],
+};
+
+struct Draw {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+}
+
+impl Draw {
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            ..
+        } = Setup::builder().build();
+        let ix = client.set_raffle_rules(&admin, RULES);
+        harness.process(&ix, &[admin]).unwrap();
+
+        let mut draw = Self {
+            harness,
+            client,
+            admin,
+        };
+        draw.set_time(ROUND as i64 * RAFFLE_ROUND_SECONDS);
+        draw
+    }
+
+    /// A round open since `ROUND` started
+    fn open() -> Self {
+        let mut draw = Self::new();
+        let ix = draw
+            .client
+            .open_raffle(&draw.admin, ROUND, hashv(&[&SEED]).to_bytes());
+        draw.harness.process(&ix, &[draw.admin]).unwrap();
+        draw
+    }
+
+    fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock = self.harness.clock();
+        clock.unix_timestamp = unix_timestamp;
+        self.harness.set_clock(clock);
+    }
+
+    /// End the round, close it and wait out its entropy slot
+    fn end_round(&mut self) {
+        self.set_time((ROUND + 1) as i64 * RAFFLE_ROUND_SECONDS);
+        self.close().unwrap();
+        self.harness.warp_slots(RAFFLE_ENTROPY_DELAY_SLOTS + 1);
+    }
+
+    fn close(&mut self) -> Result<(), ProgramError> {
+        let ix = self.client.close_raffle(ROUND);
+        self.harness.process(&ix, &[])
+    }
+
+    /// A player with a game started, not yet in the raffle
+    fn add_player(&mut self) -> Pubkey {
+        start_game(
+            &mut self.harness,
+            &self.client,
+            PLAYER_JUNK,
+            INITIAL_BALANCE,
+        )
+    }
+
+    fn join(&mut self, player: Pubkey) -> Result<(), ProgramError> {
+        let ix = self.client.join_raffle(&player, ROUND);
+        self.harness.process(&ix, &[player])
+    }
+
+    fn add_entrant(&mut self) -> Pubkey {
+        let player = self.add_player();
+        self.join(player).unwrap();
+        player
+    }
+
+    fn drop(&mut self, player: Pubkey, tickets: bool) {
+        let ix = self.client.drop_coin_with(
+            &player,
+            DropOptions {
+                raffle_round: Some(ROUND),
+                raffle_tickets: tickets,
+                ..DropOptions::default()
+            },
+        );
+        self.harness.process(&ix, &[player]).unwrap();
+    }
+
+    fn draw(&mut self, seed: [u8; 32]) -> Result<(), ProgramError> {
+        let ix = self.client.draw_raffle(ROUND, seed);
+        let payer = self.harness.new_funded_account();
+        self.harness.process(&ix, &[payer])
+    }
+
+    fn set_status(&mut self, player: Pubkey, status: PlayerStatus) {
+        let ix = self
+            .client
+            .set_player_status(&self.admin, &player, status, 0, 0);
+        self.harness.process(&ix, &[self.admin]).unwrap();
+    }
+
+    fn claim(&mut self, player: Pubkey, rank: u8) -> Result<(), ProgramError> {
+        let ix = self.client.claim_raffle_prize(&player, ROUND, rank);
+        self.harness.process(&ix, &[player])
+    }
+
+    fn refund(&mut self, player: Pubkey) -> Result<(), ProgramError> {
+        let ix = self.client.refund_raffle_tickets(&player, ROUND);
+        self.harness.process(&ix, &[player])
+    }
+
+    fn address(&self) -> Pubkey {
+        pda::raffle(&coin_pusher_game::ID, ROUND).0
+    }
+
+    fn raffle(&self) -> Raffle {
+        self.harness.get(&self.address())
+    }
+
+    fn tickets(&self, player: &Pubkey) -> RaffleTickets {
+        self.harness
+            .get(&pda::raffle_tickets(&coin_pusher_game::ID, &self.address(), player).0)
+    }
+
+    /// Ticket number drawn for `rank` from `seed` and the entropy slot's hash
+    fn winning_ticket(&self, seed: [u8; 32], rank: u8) -> u64 {
+        let slot_hash = slot_hash(self.raffle().entropy_slot);
+        let hash = hashv(&[&seed, slot_hash.as_ref(), self.address().as_ref(), &[rank]]);
+        u64::from_le_bytes(hash.to_bytes()[..8].try_into().unwrap()) % self.raffle().total_tickets
+    }
+
+    fn pool_balance(&self) -> u64 {
+        self.harness
+            .token_balance(&pda::raffle_vault(&coin_pusher_game::ID, &self.address()).0)
+    }
+
+    fn house_balance(&self) -> u64 {
+        self.harness
+            .token_balance(&self.client.deployment.vault_junk_account)
+    }
+
+    fn junk_balance(&self, player: &Pubkey) -> u64 {
+        self.harness.token_balance(&get_associated_token_address(
+            player,
+            &self.client.deployment.junk_mint,
+        ))
+    }
+}
+
+#[test]
+fn drops_fill_the_pool_and_credit_tickets() {
+    let mut draw = Draw::open();
+    let entrant = draw.add_entrant();
+    let bystander = draw.add_player();
+
+    for _ in 0..3 {
+        draw.drop(entrant, true);
+    }
+    let events = decode_events(&coin_pusher_game::ID, &draw.harness.logs());
+    assert!(events.iter().any(|event| matches!(
+        event,
+        GameEvent::RaffleTicketsCredited(credited)
+            if credited.player == entrant
+                && credited.tickets == 2
+                && credited.player_tickets == 6
+                && credited.total_tickets == 6
+    )));
+
+    // Players outside the raffle still feed the pool
+    draw.drop(bystander, false);
+
+    let raffle = draw.raffle();
+    assert_eq!(raffle.prize_pool, 4 * JUNK / 2);
+    assert_eq!(raffle.total_tickets, 6);
+    assert_eq!(raffle.player_count, 1);
+    assert_eq!(draw.tickets(&entrant).tickets, 6);
+
+    // The pool sits in the round's own account, not the house vault
+    assert_eq!(draw.pool_balance(), 2 * JUNK);
+    assert_eq!(draw.house_balance(), 2 * JUNK);
+}
+
+#[test]
+fn ticket_index_maps_tickets_to_slots() {
+    let mut index = RaffleIndex {
+        raffle: Pubkey::default(),
+        tree: vec![0; MAX_RAFFLE_PLAYERS],
+        bump: 0,
+    };
+    // Slot 0 holds tickets 0..3, slot 2 holds 3..4, slot 5 holds 4..9
+    index.add(0, 2);
+    index.add(2, 1);
+    index.add(5, 5);
+    index.add(0, 1);
+
+    assert_eq!(index.prefix_sum(6), 9);
+    assert_eq!(index.prefix_sum(2), 3);
+    let slots: Vec<u32> = (0..9).map(|ticket| index.find(ticket)).collect();
+    assert_eq!(slots, [0, 0, 0, 2, 5, 5, 5, 5, 5]);
+
+    index.add(MAX_RAFFLE_PLAYERS as u32 - 1, 1);
+    assert_eq!(index.find(9), MAX_RAFFLE_PLAYERS as u32 - 1);
+}
+
+#[test]
+fn winners_are_drawn_by_ticket_ranges() {
+    let mut draw = Draw::open();
+    let players = [(); 3].map(|_| draw.add_entrant());
+    for (slot, player) in players.iter().enumerate() {
+        for _ in 0..=slot {
+            draw.drop(*player, true);
+        }
+    }
+    // Slots hold tickets 0..2, 2..6 and 6..12
+    let total = 12;
+    draw.end_round();
+    draw.draw(SEED).unwrap();
+
+    let raffle = draw.raffle();
+    let pool = 6 * JUNK / 2;
+    assert_eq!(raffle.prize_pool, pool);
+    assert_eq!(raffle.total_tickets, total);
+    for rank in 0..2u8 {
+        let slot = match draw.winning_ticket(SEED, rank) {
+            0..=1 => 0,
+            2..=5 => 1,
+            _ => 2,
+        };
+        assert_eq!(raffle.winning_slots[rank as usize], slot);
+        assert_eq!(
+            raffle.prizes[rank as usize],
+            pool * RULES.payout_bps[rank as usize] as u64 / 10_000
+        );
+
+        let winner = players[slot as usize];
+        let balance = draw.junk_balance(&winner);
+        draw.claim(winner, rank).unwrap();
+        assert_eq!(
+            draw.junk_balance(&winner),
+            balance + raffle.prizes[rank as usize]
+        );
+    }
+    assert_eq!(raffle.prizes[2], 0);
+    assert_eq!(draw.pool_balance(), 0);
+}
+
+#[test]
+fn only_the_winning_slot_can_claim_once() {
+    let mut draw = Draw::open();
+    let winner = draw.add_entrant();
+    let empty_handed = draw.add_entrant();
+    draw.drop(winner, true);
+    draw.end_round();
+
+    assert_eq!(
+        draw.claim(winner, 0),
+        Err(game_error(GameError::RaffleNotEnded))
+    );
+    assert_eq!(draw.pool_balance(), JUNK / 2);
+    draw.draw(SEED).unwrap();
+
+    // The only ticket holder wins both prizes
+    assert_eq!(draw.raffle().winning_slots, [0, 0, 0]);
+    assert_eq!(
+        draw.claim(empty_handed, 0),
+        Err(game_error(GameError::Unauthorized))
+    );
+    draw.claim(winner, 0).unwrap();
+    draw.claim(winner, 1).unwrap();
+    assert_eq!(
+        draw.claim(winner, 0),
+        Err(game_error(GameError::PrizeAlreadyClaimed))
+    );
+    assert_eq!(
+        draw.claim(winner, 2),
+        Err(game_error(GameError::Unauthorized))
+    );
+    assert_eq!(draw.junk_balance(&winner), PLAYER_JUNK - JUNK + JUNK / 2);
+    assert_eq!(draw.pool_balance(), 0);
+}
+
+#[test]
+fn draw_reveals_the_committed_seed_after_the_round() {
+    let mut draw = Draw::open();
+    let entrant = draw.add_entrant();
+    draw.drop(entrant, true);
+
+    assert_eq!(draw.close(), Err(game_error(GameError::RaffleNotEnded)));
+    assert_eq!(draw.draw(SEED), Err(game_error(GameError::RaffleNotClosed)));
+    draw.end_round();
+
+    // Late drops and joins don't count
+    draw.drop(entrant, true);
+    assert_eq!(draw.raffle().total_tickets, 2);
+    let late = draw.add_player();
+    assert_eq!(draw.join(late), Err(game_error(GameError::RaffleClosed)));
+
+    assert_eq!(
+        draw.draw([8; 32]),
+        Err(game_error(GameError::InvalidRaffleReveal))
+    );
+    draw.draw(SEED).unwrap();
+    let events = decode_events(&coin_pusher_game::ID, &draw.harness.logs());
+    let entropy_slot = draw.raffle().entropy_slot;
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::RaffleDrawn(drawn)]
+            if drawn.seed == SEED
+                && drawn.slot_hash == slot_hash(entropy_slot).to_bytes()
+                && drawn.total_tickets == 2
+    ));
+    assert_eq!(
+        draw.draw(SEED),
+        Err(game_error(GameError::RaffleAlreadyDrawn))
+    );
+    assert_eq!(draw.close(), Err(game_error(GameError::RaffleAlreadyDrawn)));
+}
+
+#[test]
+fn draw_waits_for_the_entropy_slot() {
+    let mut draw = Draw::open();
+    let entrant = draw.add_entrant();
+    draw.drop(entrant, true);
+    draw.set_time((ROUND + 1) as i64 * RAFFLE_ROUND_SECONDS);
+
+    let closed_at = draw.harness.clock().slot;
+    draw.close().unwrap();
+    let entropy_slot = closed_at + RAFFLE_ENTROPY_DELAY_SLOTS;
+    let events = decode_events(&coin_pusher_game::ID, &draw.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::RaffleClosed(closed)]
+            if closed.entropy_slot == entropy_slot && !closed.seed_forfeited
+    ));
+
+    // Nobody knows the hash until the slot has been produced
+    draw.harness.warp_slots(RAFFLE_ENTROPY_DELAY_SLOTS);
+    assert_eq!(
+        draw.draw(SEED),
+        Err(game_error(GameError::RaffleEntropyUnavailable))
+    );
+    draw.harness.warp_slots(1);
+    draw.draw(SEED).unwrap();
+}
+
+#[test]
+fn withholding_the_seed_refunds_the_pool_instead_of_redrawing() {
+    let mut draw = Draw::open();
+    // Four tickets and two
+    let entrants = [(); 2].map(|_| draw.add_entrant());
+    draw.drop(entrants[0], true);
+    draw.drop(entrants[0], true);
+    draw.drop(entrants[1], true);
+    draw.end_round();
+    let entropy_slot = draw.raffle().entropy_slot;
+    let pool = draw.pool_balance();
+    assert_eq!(pool, 3 * JUNK / 2);
+
+    // Closing again can't re-roll the entropy while the seed can be revealed
+    draw.harness.warp_slots(RAFFLE_REVEAL_SLOTS - 1);
+    assert_eq!(
+        draw.close(),
+        Err(game_error(GameError::RaffleRevealPending))
+    );
+    assert_eq!(
+        draw.refund(entrants[0]),
+        Err(game_error(GameError::RaffleNotForfeited))
+    );
+    draw.harness.warp_slots(1);
+    assert_eq!(
+        draw.draw(SEED),
+        Err(game_error(GameError::RaffleRevealExpired))
+    );
+
+    // Forfeiting keeps the entropy slot and can only happen once
+    draw.close().unwrap();
+    let raffle = draw.raffle();
+    assert!(raffle.seed_forfeited);
+    assert_eq!(raffle.entropy_slot, entropy_slot);
+    assert_eq!(
+        draw.close(),
+        Err(game_error(GameError::RaffleRevealExpired))
+    );
+
+    // No seed, withheld or not, draws the round any more
+    draw.harness.warp_slots(RAFFLE_ENTROPY_DELAY_SLOTS + 1);
+    for seed in [SEED, [0; 32]] {
+        assert_eq!(
+            draw.draw(seed),
+            Err(game_error(GameError::RaffleRevealExpired))
+        );
+    }
+    assert!(!draw.raffle().drawn);
+
+    // The whole pool goes back to the entrants by tickets, once each
+    let house = draw.house_balance();
+    let before = entrants.map(|entrant| draw.junk_balance(&entrant));
+    for entrant in entrants {
+        draw.refund(entrant).unwrap();
+    }
+    let events = decode_events(&coin_pusher_game::ID, &draw.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::RaffleRefunded(refunded)]
+            if refunded.player == entrants[1] && refunded.tickets == 2 && refunded.amount == JUNK / 2
+    ));
+    assert_eq!(draw.junk_balance(&entrants[0]), before[0] + JUNK);
+    assert_eq!(draw.junk_balance(&entrants[1]), before[1] + JUNK / 2);
+    assert_eq!(draw.pool_balance(), 0);
+    assert_eq!(draw.house_balance(), house);
+    assert!(draw.refund(entrants[0]).is_err());
+    assert!(draw.claim(entrants[0], 0).is_err());
+}
+
+#[test]
+fn an_unwon_pool_goes_back_to_the_house() {
+    let mut draw = Draw::open();
+    let bystander = draw.add_player();
+    draw.drop(bystander, false);
+    assert_eq!(draw.pool_balance(), JUNK / 2);
+    assert_eq!(draw.house_balance(), JUNK / 2);
+
+    draw.end_round();
+    draw.draw(SEED).unwrap();
+    let events = decode_events(&coin_pusher_game::ID, &draw.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::RaffleDrawn(drawn)] if drawn.house_amount == JUNK / 2
+    ));
+    assert_eq!(draw.raffle().prizes, [0; 3]);
+    assert_eq!(draw.pool_balance(), 0);
+    assert_eq!(draw.house_balance(), JUNK);
+}
+
+#[test]
+fn redeemed_entries_become_tickets() {
+    let mut draw = Draw::open();
+    let player = draw.add_entrant();
+    let deployment = draw.client.deployment.clone();
+
+    let mut options = [RedemptionOption::default(); MAX_REDEMPTION_OPTIONS];
+    options[0] = RedemptionOption {
+        kind: RedemptionKind::RaffleEntry,
+        price_trashcoin: JUNK,
+        burn: true,
+        stock: 10,
+        ..RedemptionOption::default()
+    };
+    let ix = draw.client.set_redemption_options(&draw.admin, options);
+    draw.harness.process(&ix, &[draw.admin]).unwrap();
+    draw.harness.create_token_account_at(
+        get_associated_token_address(&player, &deployment.trashcoin_mint),
+        &deployment.trashcoin_mint,
+        &player,
+        10 * JUNK,
+    );
+    let ix = draw.client.redeem(&player, 0, 3);
+    draw.harness.process(&ix, &[player]).unwrap();
+
+    let ix = draw.client.use_raffle_entries(&player, ROUND, 2);
+    draw.harness.process(&ix, &[player]).unwrap();
+    assert_eq!(draw.tickets(&player).tickets, 2);
+    assert_eq!(draw.raffle().total_tickets, 2);
+    let inventory: Inventory = draw
+        .harness
+        .get(&pda::inventory(&coin_pusher_game::ID, &player).0);
+    assert_eq!(inventory.raffle_entries, 1);
+
+    let ix = draw.client.use_raffle_entries(&player, ROUND, 2);
+    assert_eq!(
+        draw.harness.process(&ix, &[player]),
+        Err(game_error(GameError::InsufficientBalance))
+    );
+}
+
+#[test]
+fn open_raffle_requires_admin_and_a_future_round() {
+    let mut draw = Draw::new();
+    let player = draw.add_player();
+    let seed_hash = hashv(&[&SEED]).to_bytes();
+
+    let ix = draw.client.open_raffle(&player, ROUND, seed_hash);
+    assert_eq!(
+        draw.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+    let ix = draw.client.open_raffle(&draw.admin, ROUND - 1, seed_hash);
+    assert_eq!(
+        draw.harness.process(&ix, &[draw.admin]),
+        Err(game_error(GameError::RaffleClosed))
+    );
+    let ix = draw.client.open_raffle(&draw.admin, ROUND + 1, seed_hash);
+    draw.harness.process(&ix, &[draw.admin]).unwrap();
+}
+
+#[test]
+fn set_raffle_rules_validates_and_requires_admin() {
+    let mut draw = Draw::new();
+    let player = draw.add_player();
+
+    let ix = draw.client.set_raffle_rules(&player, RULES);
+    assert_eq!(
+        draw.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+    for rules in [
+        RaffleRules {
+            pool_bps: 10_001,
+            ..RULES
+        },
+        RaffleRules {
+            payout_bps: [6_000, 3_000, 0],
+            ..RULES
+        },
+    ] {
+        let ix = draw.client.set_raffle_rules(&draw.admin, rules);
+        assert_eq!(
+            draw.harness.process(&ix, &[draw.admin]),
+            Err(game_error(GameError::InvalidRaffle))
+        );
+    }
+
+    // A round can't open without tickets to hand out
+    let rules = RaffleRules {
+        tickets_per_drop: 0,
+        ..RULES
+    };
+    let ix = draw.client.set_raffle_rules(&draw.admin, rules);
+    draw.harness.process(&ix, &[draw.admin]).unwrap();
+    let ix = draw
+        .client
+        .open_raffle(&draw.admin, ROUND, hashv(&[&SEED]).to_bytes());
+    assert_eq!(
+        draw.harness.process(&ix, &[draw.admin]),
+        Err(game_error(GameError::InvalidRaffle))
+    );
+}
+
+#[test]
+fn frozen_players_cannot_join_or_claim() {
+    let mut draw = Draw::open();
+    let winner = draw.add_entrant();
+    let frozen = draw.add_player();
+    draw.drop(winner, true);
+
+    draw.set_status(frozen, PlayerStatus::Frozen);
+    assert_eq!(draw.join(frozen), Err(game_error(GameError::PlayerFrozen)));
+
+    draw.end_round();
+    draw.draw(SEED).unwrap();
+    draw.set_status(winner, PlayerStatus::Frozen);
+    assert_eq!(
+        draw.claim(winner, 0),
+        Err(game_error(GameError::PlayerFrozen))
+    );
+    // The prize waits until the freeze is lifted
+    draw.set_status(winner, PlayerStatus::Active);
+    draw.claim(winner, 0).unwrap();
+}
@@ -0,0 +1,293 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    GameConfig, GameError, Inventory, PlayerStatus, RedemptionKind, RedemptionOption,
+    MAX_REDEMPTION_OPTIONS, MAX_SKINS,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, start_game, Setup, JUNK, VAULT_TRASHCOIN};
+
+const PLAYER_TRASHCOIN: u64 = 100 * JUNK;
+const VAULT_JUNK: u64 = 1_000 * JUNK;
+
+const JUNK_OPTION: u8 = 0;
+const SKIN_OPTION: u8 = 1;
+const RAFFLE_OPTION: u8 = 2;
+const SKIN_ID: u8 = 7;
+
+fn catalogue() -> [RedemptionOption; MAX_REDEMPTION_OPTIONS] {
+    let mut options = [RedemptionOption::default(); MAX_REDEMPTION_OPTIONS];
+    options[JUNK_OPTION as usize] = RedemptionOption {
+        kind: RedemptionKind::Junk,
+        price_trashcoin: 10 * JUNK,
+        junk_amount: 2 * JUNK,
+        stock: 5,
+        wallet_limit: 3,
+        ..RedemptionOption::default()
+    };
+    options[SKIN_OPTION as usize] = RedemptionOption {
+        kind: RedemptionKind::Skin,
+        price_trashcoin: 20 * JUNK,
+        burn: true,
+        skin_id: SKIN_ID,
+        stock: 10,
+        ..RedemptionOption::default()
+    };
+    options[RAFFLE_OPTION as usize] = RedemptionOption {
+        kind: RedemptionKind::RaffleEntry,
+        price_trashcoin: JUNK,
+        burn: true,
+        stock: 100,
+        ..RedemptionOption::default()
+    };
+    options
+}
+
+struct Shop {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    player: Pubkey,
+}
+
+impl Shop {
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            ..
+        } = Setup::builder().vault_junk(VAULT_JUNK).build();
+        let ix = client.set_redemption_options(&admin, catalogue());
+        harness.process(&ix, &[admin]).unwrap();
+
+        let mut shop = Self {
+            harness,
+            client,
+            admin,
+            player: Pubkey::default(),
+        };
+        shop.player = shop.add_player();
+        shop
+    }
+
+    /// A player with a game, holding `PLAYER_TRASHCOIN`
+    fn add_player(&mut self) -> Pubkey {
+        let player = start_game(&mut self.harness, &self.client, 0, 0);
+        let trashcoin_mint = self.client.deployment.trashcoin_mint;
+        self.harness.create_token_account_at(
+            get_associated_token_address(&player, &trashcoin_mint),
+            &trashcoin_mint,
+            &player,
+            PLAYER_TRASHCOIN,
+        );
+        player
+    }
+
+    fn redeem_as(
+        &mut self,
+        player: Pubkey,
+        option_id: u8,
+        quantity: u32,
+    ) -> Result<(), ProgramError> {
+        let ix = self.client.redeem(&player, option_id, quantity);
+        self.harness.process(&ix, &[player])
+    }
+
+    fn redeem(&mut self, option_id: u8, quantity: u32) -> Result<(), ProgramError> {
+        self.redeem_as(self.player, option_id, quantity)
+    }
+
+    fn inventory(&self) -> Inventory {
+        self.harness
+            .get(&pda::inventory(&coin_pusher_game::ID, &self.player).0)
+    }
+
+    fn token_balance(&self, mint: &Pubkey) -> u64 {
+        self.harness
+            .token_balance(&get_associated_token_address(&self.player, mint))
+    }
+
+    fn stock(&self, option_id: u8) -> u32 {
+        let config: GameConfig = self.harness.get(&pda::config(&coin_pusher_game::ID).0);
+        config.redemption_options[option_id as usize].stock
+    }
+}
+
+#[test]
+fn redeem_for_junk_returns_trashcoin_to_vault() {
+    let mut shop = Shop::new();
+    let deployment = shop.client.deployment.clone();
+
+    shop.redeem(JUNK_OPTION, 2).unwrap();
+
+    assert_eq!(
+        shop.token_balance(&deployment.trashcoin_mint),
+        PLAYER_TRASHCOIN - 20 * JUNK
+    );
+    assert_eq!(shop.token_balance(&deployment.junk_mint), 4 * JUNK);
+    assert_eq!(
+        shop.harness
+            .token_balance(&deployment.vault_trashcoin_account),
+        VAULT_TRASHCOIN + 20 * JUNK
+    );
+    assert_eq!(
+        shop.harness.token_balance(&deployment.vault_junk_account),
+        VAULT_JUNK - 4 * JUNK
+    );
+    assert_eq!(shop.stock(JUNK_OPTION), 3);
+    assert_eq!(shop.inventory().redeemed[JUNK_OPTION as usize], 2);
+
+    let events = decode_events(&coin_pusher_game::ID, &shop.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::Redeemed(redeemed)]
+            if redeemed.kind == RedemptionKind::Junk
+                && redeemed.quantity == 2
+                && redeemed.trashcoin_spent == 20 * JUNK
+                && !redeemed.burned
+                && redeemed.junk_paid == 4 * JUNK
+                && redeemed.stock_remaining == 3
+    ));
+}
+
+#[test]
+fn skin_redemption_burns_trashcoin_and_records_skin() {
+    let mut shop = Shop::new();
+    let trashcoin_mint = shop.client.deployment.trashcoin_mint;
+    let supply = shop.harness.mint_supply(&trashcoin_mint);
+
+    shop.redeem(SKIN_OPTION, 1).unwrap();
+
+    assert_eq!(
+        shop.harness.mint_supply(&trashcoin_mint),
+        supply - 20 * JUNK
+    );
+    let inventory = shop.inventory();
+    assert!(inventory.owns_skin(SKIN_ID));
+    assert!(!inventory.owns_skin(SKIN_ID + 1));
+
+    // A skin can only be owned once
+    assert_eq!(
+        shop.redeem(SKIN_OPTION, 1),
+        Err(game_error(GameError::RedemptionLimitReached))
+    );
+}
+
+#[test]
+fn raffle_entries_accumulate_in_inventory() {
+    let mut shop = Shop::new();
+
+    shop.redeem(RAFFLE_OPTION, 3).unwrap();
+    shop.redeem(RAFFLE_OPTION, 4).unwrap();
+
+    assert_eq!(shop.inventory().raffle_entries, 7);
+    assert_eq!(shop.stock(RAFFLE_OPTION), 93);
+}
+
+#[test]
+fn per_wallet_limit_is_enforced() {
+    let mut shop = Shop::new();
+
+    shop.redeem(JUNK_OPTION, 2).unwrap();
+    assert_eq!(
+        shop.redeem(JUNK_OPTION, 2),
+        Err(game_error(GameError::RedemptionLimitReached))
+    );
+    shop.redeem(JUNK_OPTION, 1).unwrap();
+}
+
+#[test]
+fn stock_is_shared_between_players() {
+    let mut shop = Shop::new();
+    let other = shop.add_player();
+
+    shop.redeem(JUNK_OPTION, 3).unwrap();
+    assert_eq!(
+        shop.redeem_as(other, JUNK_OPTION, 3),
+        Err(game_error(GameError::RedemptionSoldOut))
+    );
+    shop.redeem_as(other, JUNK_OPTION, 2).unwrap();
+    assert_eq!(shop.stock(JUNK_OPTION), 0);
+}
+
+#[test]
+fn redeem_rejects_bad_requests() {
+    let mut shop = Shop::new();
+
+    assert_eq!(
+        shop.redeem(JUNK_OPTION, 0),
+        Err(game_error(GameError::InvalidAmount))
+    );
+    assert_eq!(
+        shop.redeem(3, 1),
+        Err(game_error(GameError::InvalidRedemption))
+    );
+    assert_eq!(
+        shop.redeem(MAX_REDEMPTION_OPTIONS as u8, 1),
+        Err(game_error(GameError::InvalidRedemption))
+    );
+}
+
+#[test]
+fn set_redemption_options_validates_entries() {
+    let mut shop = Shop::new();
+    let invalid = [
+        RedemptionOption {
+            price_trashcoin: 0,
+            ..catalogue()[RAFFLE_OPTION as usize]
+        },
+        RedemptionOption {
+            junk_amount: 0,
+            ..catalogue()[JUNK_OPTION as usize]
+        },
+        RedemptionOption {
+            skin_id: MAX_SKINS,
+            ..catalogue()[SKIN_OPTION as usize]
+        },
+    ];
+    for option in invalid {
+        let mut options = catalogue();
+        options[7] = option;
+        let ix = shop.client.set_redemption_options(&shop.admin, options);
+        assert_eq!(
+            shop.harness.process(&ix, &[shop.admin]),
+            Err(game_error(GameError::InvalidRedemption)),
+            "{option:?}"
+        );
+    }
+}
+
+#[test]
+fn set_redemption_options_requires_admin() {
+    let mut shop = Shop::new();
+
+    let ix = shop
+        .client
+        .set_redemption_options(&shop.player, catalogue());
+    assert_eq!(
+        shop.harness.process(&ix, &[shop.player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
+
+#[test]
+fn frozen_players_cannot_redeem() {
+    let mut shop = Shop::new();
+    let (admin, player) = (shop.admin, shop.player);
+    let ix = shop
+        .client
+        .set_player_status(&admin, &player, PlayerStatus::Frozen, 0, 0);
+    shop.harness.process(&ix, &[admin]).unwrap();
+
+    assert_eq!(
+        shop.redeem(JUNK_OPTION, 1),
+        Err(game_error(GameError::PlayerFrozen))
+    );
+    assert_eq!(shop.stock(JUNK_OPTION), 5);
+}
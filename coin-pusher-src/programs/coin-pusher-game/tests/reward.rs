@@ -0,0 +1,374 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{instruction_flags, GameError, PlayerStatus, RewardVault};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+const PARTNER: u64 = 1;
+const VAULT_PARTNER: u64 = 500 * JUNK;
+
+/// Point every use of account `from` in `ix` at `to` instead
+fn swap_account(ix: &mut Instruction, from: Pubkey, to: Pubkey) {
+    for meta in &mut ix.accounts {
+        if meta.pubkey == from {
+            meta.pubkey = to;
+        }
+    }
+}
+
+struct Partner {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    pause_authority: Pubkey,
+    player: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+}
+
+impl Partner {
+    /// A deployment with a partner mint under `token_program`, its funded
+    /// vault and a player with a game and an empty account for the mint
+    fn new(token_program: Pubkey) -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            pause_authority,
+            players,
+        } = Setup::builder().players(1).build();
+        let mint = if token_program == spl_token_2022::ID {
+            harness.create_token_2022_mint(&admin, 6)
+        } else {
+            harness.create_mint(&admin, 6)
+        };
+        let player = players[0];
+        let mut partner = Self {
+            harness,
+            client,
+            admin,
+            pause_authority,
+            player,
+            mint,
+            token_program,
+        };
+        let vault = pda::vault(&coin_pusher_game::ID).0;
+        partner.create_ata(&vault, VAULT_PARTNER);
+        partner.create_ata(&player, 0);
+        partner
+    }
+
+    fn create_ata(&mut self, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = self.ata(owner);
+        self.harness
+            .create_token_account_at(address, &self.mint, owner, amount);
+        address
+    }
+
+    fn ata(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+
+    fn register(&mut self, emission_budget: u64) {
+        let ix = self.client.register_reward_vault(
+            &self.admin,
+            PARTNER,
+            &self.mint,
+            &self.token_program,
+            emission_budget,
+        );
+        self.harness.process(&ix, &[self.admin]).unwrap();
+    }
+
+    fn award(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let ix = self.client.award_token(
+            &self.player,
+            &self.admin,
+            PARTNER,
+            &self.mint,
+            &self.token_program,
+            amount,
+        );
+        self.harness.process(&ix, &[self.player, self.admin])
+    }
+
+    fn reward_vault(&self) -> RewardVault {
+        self.harness
+            .get(&pda::reward_vault(&coin_pusher_game::ID, PARTNER).0)
+    }
+}
+
+#[test]
+fn awards_spl_token_rewards() {
+    let mut partner = Partner::new(spl_token::ID);
+    partner.register(100 * JUNK);
+
+    let reward_vault = partner.reward_vault();
+    assert_eq!(reward_vault.mint, partner.mint);
+    assert_eq!(reward_vault.token_program, spl_token::ID);
+    assert_eq!(
+        reward_vault.vault,
+        partner.ata(&pda::vault(&coin_pusher_game::ID).0)
+    );
+    assert!(reward_vault.enabled);
+
+    partner.award(7 * JUNK).unwrap();
+    assert_eq!(
+        partner.harness.token_balance(&partner.ata(&partner.player)),
+        7 * JUNK
+    );
+    assert_eq!(
+        partner.harness.token_balance(&reward_vault.vault),
+        VAULT_PARTNER - 7 * JUNK
+    );
+    assert_eq!(partner.reward_vault().total_emitted, 7 * JUNK);
+
+    let events = decode_events(&coin_pusher_game::ID, &partner.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::TokenAwarded(awarded)]
+            if awarded.reward_id == PARTNER && awarded.amount == 7 * JUNK
+    ));
+}
+
+#[test]
+fn awards_token_2022_rewards() {
+    let mut partner = Partner::new(spl_token_2022::ID);
+    partner.register(100 * JUNK);
+    assert_eq!(partner.reward_vault().token_program, spl_token_2022::ID);
+
+    partner.award(3 * JUNK).unwrap();
+    partner.award(4 * JUNK).unwrap();
+    assert_eq!(
+        partner.harness.token_balance(&partner.ata(&partner.player)),
+        7 * JUNK
+    );
+    assert_eq!(partner.reward_vault().total_emitted, 7 * JUNK);
+}
+
+#[test]
+fn emission_budget_limits_awards() {
+    let mut partner = Partner::new(spl_token::ID);
+    let admin = partner.admin;
+    partner.register(10 * JUNK);
+
+    partner.award(8 * JUNK).unwrap();
+    assert_eq!(
+        partner.award(3 * JUNK),
+        Err(game_error(GameError::RewardBudgetExhausted))
+    );
+
+    let ix = partner
+        .client
+        .set_reward_vault(&admin, PARTNER, 11 * JUNK, true);
+    partner.harness.process(&ix, &[admin]).unwrap();
+    partner.award(3 * JUNK).unwrap();
+    assert_eq!(partner.reward_vault().total_emitted, 11 * JUNK);
+}
+
+#[test]
+fn disabled_rewards_are_not_awarded() {
+    let mut partner = Partner::new(spl_token::ID);
+    let admin = partner.admin;
+    partner.register(100 * JUNK);
+
+    let ix = partner
+        .client
+        .set_reward_vault(&admin, PARTNER, 100 * JUNK, false);
+    partner.harness.process(&ix, &[admin]).unwrap();
+    assert_eq!(
+        partner.award(JUNK),
+        Err(game_error(GameError::RewardVaultDisabled))
+    );
+
+    // The instruction flag turns off every reward at once
+    let ix = partner
+        .client
+        .set_reward_vault(&admin, PARTNER, 100 * JUNK, true);
+    partner.harness.process(&ix, &[admin]).unwrap();
+    let pause_authority = partner.pause_authority;
+    let ix = partner
+        .client
+        .set_pause(&pause_authority, false, instruction_flags::AWARD_TOKEN);
+    partner.harness.process(&ix, &[pause_authority]).unwrap();
+    assert_eq!(
+        partner.award(JUNK),
+        Err(game_error(GameError::InstructionDisabled))
+    );
+}
+
+#[test]
+fn vault_must_be_the_vault_pdas_associated_account() {
+    let mut partner = Partner::new(spl_token::ID);
+    let admin = partner.admin;
+    let vault = pda::vault(&coin_pusher_game::ID).0;
+    let other_vault = partner
+        .harness
+        .create_token_account(&partner.mint, &vault, 0);
+
+    let mut ix = partner.client.register_reward_vault(
+        &admin,
+        PARTNER,
+        &partner.mint,
+        &spl_token::ID,
+        100 * JUNK,
+    );
+    let ata = partner.ata(&vault);
+    swap_account(&mut ix, ata, other_vault);
+    assert_eq!(
+        partner.harness.process(&ix, &[admin]),
+        Err(ProgramError::Custom(ErrorCode::ConstraintAssociated.into()))
+    );
+
+    // The token program has to own the mint
+    let ix = partner.client.register_reward_vault(
+        &admin,
+        PARTNER,
+        &partner.mint,
+        &spl_token_2022::ID,
+        100 * JUNK,
+    );
+    assert!(partner.harness.process(&ix, &[admin]).is_err());
+}
+
+#[test]
+fn trashcoin_cannot_be_registered() {
+    let mut partner = Partner::new(spl_token::ID);
+    let admin = partner.admin;
+    let trashcoin_mint = partner.client.deployment.trashcoin_mint;
+    let vault = pda::vault(&coin_pusher_game::ID).0;
+    partner.harness.create_token_account_at(
+        get_associated_token_address_with_program_id(&vault, &trashcoin_mint, &spl_token::ID),
+        &trashcoin_mint,
+        &vault,
+        VAULT_PARTNER,
+    );
+
+    let ix = partner.client.register_reward_vault(
+        &admin,
+        PARTNER,
+        &trashcoin_mint,
+        &spl_token::ID,
+        100 * JUNK,
+    );
+    assert_eq!(
+        partner.harness.process(&ix, &[admin]),
+        Err(game_error(GameError::InvalidRewardVault))
+    );
+}
+
+#[test]
+fn awards_must_match_the_registry() {
+    let mut partner = Partner::new(spl_token::ID);
+    partner.register(100 * JUNK);
+    let (player, admin) = (partner.player, partner.admin);
+
+    // Pay TRASHCOIN out of its vault under the partner reward's id
+    let deployment = partner.client.deployment.clone();
+    let ix = partner.client.award_token(
+        &player,
+        &admin,
+        PARTNER,
+        &partner.mint,
+        &spl_token::ID,
+        JUNK,
+    );
+    let vault = partner.ata(&pda::vault(&coin_pusher_game::ID).0);
+    let mut trashcoin = ix.clone();
+    swap_account(&mut trashcoin, partner.mint, deployment.trashcoin_mint);
+    swap_account(&mut trashcoin, vault, deployment.vault_trashcoin_account);
+    swap_account(
+        &mut trashcoin,
+        partner.ata(&player),
+        get_associated_token_address_with_program_id(
+            &player,
+            &deployment.trashcoin_mint,
+            &spl_token::ID,
+        ),
+    );
+    assert_eq!(
+        partner.harness.process(&trashcoin, &[player, admin]),
+        Err(game_error(GameError::InvalidRewardVault))
+    );
+
+    // Right mint, wrong vault
+    let mut other_vault = ix;
+    let unregistered = partner.harness.create_token_account(
+        &partner.mint,
+        &pda::vault(&coin_pusher_game::ID).0,
+        VAULT_PARTNER,
+    );
+    swap_account(&mut other_vault, vault, unregistered);
+    assert_eq!(
+        partner.harness.process(&other_vault, &[player, admin]),
+        Err(game_error(GameError::InvalidRewardVault))
+    );
+    assert_eq!(partner.reward_vault().total_emitted, 0);
+}
+
+#[test]
+fn only_the_game_authority_awards_and_the_admin_registers() {
+    let mut partner = Partner::new(spl_token::ID);
+    let player = partner.player;
+    let intruder = partner.harness.new_funded_account();
+
+    let ix = partner.client.register_reward_vault(
+        &intruder,
+        PARTNER,
+        &partner.mint,
+        &spl_token::ID,
+        100 * JUNK,
+    );
+    assert_eq!(
+        partner.harness.process(&ix, &[intruder]),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    partner.register(100 * JUNK);
+    let ix = partner.client.award_token(
+        &player,
+        &intruder,
+        PARTNER,
+        &partner.mint,
+        &spl_token::ID,
+        JUNK,
+    );
+    assert_eq!(
+        partner.harness.process(&ix, &[player, intruder]),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    let ix = partner
+        .client
+        .set_reward_vault(&intruder, PARTNER, 0, false);
+    assert_eq!(
+        partner.harness.process(&ix, &[intruder]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
+
+#[test]
+fn frozen_players_are_not_awarded() {
+    let mut partner = Partner::new(spl_token::ID);
+    let (player, admin) = (partner.player, partner.admin);
+    partner.register(100 * JUNK);
+
+    let ix = partner
+        .client
+        .set_player_status(&admin, &player, PlayerStatus::Frozen, 0, 0);
+    partner.harness.process(&ix, &[admin]).unwrap();
+    assert_eq!(
+        partner.award(JUNK),
+        Err(game_error(GameError::PlayerFrozen))
+    );
+    assert_eq!(partner.reward_vault().total_emitted, 0);
+}
@@ -0,0 +1,253 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    GameError, GameState, Schedule, ScheduleWindow, COIN_TYPE_NORMAL, MAX_SCHEDULE_WINDOWS,
+    SECONDS_PER_DAY,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+/// Midnight UTC, 2024-01-01
+const MIDNIGHT: i64 = 1_704_067_200;
+const HOUR: i64 = 3_600;
+
+struct Promotions {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    player: Pubkey,
+}
+
+impl Promotions {
+    fn new() -> Self {
+        let setup = Setup::builder().players(1).player_junk(10 * JUNK).build();
+        let mut promotions = Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+            player: setup.players[0],
+        };
+        promotions.set_time(MIDNIGHT + 12 * HOUR);
+        promotions
+    }
+
+    fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock = self.harness.clock();
+        clock.unix_timestamp = unix_timestamp;
+        self.harness.set_clock(clock);
+    }
+
+    fn set_schedule(&mut self, windows: &[ScheduleWindow]) -> Result<(), ProgramError> {
+        let mut table = [ScheduleWindow::default(); MAX_SCHEDULE_WINDOWS];
+        table[..windows.len()].copy_from_slice(windows);
+        let ix = self.client.set_schedule(&self.admin, table);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    /// Collect a normal coin and return the reward and multiplier it was credited with
+    fn collect(&mut self) -> (u64, u16) {
+        let ix = self.client.collect_coin(
+            &self.player,
+            &self.admin,
+            COIN_TYPE_NORMAL,
+            CollectOptions::default(),
+        );
+        self.harness
+            .process(&ix, &[self.player, self.admin])
+            .unwrap();
+        match decode_events(&coin_pusher_game::ID, &self.harness.logs()).as_slice() {
+            [GameEvent::CoinCollected(collected)] => (collected.amount, collected.multiplier_bps),
+            _ => panic!("expected a single CoinCollected event"),
+        }
+    }
+
+    fn junk_balance(&self) -> u64 {
+        self.harness.token_balance(&get_associated_token_address(
+            &self.player,
+            &self.client.deployment.junk_mint,
+        ))
+    }
+
+    fn state(&self) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, &self.player).0)
+    }
+}
+
+fn fixed(start: i64, end: i64, reward_multiplier_bps: u16) -> ScheduleWindow {
+    ScheduleWindow {
+        start,
+        end,
+        daily: false,
+        reward_multiplier_bps,
+        drop_discount_bps: 0,
+    }
+}
+
+fn daily(start: i64, end: i64, reward_multiplier_bps: u16) -> ScheduleWindow {
+    ScheduleWindow {
+        daily: true,
+        ..fixed(start, end, reward_multiplier_bps)
+    }
+}
+
+#[test]
+fn schedule_starts_empty() {
+    let mut promotions = Promotions::new();
+    let schedule: Schedule = promotions
+        .harness
+        .get(&pda::schedule(&coin_pusher_game::ID).0);
+    assert_eq!(
+        schedule.windows,
+        [ScheduleWindow::default(); MAX_SCHEDULE_WINDOWS]
+    );
+
+    assert_eq!(promotions.collect(), (1, 10_000));
+}
+
+#[test]
+fn fixed_window_multiplies_rewards_only_while_open() {
+    let mut promotions = Promotions::new();
+    let now = MIDNIGHT + 12 * HOUR;
+    promotions
+        .set_schedule(&[fixed(now + HOUR, now + 2 * HOUR, 30_000)])
+        .unwrap();
+
+    assert_eq!(promotions.collect(), (1, 10_000));
+    promotions.set_time(now + HOUR);
+    assert_eq!(promotions.collect(), (3, 30_000));
+    promotions.set_time(now + 2 * HOUR);
+    assert_eq!(promotions.collect(), (1, 10_000));
+
+    assert_eq!(promotions.state().balance, 105);
+}
+
+#[test]
+fn daily_window_recurs_and_wraps_midnight() {
+    let mut promotions = Promotions::new();
+    // 22:00 to 02:00 UTC every day
+    promotions
+        .set_schedule(&[daily(22 * HOUR, 2 * HOUR, 20_000)])
+        .unwrap();
+
+    assert_eq!(promotions.collect().1, 10_000);
+    for time in [
+        MIDNIGHT + 23 * HOUR,
+        MIDNIGHT + SECONDS_PER_DAY + HOUR,
+        MIDNIGHT + 10 * SECONDS_PER_DAY + 22 * HOUR,
+    ] {
+        promotions.set_time(time);
+        assert_eq!(promotions.collect(), (2, 20_000));
+    }
+    promotions.set_time(MIDNIGHT + SECONDS_PER_DAY + 2 * HOUR);
+    assert_eq!(promotions.collect().1, 10_000);
+}
+
+#[test]
+fn earlier_window_wins_when_windows_overlap() {
+    let mut promotions = Promotions::new();
+    promotions
+        .set_schedule(&[
+            ScheduleWindow::default(),
+            daily(11 * HOUR, 13 * HOUR, 20_000),
+            daily(0, SECONDS_PER_DAY, 50_000),
+        ])
+        .unwrap();
+
+    assert_eq!(promotions.collect(), (2, 20_000));
+    promotions.set_time(MIDNIGHT + 14 * HOUR);
+    assert_eq!(promotions.collect(), (5, 50_000));
+}
+
+#[test]
+fn drop_discount_reduces_junk_cost() {
+    let mut promotions = Promotions::new();
+    promotions
+        .set_schedule(&[ScheduleWindow {
+            drop_discount_bps: 5_000,
+            ..daily(0, SECONDS_PER_DAY, 10_000)
+        }])
+        .unwrap();
+
+    let ix = promotions.client.drop_coin(&promotions.player);
+    promotions
+        .harness
+        .process(&ix, &[promotions.player])
+        .unwrap();
+
+    assert_eq!(promotions.junk_balance(), 10 * JUNK - JUNK / 2);
+    let events = decode_events(&coin_pusher_game::ID, &promotions.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::CoinDropped(dropped)] if dropped.discount_bps == 5_000 && dropped.cost == 1
+    ));
+}
+
+#[test]
+fn drop_without_promotion_pays_full_cost() {
+    let mut promotions = Promotions::new();
+
+    let ix = promotions.client.drop_coin(&promotions.player);
+    promotions
+        .harness
+        .process(&ix, &[promotions.player])
+        .unwrap();
+
+    assert_eq!(promotions.junk_balance(), 9 * JUNK);
+    let events = decode_events(&coin_pusher_game::ID, &promotions.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::CoinDropped(dropped)] if dropped.discount_bps == 0
+    ));
+}
+
+#[test]
+fn set_schedule_validates_windows() {
+    let mut promotions = Promotions::new();
+    let invalid = [
+        // Promotions can't reduce rewards
+        fixed(0, 10, 9_999),
+        // Empty range
+        fixed(10, 10, 10_000),
+        // Daily times must fall within a day
+        daily(SECONDS_PER_DAY, 10, 10_000),
+        daily(0, SECONDS_PER_DAY + 1, 10_000),
+        // Discounts above 100%
+        ScheduleWindow {
+            drop_discount_bps: 10_001,
+            ..fixed(0, 10, 10_000)
+        },
+        // Unused slots can't carry a discount
+        ScheduleWindow {
+            drop_discount_bps: 1,
+            ..ScheduleWindow::default()
+        },
+    ];
+    for window in invalid {
+        assert_eq!(
+            promotions.set_schedule(&[window]),
+            Err(game_error(GameError::InvalidSchedule)),
+            "{window:?}"
+        );
+    }
+}
+
+#[test]
+fn set_schedule_requires_admin() {
+    let mut promotions = Promotions::new();
+
+    let ix = promotions.client.set_schedule(
+        &promotions.player,
+        [ScheduleWindow::default(); MAX_SCHEDULE_WINDOWS],
+    );
+    assert_eq!(
+        promotions.harness.process(&ix, &[promotions.player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
@@ -0,0 +1,266 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use coin_pusher_client::snapshot::{verify_snapshot, CoinPosition, SnapshotStatus};
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    GameError, GameState, MachineSnapshot, SnapshotCoin, COIN_TYPE_NORMAL, COIN_TYPE_TRASHCOIN,
+    MAX_SNAPSHOT_COINS,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+struct Snapshots {
+    harness: Harness,
+    client: CoinPusherClient,
+    pause_authority: Pubkey,
+    player: Pubkey,
+}
+
+impl Snapshots {
+    fn new() -> Self {
+        let setup = Setup::builder().players(1).player_junk(10 * JUNK).build();
+        Self {
+            harness: setup.harness,
+            client: setup.client,
+            pause_authority: setup.pause_authority,
+            player: setup.players[0],
+        }
+    }
+
+    fn run(&mut self, ix: Instruction) -> Result<(), ProgramError> {
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn set_pause(&mut self, paused: bool) {
+        let ix = self.client.set_pause(&self.pause_authority, paused, 0);
+        self.harness.process(&ix, &[self.pause_authority]).unwrap();
+    }
+
+    fn save(&mut self, session_id: u64, coins: &[CoinPosition]) {
+        let instructions =
+            self.client
+                .save_snapshot_instructions(&self.player, session_id, 1_234, coins);
+        for ix in instructions {
+            self.run(ix).unwrap();
+        }
+    }
+
+    fn snapshot(&self) -> MachineSnapshot {
+        self.harness
+            .get(&pda::machine_snapshot(&coin_pusher_game::ID, &self.player).0)
+    }
+
+    fn state(&self) -> GameState {
+        self.harness
+            .get(&pda::game_state(&coin_pusher_game::ID, &self.player).0)
+    }
+}
+
+/// A pile of `count` coins spread over the playfield
+fn pile(count: usize) -> Vec<CoinPosition> {
+    (0..count)
+        .map(|i| CoinPosition {
+            x: (i % 16) as f32 * 0.5 - 4.0,
+            y: 0.05 + (i / 160) as f32 * 0.1,
+            z: ((i / 16) % 10) as f32 - 5.0,
+            coin_type: if i % 17 == 0 {
+                COIN_TYPE_TRASHCOIN
+            } else {
+                COIN_TYPE_NORMAL
+            },
+        })
+        .collect()
+}
+
+#[test]
+fn save_and_load_full_playfield() {
+    let mut snapshots = Snapshots::new();
+    let coins = pile(MAX_SNAPSHOT_COINS);
+
+    snapshots.save(7, &coins);
+
+    let snapshot = snapshots.snapshot();
+    assert!(snapshot.committed);
+    assert_eq!(snapshot.session_id, 7);
+    assert_eq!(snapshot.sequence, 1);
+    assert_eq!(snapshot.pusher_phase, 1_234);
+    assert_eq!(snapshot.coins.len(), MAX_SNAPSHOT_COINS);
+    assert_eq!(
+        verify_snapshot(&snapshot, &snapshots.state()),
+        SnapshotStatus::Valid
+    );
+
+    for (saved, original) in snapshot.coins.iter().zip(&coins) {
+        let loaded = CoinPosition::decode(saved);
+        assert!((loaded.x - original.x).abs() < 0.001);
+        assert!((loaded.y - original.y).abs() < 0.001);
+        assert!((loaded.z - original.z).abs() < 0.001);
+        assert_eq!(loaded.coin_type, original.coin_type);
+    }
+
+    let events = decode_events(&coin_pusher_game::ID, &snapshots.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::SnapshotSaved(saved)]
+            if saved.hash == snapshot.hash && saved.coin_count == MAX_SNAPSHOT_COINS as u16
+    ));
+}
+
+#[test]
+fn snapshot_hashes_chain() {
+    let mut snapshots = Snapshots::new();
+
+    snapshots.save(7, &pile(10));
+    let first = snapshots.snapshot();
+    snapshots.save(7, &pile(20));
+    let second = snapshots.snapshot();
+
+    assert_eq!(first.previous_hash, [0; 32]);
+    assert_eq!(second.sequence, 2);
+    assert_eq!(second.previous_hash, first.hash);
+    assert_ne!(second.hash, first.hash);
+}
+
+#[test]
+fn snapshot_is_stale_after_gameplay() {
+    let mut snapshots = Snapshots::new();
+    snapshots.save(7, &pile(10));
+
+    let ix = snapshots.client.drop_coin(&snapshots.player);
+    snapshots.harness.warp_seconds(1);
+    snapshots.run(ix).unwrap();
+
+    assert_eq!(
+        verify_snapshot(&snapshots.snapshot(), &snapshots.state()),
+        SnapshotStatus::Stale
+    );
+}
+
+#[test]
+fn tampered_snapshot_is_detected() {
+    let mut snapshots = Snapshots::new();
+    snapshots.save(7, &pile(10));
+
+    let mut snapshot = snapshots.snapshot();
+    snapshot.coins[3].x += 1;
+    assert_eq!(
+        verify_snapshot(&snapshot, &snapshots.state()),
+        SnapshotStatus::Corrupt
+    );
+}
+
+#[test]
+fn snapshot_must_be_written_in_order_and_completely() {
+    let mut snapshots = Snapshots::new();
+    let coins = vec![SnapshotCoin::default(); 4];
+
+    let ix = snapshots.client.begin_snapshot(&snapshots.player, 7, 0, 6);
+    snapshots.run(ix).unwrap();
+
+    let ix = snapshots
+        .client
+        .write_snapshot(&snapshots.player, 2, coins.clone());
+    assert_eq!(
+        snapshots.run(ix),
+        Err(game_error(GameError::SnapshotOutOfOrder))
+    );
+
+    let ix = snapshots
+        .client
+        .write_snapshot(&snapshots.player, 0, coins.clone());
+    snapshots.run(ix).unwrap();
+    let ix = snapshots.client.save_snapshot(&snapshots.player);
+    assert_eq!(
+        snapshots.run(ix),
+        Err(game_error(GameError::SnapshotIncomplete))
+    );
+    assert_eq!(
+        verify_snapshot(&snapshots.snapshot(), &snapshots.state()),
+        SnapshotStatus::Incomplete
+    );
+
+    let ix = snapshots.client.write_snapshot(&snapshots.player, 4, coins);
+    assert_eq!(
+        snapshots.run(ix),
+        Err(game_error(GameError::SnapshotTooLarge))
+    );
+}
+
+#[test]
+fn snapshot_is_capped_at_max_coins() {
+    let mut snapshots = Snapshots::new();
+
+    let ix =
+        snapshots
+            .client
+            .begin_snapshot(&snapshots.player, 7, 0, MAX_SNAPSHOT_COINS as u16 + 1);
+    assert_eq!(
+        snapshots.run(ix),
+        Err(game_error(GameError::SnapshotTooLarge))
+    );
+}
+
+#[test]
+fn committed_snapshot_is_read_only() {
+    let mut snapshots = Snapshots::new();
+    snapshots.save(7, &pile(10));
+
+    let ix = snapshots
+        .client
+        .write_snapshot(&snapshots.player, 10, vec![SnapshotCoin::default()]);
+    assert_eq!(
+        snapshots.run(ix),
+        Err(game_error(GameError::SnapshotNotWritable))
+    );
+}
+
+#[test]
+fn close_snapshot_refunds_rent() {
+    let mut snapshots = Snapshots::new();
+    snapshots.save(7, &pile(10));
+    let address = pda::machine_snapshot(&coin_pusher_game::ID, &snapshots.player).0;
+    let rent = snapshots.harness.lamports(&address);
+    let player_before = snapshots.harness.lamports(&snapshots.player);
+
+    let ix = snapshots.client.close_snapshot(&snapshots.player);
+    snapshots.run(ix).unwrap();
+
+    assert!(snapshots.harness.account(&address).is_none());
+    assert_eq!(
+        snapshots.harness.lamports(&snapshots.player),
+        player_before + rent
+    );
+}
+
+#[test]
+fn pause_stops_a_snapshot_in_progress() {
+    let mut snapshots = Snapshots::new();
+    let ix = snapshots.client.begin_snapshot(&snapshots.player, 7, 0, 1);
+    snapshots.run(ix).unwrap();
+
+    snapshots.set_pause(true);
+    let write =
+        snapshots
+            .client
+            .write_snapshot(&snapshots.player, 0, vec![SnapshotCoin::default()]);
+    assert_eq!(
+        snapshots.run(write.clone()),
+        Err(game_error(GameError::ProgramPaused))
+    );
+
+    snapshots.set_pause(false);
+    snapshots.run(write).unwrap();
+    snapshots.set_pause(true);
+    let save = snapshots.client.save_snapshot(&snapshots.player);
+    assert_eq!(
+        snapshots.run(save.clone()),
+        Err(game_error(GameError::ProgramPaused))
+    );
+    snapshots.set_pause(false);
+    snapshots.run(save).unwrap();
+    assert!(snapshots.snapshot().committed);
+}
@@ -0,0 +1,292 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{instruction_flags, GameError, GameState, SponsorBudget, COIN_TYPE_NORMAL};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup};
+
+const MAX_LAMPORTS: u64 = 1_000_000_000;
+
+struct Onboarding {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    pause_authority: Pubkey,
+    sponsor: Pubkey,
+}
+
+impl Onboarding {
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            pause_authority,
+            ..
+        } = Setup::builder().build();
+        let sponsor = harness.new_funded_account();
+        let mut onboarding = Self {
+            harness,
+            client,
+            admin,
+            pause_authority,
+            sponsor,
+        };
+        onboarding.set_budget(2, MAX_LAMPORTS);
+        onboarding
+    }
+
+    fn set_budget(&mut self, max_accounts: u32, max_lamports: u64) {
+        let ix = self
+            .client
+            .set_sponsor_budget(&self.sponsor, max_accounts, max_lamports);
+        self.harness.process(&ix, &[self.sponsor]).unwrap();
+    }
+
+    fn set_pause(&mut self, paused: bool, disabled_instructions: u64) {
+        let ix = self
+            .client
+            .set_pause(&self.pause_authority, paused, disabled_instructions);
+        self.harness.process(&ix, &[self.pause_authority]).unwrap();
+    }
+
+    /// A brand new wallet holding no lamports at all
+    fn new_wallet(&self) -> Pubkey {
+        Pubkey::new_unique()
+    }
+
+    fn start(&mut self, player: Pubkey) -> Result<(), ProgramError> {
+        let ix = self
+            .client
+            .initialize_sponsored_game(&player, &self.sponsor, 100);
+        self.harness.process(&ix, &[self.sponsor, player])
+    }
+
+    fn close(&mut self, player: Pubkey, rent_recipient: Pubkey) -> Result<(), ProgramError> {
+        let ix = self.client.close_game(&player, &rent_recipient);
+        self.harness.process(&ix, &[player])
+    }
+
+    fn game_address(&self, player: &Pubkey) -> Pubkey {
+        pda::game_state(&coin_pusher_game::ID, player).0
+    }
+
+    fn game_state(&self, player: &Pubkey) -> GameState {
+        self.harness.get(&self.game_address(player))
+    }
+
+    fn budget(&self) -> SponsorBudget {
+        self.harness
+            .get(&pda::sponsor_budget(&coin_pusher_game::ID, &self.sponsor).0)
+    }
+}
+
+#[test]
+fn sponsor_pays_rent_for_an_empty_wallet() {
+    let mut onboarding = Onboarding::new();
+    let player = onboarding.new_wallet();
+    let sponsor = onboarding.sponsor;
+    let before = onboarding.harness.lamports(&sponsor);
+
+    onboarding.start(player).unwrap();
+
+    let game_state = onboarding.game_state(&player);
+    assert_eq!(game_state.player, player);
+    assert_eq!(game_state.sponsor, sponsor);
+    assert_eq!(game_state.balance, 100);
+    assert_eq!(game_state.rent_recipient(), sponsor);
+
+    let rent = onboarding
+        .harness
+        .lamports(&onboarding.game_address(&player));
+    assert_eq!(onboarding.harness.lamports(&sponsor), before - rent);
+    assert_eq!(onboarding.harness.lamports(&player), 0);
+
+    let budget = onboarding.budget();
+    assert_eq!(budget.accounts_sponsored, 1);
+    assert_eq!(budget.lamports_sponsored, rent);
+
+    let events = decode_events(&coin_pusher_game::ID, &onboarding.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::GameInitialized(_), GameEvent::GameSponsored(sponsored)]
+            if sponsored.player == player && sponsored.sponsor == sponsor && sponsored.rent == rent
+    ));
+}
+
+#[test]
+fn budget_caps_accounts_and_rent() {
+    let mut onboarding = Onboarding::new();
+    onboarding.set_budget(1, MAX_LAMPORTS);
+    let first = onboarding.new_wallet();
+    onboarding.start(first).unwrap();
+    let rent = onboarding.budget().lamports_sponsored;
+
+    let second = onboarding.new_wallet();
+    assert_eq!(
+        onboarding.start(second),
+        Err(game_error(GameError::SponsorBudgetExhausted))
+    );
+
+    // Room for another account, but not for its rent
+    onboarding.set_budget(2, 2 * rent - 1);
+    assert_eq!(
+        onboarding.start(second),
+        Err(game_error(GameError::SponsorBudgetExhausted))
+    );
+
+    onboarding.set_budget(2, 2 * rent);
+    onboarding.start(second).unwrap();
+    let budget = onboarding.budget();
+    assert_eq!(budget.accounts_sponsored, 2);
+    assert_eq!(budget.lamports_sponsored, 2 * rent);
+}
+
+#[test]
+fn sponsoring_requires_a_budget() {
+    let mut onboarding = Onboarding::new();
+    onboarding.sponsor = onboarding.harness.new_funded_account();
+    let player = onboarding.new_wallet();
+
+    assert_eq!(
+        onboarding.start(player),
+        Err(ProgramError::Custom(
+            ErrorCode::AccountNotInitialized.into()
+        ))
+    );
+}
+
+#[test]
+fn sponsored_game_refunds_the_sponsor_on_close() {
+    let mut onboarding = Onboarding::new();
+    let player = onboarding.new_wallet();
+    let sponsor = onboarding.sponsor;
+    onboarding.start(player).unwrap();
+    let rent = onboarding
+        .harness
+        .lamports(&onboarding.game_address(&player));
+
+    assert_eq!(
+        onboarding.close(player, player),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    let before = onboarding.harness.lamports(&sponsor);
+    onboarding.close(player, sponsor).unwrap();
+    assert_eq!(onboarding.harness.lamports(&sponsor), before + rent);
+    assert_eq!(onboarding.harness.lamports(&player), 0);
+    assert!(onboarding
+        .harness
+        .account(&onboarding.game_address(&player))
+        .is_none());
+
+    let events = decode_events(&coin_pusher_game::ID, &onboarding.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::GameClosed(closed)]
+            if closed.player == player && closed.rent_recipient == sponsor && closed.rent == rent
+    ));
+}
+
+#[test]
+fn unsponsored_game_refunds_the_player_on_close() {
+    let mut onboarding = Onboarding::new();
+    let player = onboarding.harness.new_funded_account();
+    let ix = onboarding.client.initialize_game(&player, 100);
+    onboarding.harness.process(&ix, &[player]).unwrap();
+    assert_eq!(onboarding.game_state(&player).sponsor, Pubkey::default());
+    let rent = onboarding
+        .harness
+        .lamports(&onboarding.game_address(&player));
+
+    let sponsor = onboarding.sponsor;
+    assert_eq!(
+        onboarding.close(player, sponsor),
+        Err(game_error(GameError::Unauthorized))
+    );
+
+    let before = onboarding.harness.lamports(&player);
+    onboarding.close(player, player).unwrap();
+    assert_eq!(onboarding.harness.lamports(&player), before + rent);
+
+    // Closing frees the address for a fresh game
+    onboarding.harness.process(&ix, &[player]).unwrap();
+}
+
+#[test]
+fn close_game_requires_no_withdrawable_credits() {
+    let mut onboarding = Onboarding::new();
+    let (admin, sponsor) = (onboarding.admin, onboarding.sponsor);
+    let player = onboarding.new_wallet();
+    onboarding.start(player).unwrap();
+
+    let ix = onboarding.client.collect_coin(
+        &player,
+        &admin,
+        COIN_TYPE_NORMAL,
+        CollectOptions::default(),
+    );
+    onboarding.harness.process(&ix, &[player, admin]).unwrap();
+    assert_eq!(onboarding.game_state(&player).withdrawable_balance(), 1);
+
+    assert_eq!(
+        onboarding.close(player, sponsor),
+        Err(game_error(GameError::GameNotEmpty))
+    );
+}
+
+#[test]
+fn budgets_belong_to_their_sponsor() {
+    let mut onboarding = Onboarding::new();
+    let other_sponsor = onboarding.harness.new_funded_account();
+    let player = onboarding.new_wallet();
+
+    // Spend someone else's budget
+    let mut ix = onboarding
+        .client
+        .initialize_sponsored_game(&player, &other_sponsor, 100);
+    let theirs = pda::sponsor_budget(&coin_pusher_game::ID, &other_sponsor).0;
+    for meta in &mut ix.accounts {
+        if meta.pubkey == theirs {
+            meta.pubkey = pda::sponsor_budget(&coin_pusher_game::ID, &onboarding.sponsor).0;
+        }
+    }
+    assert_eq!(
+        onboarding.harness.process(&ix, &[other_sponsor, player]),
+        Err(ProgramError::Custom(ErrorCode::ConstraintSeeds.into()))
+    );
+    assert_eq!(onboarding.budget().accounts_sponsored, 0);
+}
+
+#[test]
+fn games_can_be_closed_while_paused() {
+    let mut onboarding = Onboarding::new();
+    let sponsor = onboarding.sponsor;
+    let (first, second) = (onboarding.new_wallet(), onboarding.new_wallet());
+    onboarding.start(first).unwrap();
+
+    onboarding.set_pause(true, 0);
+    assert_eq!(
+        onboarding.start(second),
+        Err(game_error(GameError::ProgramPaused))
+    );
+    let ix = onboarding
+        .client
+        .set_sponsor_budget(&sponsor, 5, MAX_LAMPORTS);
+    assert_eq!(
+        onboarding.harness.process(&ix, &[sponsor]),
+        Err(game_error(GameError::ProgramPaused))
+    );
+    onboarding.close(first, sponsor).unwrap();
+
+    onboarding.set_pause(false, instruction_flags::SPONSOR_BUDGET);
+    assert_eq!(
+        onboarding.harness.process(&ix, &[sponsor]),
+        Err(game_error(GameError::InstructionDisabled))
+    );
+}
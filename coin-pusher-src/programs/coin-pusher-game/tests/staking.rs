@@ -0,0 +1,310 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::{BumpOptions, CollectOptions};
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    CoinType, GameError, GlobalStats, StakePosition, StakeTier, COIN_TYPE_NORMAL, MAX_STAKE_TIERS,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, Setup, JUNK};
+
+const DAY: i64 = 86_400;
+const PLAYER_JUNK: u64 = 1_000 * JUNK;
+/// Credits a normal coin pays before any boost
+const REWARD: u64 = 100;
+
+const SHORT: u8 = 0;
+const LONG: u8 = 1;
+const UNUSED: u8 = 2;
+
+fn tiers() -> [StakeTier; MAX_STAKE_TIERS] {
+    let mut tiers = [StakeTier::default(); MAX_STAKE_TIERS];
+    tiers[SHORT as usize] = StakeTier {
+        lock_duration: DAY,
+        min_amount: 10 * JUNK,
+        reward_multiplier_bps: 12_500,
+        bump_discount_bps: 2_000,
+    };
+    tiers[LONG as usize] = StakeTier {
+        lock_duration: 7 * DAY,
+        min_amount: 50 * JUNK,
+        reward_multiplier_bps: 15_000,
+        bump_discount_bps: 3_333,
+    };
+    tiers
+}
+
+struct Staking {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+    player: Pubkey,
+}
+
+impl Staking {
+    /// A player with a game and `PLAYER_JUNK`, and the tier table set
+    fn new() -> Self {
+        let Setup {
+            mut harness,
+            client,
+            admin,
+            players,
+            ..
+        } = Setup::builder()
+            .players(1)
+            .player_junk(PLAYER_JUNK)
+            .initial_balance(1_000)
+            .build();
+        let ix = client.set_stake_tiers(&admin, tiers());
+        harness.process(&ix, &[admin]).unwrap();
+        let mut coin_types = CoinType::default_table();
+        coin_types[COIN_TYPE_NORMAL as usize].reward = REWARD;
+        let ix = client.set_coin_types(&admin, coin_types);
+        harness.process(&ix, &[admin]).unwrap();
+        Self {
+            harness,
+            client,
+            admin,
+            player: players[0],
+        }
+    }
+
+    fn now(&self) -> i64 {
+        self.harness.clock().unix_timestamp
+    }
+
+    fn stake(&mut self, amount: u64, tier: u8) -> Result<(), ProgramError> {
+        let ix = self.client.stake(&self.player, amount, tier);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn unstake(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let ix = self.client.unstake(&self.player, amount);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn extend_lock(&mut self, tier: u8) -> Result<(), ProgramError> {
+        let ix = self.client.extend_lock(&self.player, tier);
+        self.harness.process(&ix, &[self.player])
+    }
+
+    /// Credits paid for a normal coin collected with the stake attached
+    fn collect(&mut self) -> u64 {
+        let ix = self.client.collect_coin(
+            &self.player,
+            &self.admin,
+            COIN_TYPE_NORMAL,
+            CollectOptions {
+                stake_position: true,
+                ..CollectOptions::default()
+            },
+        );
+        self.harness
+            .process(&ix, &[self.player, self.admin])
+            .unwrap();
+        let events = decode_events(&coin_pusher_game::ID, &self.harness.logs());
+        match events.as_slice() {
+            [GameEvent::CoinCollected(collected)] => collected.amount,
+            _ => panic!("expected a CoinCollected event"),
+        }
+    }
+
+    /// Credits and JUNK paid for a bump with the stake attached, a second
+    /// after the last play
+    fn bump(&mut self) -> (u64, u64) {
+        self.harness.warp_seconds(1);
+        let junk = self.junk_balance();
+        let ix = self.client.bump_machine_with(
+            &self.player,
+            BumpOptions {
+                stake_position: true,
+                ..BumpOptions::default()
+            },
+        );
+        self.harness.process(&ix, &[self.player]).unwrap();
+        let events = decode_events(&coin_pusher_game::ID, &self.harness.logs());
+        match events.as_slice() {
+            [GameEvent::MachineBumped(bumped)] => (bumped.cost, junk - self.junk_balance()),
+            _ => panic!("expected a MachineBumped event"),
+        }
+    }
+
+    fn position(&self) -> StakePosition {
+        self.harness
+            .get(&pda::stake_position(&coin_pusher_game::ID, &self.player).0)
+    }
+
+    fn total_staked(&self) -> u64 {
+        let stats: GlobalStats = self
+            .harness
+            .get(&pda::global_stats(&coin_pusher_game::ID).0);
+        stats.total_staked
+    }
+
+    fn vault_balance(&self) -> u64 {
+        self.harness
+            .token_balance(&pda::stake_vault(&coin_pusher_game::ID, &self.player).0)
+    }
+
+    fn junk_balance(&self) -> u64 {
+        self.harness.token_balance(&get_associated_token_address(
+            &self.player,
+            &self.client.deployment.junk_mint,
+        ))
+    }
+}
+
+#[test]
+fn stakes_are_locked_until_the_tier_ends() {
+    let mut staking = Staking::new();
+    let staked_at = staking.now();
+
+    staking.stake(20 * JUNK, SHORT).unwrap();
+    let position = staking.position();
+    assert_eq!(position.amount, 20 * JUNK);
+    assert_eq!(position.tier, SHORT);
+    assert_eq!(position.lock_end, staked_at + DAY);
+    assert_eq!(staking.vault_balance(), 20 * JUNK);
+    assert_eq!(staking.total_staked(), 20 * JUNK);
+
+    staking.harness.warp_seconds(DAY - 1);
+    assert_eq!(
+        staking.unstake(JUNK),
+        Err(game_error(GameError::StakeLocked))
+    );
+
+    staking.harness.warp_seconds(1);
+    assert_eq!(
+        staking.unstake(21 * JUNK),
+        Err(game_error(GameError::InsufficientBalance))
+    );
+    staking.unstake(5 * JUNK).unwrap();
+    assert_eq!(staking.position().amount, 15 * JUNK);
+    staking.unstake(15 * JUNK).unwrap();
+    assert_eq!(staking.position().amount, 0);
+    assert_eq!(staking.vault_balance(), 0);
+    assert_eq!(staking.total_staked(), 0);
+    assert_eq!(staking.junk_balance(), PLAYER_JUNK);
+}
+
+#[test]
+fn stakes_must_meet_the_tier_minimum() {
+    let mut staking = Staking::new();
+
+    assert_eq!(
+        staking.stake(10 * JUNK - 1, SHORT),
+        Err(game_error(GameError::StakeBelowTierMinimum))
+    );
+    assert_eq!(
+        staking.stake(10 * JUNK, UNUSED),
+        Err(game_error(GameError::InvalidStakeTier))
+    );
+    staking.stake(10 * JUNK, SHORT).unwrap();
+
+    // Top-ups count towards a higher tier's minimum
+    assert_eq!(
+        staking.stake(39 * JUNK, LONG),
+        Err(game_error(GameError::StakeBelowTierMinimum))
+    );
+    assert_eq!(
+        staking.extend_lock(LONG),
+        Err(game_error(GameError::StakeBelowTierMinimum))
+    );
+    staking.stake(40 * JUNK, LONG).unwrap();
+    assert_eq!(staking.position().amount, 50 * JUNK);
+    assert_eq!(staking.position().tier, LONG);
+}
+
+#[test]
+fn extending_a_lock_never_shortens_it() {
+    let mut staking = Staking::new();
+    let staked_at = staking.now();
+    staking.stake(60 * JUNK, SHORT).unwrap();
+
+    staking.harness.warp_seconds(DAY / 2);
+    staking.extend_lock(LONG).unwrap();
+    assert_eq!(staking.position().lock_end, staked_at + DAY / 2 + 7 * DAY);
+
+    // Back to the short tier: its boosts apply, the long lock stays
+    staking.extend_lock(SHORT).unwrap();
+    let position = staking.position();
+    assert_eq!(position.tier, SHORT);
+    assert_eq!(position.lock_end, staked_at + DAY / 2 + 7 * DAY);
+
+    staking.harness.warp_seconds(DAY);
+    assert_eq!(
+        staking.unstake(JUNK),
+        Err(game_error(GameError::StakeLocked))
+    );
+}
+
+#[test]
+fn locked_stakes_boost_collections_until_the_lock_ends() {
+    let mut staking = Staking::new();
+    staking.stake(10 * JUNK, SHORT).unwrap();
+    assert_eq!(staking.collect(), 125);
+
+    staking.stake(40 * JUNK, LONG).unwrap();
+    assert_eq!(staking.collect(), 150);
+
+    staking.harness.warp_seconds(7 * DAY - 1);
+    assert_eq!(staking.collect(), 150);
+    staking.harness.warp_seconds(1);
+    assert_eq!(staking.collect(), REWARD);
+}
+
+#[test]
+fn locked_stakes_discount_bumps_until_the_lock_ends() {
+    let mut staking = Staking::new();
+    staking.stake(10 * JUNK, SHORT).unwrap();
+    // The default curve charges 50 credits; 20% off
+    assert_eq!(staking.bump(), (40, 40 * JUNK));
+
+    // 33.33% off: credits round the discount down, JUNK is exact
+    staking.stake(40 * JUNK, LONG).unwrap();
+    assert_eq!(staking.bump(), (34, 33_335_000));
+
+    // The bump's one-second wait lands it on the lock end
+    staking.harness.warp_seconds(7 * DAY - 1);
+    assert_eq!(staking.bump(), (50, 50 * JUNK));
+}
+
+#[test]
+fn only_the_admin_sets_valid_tiers() {
+    let mut staking = Staking::new();
+    let admin = staking.admin;
+
+    for tier in [
+        StakeTier {
+            reward_multiplier_bps: 9_999,
+            ..tiers()[SHORT as usize]
+        },
+        StakeTier {
+            bump_discount_bps: 10_001,
+            ..tiers()[SHORT as usize]
+        },
+        StakeTier {
+            lock_duration: -1,
+            ..tiers()[SHORT as usize]
+        },
+    ] {
+        let mut table = tiers();
+        table[UNUSED as usize] = tier;
+        let ix = staking.client.set_stake_tiers(&admin, table);
+        assert_eq!(
+            staking.harness.process(&ix, &[admin]),
+            Err(game_error(GameError::InvalidStakeTier))
+        );
+    }
+
+    let player = staking.player;
+    let ix = staking.client.set_stake_tiers(&player, tiers());
+    assert_eq!(
+        staking.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+}
@@ -0,0 +1,194 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use coin_pusher_client::instructions::CollectOptions;
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    CashOutLimits, EmissionSchedule, GameError, GlobalStats, StatsDay, COIN_TYPE_NORMAL,
+    COIN_TYPE_TRASHCOIN, SECONDS_PER_DAY,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, start_game, Setup, INITIAL_BALANCE, JUNK};
+
+/// 2024-03-01
+const DAY: i64 = 19_783;
+
+struct Dashboard {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+}
+
+impl Dashboard {
+    fn new() -> Self {
+        let setup = Setup::builder().build();
+        let mut dashboard = Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+        };
+        dashboard.set_time(DAY * SECONDS_PER_DAY + 3_600);
+        dashboard
+    }
+
+    fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock = self.harness.clock();
+        clock.unix_timestamp = unix_timestamp;
+        self.harness.set_clock(clock);
+    }
+
+    fn add_player(&mut self) -> Pubkey {
+        start_game(&mut self.harness, &self.client, 100 * JUNK, INITIAL_BALANCE)
+    }
+
+    fn drop(&mut self, player: Pubkey) {
+        let ix = self.client.drop_coin(&player);
+        self.harness.process(&ix, &[player]).unwrap();
+    }
+
+    fn collect(&mut self, player: Pubkey, coin_type: u8) {
+        let ix =
+            self.client
+                .collect_coin(&player, &self.admin, coin_type, CollectOptions::default());
+        self.harness.process(&ix, &[player, self.admin]).unwrap();
+    }
+
+    fn snapshot(&mut self, day: i64) -> Result<(), ProgramError> {
+        let payer = self.harness.new_funded_account();
+        let ix = self.client.stats_snapshot(&payer, day);
+        self.harness.process(&ix, &[payer])
+    }
+
+    fn stats(&self) -> GlobalStats {
+        self.harness
+            .get(&pda::global_stats(&coin_pusher_game::ID).0)
+    }
+
+    fn stats_day(&self, day: i64) -> StatsDay {
+        self.harness
+            .get(&pda::stats_day(&coin_pusher_game::ID, day).0)
+    }
+}
+
+#[test]
+fn gameplay_updates_the_totals() {
+    let mut dashboard = Dashboard::new();
+    let admin = dashboard.admin;
+    let players = [(); 2].map(|_| dashboard.add_player());
+
+    for player in players {
+        dashboard.drop(player);
+    }
+    dashboard.harness.warp_seconds(1);
+    let ix = dashboard.client.bump_machine(&players[0], false);
+    dashboard.harness.process(&ix, &[players[0]]).unwrap();
+    dashboard.collect(players[0], COIN_TYPE_NORMAL);
+    dashboard.collect(players[1], COIN_TYPE_TRASHCOIN);
+
+    let schedule = EmissionSchedule {
+        initial_epoch_budget: 100 * JUNK,
+        start_epoch: 0,
+        halving_interval: 5,
+        player_daily_cap: 60 * JUNK,
+    };
+    let ix = dashboard.client.set_emission_schedule(&admin, schedule);
+    dashboard.harness.process(&ix, &[admin]).unwrap();
+    let ix = dashboard
+        .client
+        .award_trashcoin(&players[1], &admin, 3 * JUNK);
+    dashboard
+        .harness
+        .process(&ix, &[players[1], admin])
+        .unwrap();
+
+    let stats = dashboard.stats();
+    assert_eq!(stats.total_players, 2);
+    assert_eq!(stats.total_drops, 2);
+    assert_eq!(stats.total_bumps, 1);
+    assert_eq!(stats.total_coins_collected, 2);
+    assert_eq!(stats.total_trashcoin_awarded, 3 * JUNK);
+    assert_eq!(stats.total_junk_wagered, 52 * JUNK);
+    assert_eq!(stats.total_junk_paid_out, 0);
+    assert_eq!(stats.total_burned, 0);
+}
+
+#[test]
+fn cash_outs_count_as_junk_paid_out() {
+    let mut dashboard = Dashboard::new();
+    let admin = dashboard.admin;
+    let player = dashboard.add_player();
+    let limits = CashOutLimits {
+        player_daily_cap: 30,
+        global_daily_cap: 50,
+        queue_threshold: 20,
+        withdrawal_delay: 3_600,
+    };
+    let ix = dashboard.client.set_cash_out_limits(&admin, limits);
+    dashboard.harness.process(&ix, &[admin]).unwrap();
+
+    // Fund the vault, then win some withdrawable credits
+    for _ in 0..5 {
+        dashboard.drop(player);
+    }
+    dashboard.collect(player, COIN_TYPE_TRASHCOIN);
+    let ix = dashboard.client.cash_out(&player, 5);
+    dashboard.harness.process(&ix, &[player]).unwrap();
+
+    let stats = dashboard.stats();
+    assert_eq!(stats.total_junk_paid_out, 5 * JUNK);
+    assert_eq!(stats.total_junk_wagered, 5 * JUNK);
+}
+
+#[test]
+fn snapshot_copies_the_totals_into_the_day() {
+    let mut dashboard = Dashboard::new();
+    let player = dashboard.add_player();
+    dashboard.drop(player);
+
+    dashboard.snapshot(DAY).unwrap();
+    let stats_day = dashboard.stats_day(DAY);
+    assert_eq!(stats_day.day, DAY);
+    assert_eq!(stats_day.taken_at, DAY * SECONDS_PER_DAY + 3_600);
+    assert_eq!(stats_day.total_players, 1);
+    assert_eq!(stats_day.total_drops, 1);
+    assert_eq!(stats_day.total_junk_wagered, JUNK);
+
+    let events = decode_events(&coin_pusher_game::ID, &dashboard.harness.logs());
+    assert!(matches!(
+        events.as_slice(),
+        [GameEvent::StatsSnapshotTaken(taken)] if taken.day == DAY && taken.total_drops == 1
+    ));
+}
+
+#[test]
+fn later_snapshots_overwrite_the_day() {
+    let mut dashboard = Dashboard::new();
+    let player = dashboard.add_player();
+    dashboard.snapshot(DAY).unwrap();
+
+    dashboard.drop(player);
+    dashboard.drop(player);
+    dashboard.snapshot(DAY).unwrap();
+    assert_eq!(dashboard.stats_day(DAY).total_drops, 2);
+
+    // The next day gets its own account, and the old one is kept
+    dashboard.set_time((DAY + 1) * SECONDS_PER_DAY);
+    dashboard.drop(player);
+    dashboard.snapshot(DAY + 1).unwrap();
+    assert_eq!(dashboard.stats_day(DAY + 1).total_drops, 3);
+    assert_eq!(dashboard.stats_day(DAY).total_drops, 2);
+}
+
+#[test]
+fn snapshot_only_for_today() {
+    let mut dashboard = Dashboard::new();
+
+    for day in [DAY - 1, DAY + 1] {
+        assert_eq!(
+            dashboard.snapshot(day),
+            Err(game_error(GameError::InvalidStatsDay))
+        );
+    }
+}
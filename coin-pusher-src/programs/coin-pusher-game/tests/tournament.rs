@@ -0,0 +1,499 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_spl::associated_token::get_associated_token_address;
+use coin_pusher_client::instructions::{CollectOptions, TournamentParams};
+use coin_pusher_client::{decode_events, pda, CoinPusherClient, GameEvent};
+use coin_pusher_game::{
+    GameError, LeaderboardEntry, PlayerStatus, Tournament, TournamentEntry, COIN_TYPE_NORMAL,
+    COIN_TYPE_TRASHCOIN, MAX_PAYOUT_RANKS,
+};
+use pusher_test_harness::Harness;
+
+mod common;
+
+use common::{game_error, start_game, Setup, INITIAL_BALANCE, JUNK};
+
+const DAY: i64 = 86_400;
+const ID: u64 = 1;
+const ENTRY_FEE: u64 = 10 * JUNK;
+const PLAYER_JUNK: u64 = 100 * JUNK;
+/// Seconds between creation and the start of scoring
+const START_DELAY: i64 = 60;
+
+fn payouts() -> [u16; MAX_PAYOUT_RANKS] {
+    let mut payout_bps = [0; MAX_PAYOUT_RANKS];
+    payout_bps[..4].copy_from_slice(&[5_000, 3_000, 1_000, 1_000]);
+    payout_bps
+}
+
+struct Tournaments {
+    harness: Harness,
+    client: CoinPusherClient,
+    admin: Pubkey,
+}
+
+impl Tournaments {
+    fn new() -> Self {
+        let setup = Setup::builder().build();
+        Self {
+            harness: setup.harness,
+            client: setup.client,
+            admin: setup.admin,
+        }
+    }
+
+    /// A tournament for four players with a 10% rake, scoring from
+    /// `START_DELAY` seconds from now for a day
+    fn params(&self, tournament_id: u64) -> TournamentParams {
+        let start_time = self.harness.clock().unix_timestamp + START_DELAY;
+        TournamentParams {
+            tournament_id,
+            entry_fee: ENTRY_FEE,
+            start_time,
+            end_time: start_time + DAY,
+            max_players: 4,
+            rake_bps: 1_000,
+            payout_bps: payouts(),
+        }
+    }
+
+    fn create(&mut self, params: TournamentParams) -> Result<(), ProgramError> {
+        let ix = self.client.create_tournament(&self.admin, params);
+        self.harness.process(&ix, &[self.admin])
+    }
+
+    /// A player with a game and `PLAYER_JUNK`
+    fn player(&mut self) -> Pubkey {
+        start_game(
+            &mut self.harness,
+            &self.client,
+            PLAYER_JUNK,
+            INITIAL_BALANCE,
+        )
+    }
+
+    fn enter(&mut self, player: Pubkey, tournament_id: u64) -> Result<(), ProgramError> {
+        let ix = self.client.enter_tournament(&player, tournament_id);
+        self.harness.process(&ix, &[player])
+    }
+
+    /// Collect `count` coins of `coin_type` for tournament `ID`
+    fn score(&mut self, player: Pubkey, coin_type: u8, count: usize) {
+        for _ in 0..count {
+            let ix = self.client.collect_coin(
+                &player,
+                &self.admin,
+                coin_type,
+                CollectOptions {
+                    tournament_id: Some(ID),
+                    ..CollectOptions::default()
+                },
+            );
+            self.harness.process(&ix, &[player, self.admin]).unwrap();
+        }
+    }
+
+    fn finalize(&mut self) -> Result<(), ProgramError> {
+        let ix = self.client.finalize_tournament(ID);
+        self.harness.process(&ix, &[])
+    }
+
+    fn claim(&mut self, player: Pubkey, rank: u8) -> Result<(), ProgramError> {
+        let ix = self.client.claim_tournament_prize(&player, ID, rank);
+        self.harness.process(&ix, &[player])
+    }
+
+    fn set_status(&mut self, player: Pubkey, status: PlayerStatus) {
+        let ix = self
+            .client
+            .set_player_status(&self.admin, &player, status, 0, 0);
+        self.harness.process(&ix, &[self.admin]).unwrap();
+    }
+
+    fn tournament(&self) -> Tournament {
+        self.harness
+            .get(&pda::tournament(&coin_pusher_game::ID, ID).0)
+    }
+
+    fn entry(&self, player: Pubkey) -> TournamentEntry {
+        self.harness.get(&self.entry_address(player, ID))
+    }
+
+    fn entry_address(&self, player: Pubkey, tournament_id: u64) -> Pubkey {
+        let program_id = coin_pusher_game::ID;
+        let tournament = pda::tournament(&program_id, tournament_id).0;
+        pda::tournament_entry(&program_id, &tournament, &player).0
+    }
+
+    fn leaderboard(&self) -> Vec<(Pubkey, u64)> {
+        ranked(&self.tournament())
+    }
+
+    fn junk_balance(&self, player: Pubkey) -> u64 {
+        self.harness.token_balance(&get_associated_token_address(
+            &player,
+            &self.client.deployment.junk_mint,
+        ))
+    }
+
+    fn house_balance(&self) -> u64 {
+        self.harness
+            .token_balance(&self.client.deployment.vault_junk_account)
+    }
+}
+
+/// The filled part of a leaderboard
+fn ranked(tournament: &Tournament) -> Vec<(Pubkey, u64)> {
+    tournament
+        .leaderboard
+        .iter()
+        .filter(|entry| entry.is_filled())
+        .map(|entry| (entry.player, entry.score))
+        .collect()
+}
+
+fn empty_tournament() -> Tournament {
+    Tournament {
+        tournament_id: ID,
+        entry_fee: 0,
+        start_time: 0,
+        end_time: DAY,
+        max_players: 100,
+        player_count: 0,
+        rake_bps: 0,
+        payout_bps: payouts(),
+        pot: 0,
+        leaderboard: [LeaderboardEntry::default(); MAX_PAYOUT_RANKS],
+        prizes: [0; MAX_PAYOUT_RANKS],
+        claimed: 0,
+        finalized: false,
+        bump: 0,
+    }
+}
+
+#[test]
+fn ranked_players_split_the_pot_after_the_rake() {
+    let mut tournaments = Tournaments::new();
+    tournaments.create(tournaments.params(ID)).unwrap();
+    let players: Vec<Pubkey> = (0..4).map(|_| tournaments.player()).collect();
+    for &player in &players {
+        tournaments.enter(player, ID).unwrap();
+        assert_eq!(tournaments.junk_balance(player), PLAYER_JUNK - ENTRY_FEE);
+    }
+    assert_eq!(tournaments.tournament().pot, 4 * ENTRY_FEE);
+
+    // Collections before the start don't count
+    tournaments.score(players[0], COIN_TYPE_NORMAL, 3);
+    assert_eq!(tournaments.entry(players[0]).score, 0);
+    assert!(tournaments.leaderboard().is_empty());
+
+    // The fourth player never scores, so rank 3 goes unfilled
+    tournaments.harness.warp_seconds(START_DELAY);
+    tournaments.score(players[0], COIN_TYPE_TRASHCOIN, 1);
+    tournaments.score(players[1], COIN_TYPE_NORMAL, 2);
+    tournaments.score(players[2], COIN_TYPE_NORMAL, 2);
+    assert_eq!(
+        tournaments.leaderboard(),
+        vec![(players[0], 5), (players[1], 2), (players[2], 2)]
+    );
+
+    assert_eq!(
+        tournaments.finalize(),
+        Err(game_error(GameError::TournamentNotEnded))
+    );
+    assert_eq!(
+        tournaments.claim(players[0], 0),
+        Err(game_error(GameError::TournamentNotEnded))
+    );
+
+    // Nothing scores once the window closes
+    tournaments.harness.warp_seconds(DAY);
+    tournaments.score(players[2], COIN_TYPE_TRASHCOIN, 1);
+    assert_eq!(tournaments.entry(players[2]).score, 2);
+
+    // 40 JUNK pot, 4 raked; the 36 left pays 50/30/10% and the unfilled
+    // rank's 10% goes to the house with the rake
+    tournaments.finalize().unwrap();
+    let prizes = [18 * JUNK, 10_800_000, 3_600_000];
+    let events = decode_events(&coin_pusher_game::ID, &tournaments.harness.logs());
+    match events.as_slice() {
+        [GameEvent::TournamentFinalized(finalized)] => {
+            assert_eq!(finalized.pot, 40 * JUNK);
+            assert_eq!(finalized.house_amount, 7_600_000);
+            assert_eq!(finalized.prizes[..4], [prizes[0], prizes[1], prizes[2], 0]);
+        }
+        _ => panic!("expected a TournamentFinalized event"),
+    }
+    assert_eq!(tournaments.house_balance(), 7_600_000);
+    assert_eq!(
+        tournaments.finalize(),
+        Err(game_error(GameError::TournamentFinalized))
+    );
+
+    for (rank, prize) in prizes.into_iter().enumerate() {
+        let player = players[rank];
+        tournaments.claim(player, rank as u8).unwrap();
+        assert_eq!(
+            tournaments.junk_balance(player),
+            PLAYER_JUNK - ENTRY_FEE + prize
+        );
+    }
+    let vault = pda::tournament_vault(
+        &coin_pusher_game::ID,
+        &pda::tournament(&coin_pusher_game::ID, ID).0,
+    )
+    .0;
+    assert_eq!(tournaments.harness.token_balance(&vault), 0);
+}
+
+#[test]
+fn prizes_are_claimed_once_by_their_rank_holder() {
+    let mut tournaments = Tournaments::new();
+    tournaments.create(tournaments.params(ID)).unwrap();
+    let winner = tournaments.player();
+    let unranked = tournaments.player();
+    tournaments.enter(winner, ID).unwrap();
+    tournaments.enter(unranked, ID).unwrap();
+    tournaments.harness.warp_seconds(START_DELAY);
+    tournaments.score(winner, COIN_TYPE_NORMAL, 1);
+    tournaments.harness.warp_seconds(DAY);
+    tournaments.finalize().unwrap();
+
+    assert_eq!(
+        tournaments.claim(unranked, 0),
+        Err(game_error(GameError::Unauthorized))
+    );
+    // Unfilled ranks belong to nobody
+    assert_eq!(
+        tournaments.claim(unranked, 1),
+        Err(game_error(GameError::Unauthorized))
+    );
+    assert_eq!(
+        tournaments.claim(winner, MAX_PAYOUT_RANKS as u8),
+        Err(game_error(GameError::InvalidRank))
+    );
+
+    tournaments.claim(winner, 0).unwrap();
+    assert_eq!(
+        tournaments.claim(winner, 0),
+        Err(game_error(GameError::PrizeAlreadyClaimed))
+    );
+    assert_eq!(tournaments.tournament().claimed, 1);
+    assert_eq!(
+        tournaments.junk_balance(winner),
+        PLAYER_JUNK - ENTRY_FEE + 9 * JUNK
+    );
+    assert_eq!(tournaments.junk_balance(unranked), PLAYER_JUNK - ENTRY_FEE);
+}
+
+#[test]
+fn entries_close_when_full_or_ended() {
+    let mut tournaments = Tournaments::new();
+    let params = TournamentParams {
+        max_players: 1,
+        ..tournaments.params(ID)
+    };
+    tournaments.create(params).unwrap();
+    let first = tournaments.player();
+    let second = tournaments.player();
+
+    tournaments.enter(first, ID).unwrap();
+    assert!(tournaments.enter(first, ID).is_err());
+    assert_eq!(
+        tournaments.enter(second, ID),
+        Err(game_error(GameError::TournamentFull))
+    );
+
+    let params = tournaments.params(2);
+    tournaments.create(params).unwrap();
+    tournaments.harness.warp_seconds(START_DELAY + DAY);
+    assert_eq!(
+        tournaments.enter(second, 2),
+        Err(game_error(GameError::TournamentClosed))
+    );
+    assert_eq!(tournaments.junk_balance(second), PLAYER_JUNK);
+}
+
+#[test]
+fn frozen_players_cannot_enter_or_claim() {
+    let mut tournaments = Tournaments::new();
+    tournaments.create(tournaments.params(ID)).unwrap();
+    let player = tournaments.player();
+
+    tournaments.set_status(player, PlayerStatus::Frozen);
+    assert_eq!(
+        tournaments.enter(player, ID),
+        Err(game_error(GameError::PlayerFrozen))
+    );
+    tournaments.set_status(player, PlayerStatus::Active);
+    tournaments.enter(player, ID).unwrap();
+    tournaments.harness.warp_seconds(START_DELAY);
+    tournaments.score(player, COIN_TYPE_NORMAL, 1);
+    tournaments.harness.warp_seconds(DAY);
+    tournaments.finalize().unwrap();
+
+    tournaments.set_status(player, PlayerStatus::Banned);
+    assert_eq!(
+        tournaments.claim(player, 0),
+        Err(game_error(GameError::PlayerBanned))
+    );
+    tournaments.set_status(player, PlayerStatus::Active);
+    tournaments.claim(player, 0).unwrap();
+}
+
+#[test]
+fn scores_need_the_players_own_entry() {
+    let mut tournaments = Tournaments::new();
+    tournaments.create(tournaments.params(ID)).unwrap();
+    tournaments.create(tournaments.params(2)).unwrap();
+    let player = tournaments.player();
+    let other = tournaments.player();
+    tournaments.enter(player, ID).unwrap();
+    tournaments.enter(other, 2).unwrap();
+    tournaments.harness.warp_seconds(START_DELAY);
+
+    let admin = tournaments.admin;
+    let collect = |tournament_id: u64| {
+        tournaments.client.collect_coin(
+            &player,
+            &admin,
+            COIN_TYPE_NORMAL,
+            CollectOptions {
+                tournament_id: Some(tournament_id),
+                ..CollectOptions::default()
+            },
+        )
+    };
+
+    // The player's entry for another tournament
+    let mut ix = collect(2);
+    let own_entry = tournaments.entry_address(player, ID);
+    ix.accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == tournaments.entry_address(player, 2))
+        .unwrap()
+        .pubkey = own_entry;
+    assert_eq!(
+        tournaments.harness.process(&ix, &[player, admin]),
+        Err(game_error(GameError::InvalidTournamentEntry))
+    );
+
+    // Another player's entry for this tournament
+    let mut ix = collect(2);
+    let others_entry = tournaments.entry_address(other, 2);
+    ix.accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == tournaments.entry_address(player, 2))
+        .unwrap()
+        .pubkey = others_entry;
+    assert_eq!(
+        tournaments.harness.process(&ix, &[player, admin]),
+        Err(game_error(GameError::InvalidTournamentEntry))
+    );
+    assert_eq!(tournaments.entry(player).score, 0);
+}
+
+#[test]
+fn only_the_admin_creates_valid_tournaments() {
+    let mut tournaments = Tournaments::new();
+    let params = tournaments.params(ID);
+
+    let mut short_payouts = payouts();
+    short_payouts[0] -= 1;
+    for (invalid, err) in [
+        (
+            TournamentParams {
+                end_time: params.start_time,
+                ..params
+            },
+            GameError::InvalidTournamentWindow,
+        ),
+        (
+            TournamentParams {
+                payout_bps: short_payouts,
+                ..params
+            },
+            GameError::InvalidPayoutTable,
+        ),
+        (
+            TournamentParams {
+                rake_bps: 10_001,
+                ..params
+            },
+            GameError::InvalidPayoutTable,
+        ),
+        (
+            TournamentParams {
+                max_players: 0,
+                ..params
+            },
+            GameError::InvalidAmount,
+        ),
+    ] {
+        assert_eq!(tournaments.create(invalid), Err(game_error(err)));
+    }
+
+    let player = tournaments.player();
+    let ix = tournaments.client.create_tournament(&player, params);
+    assert_eq!(
+        tournaments.harness.process(&ix, &[player]),
+        Err(game_error(GameError::Unauthorized))
+    );
+    tournaments.create(params).unwrap();
+}
+
+#[test]
+fn leaderboard_keeps_the_top_scores_highest_first() {
+    let mut tournament = empty_tournament();
+    let [first, second, third] = [(); 3].map(|_| Pubkey::new_unique());
+
+    tournament.record_score(first, 5);
+    tournament.record_score(second, 5);
+    assert_eq!(ranked(&tournament), vec![(first, 5), (second, 5)]);
+
+    // A tie leaves the earlier scorer ahead, a higher score passes it
+    tournament.record_score(third, 7);
+    tournament.record_score(second, 6);
+    assert_eq!(
+        ranked(&tournament),
+        vec![(third, 7), (second, 6), (first, 5)]
+    );
+    tournament.record_score(first, 7);
+    assert_eq!(
+        ranked(&tournament),
+        vec![(third, 7), (first, 7), (second, 6)]
+    );
+}
+
+#[test]
+fn leaderboard_drops_the_lowest_score_when_full() {
+    let mut tournament = empty_tournament();
+    let players: Vec<Pubkey> = (0..MAX_PAYOUT_RANKS)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    for (score, &player) in players.iter().enumerate() {
+        tournament.record_score(player, 10 + score as u64);
+    }
+    let last = players[0];
+    assert_eq!(ranked(&tournament).last(), Some(&(last, 10)));
+
+    // Matching the lowest score isn't enough to get on
+    let newcomer = Pubkey::new_unique();
+    tournament.record_score(newcomer, 10);
+    assert_eq!(ranked(&tournament).last(), Some(&(last, 10)));
+
+    tournament.record_score(newcomer, 15);
+    let board = ranked(&tournament);
+    assert_eq!(board.len(), MAX_PAYOUT_RANKS);
+    assert!(!board.iter().any(|(player, _)| *player == last));
+    assert_eq!(board[4], (players[5], 15));
+    assert_eq!(board[5], (newcomer, 15));
+    assert_eq!(board.last(), Some(&(players[1], 11)));
+
+    // Moving up doesn't duplicate the entry
+    tournament.record_score(players[1], 30);
+    let board = ranked(&tournament);
+    assert_eq!(board[0], (players[1], 30));
+    assert_eq!(board.last(), Some(&(players[2], 12)));
+    assert_eq!(board.len(), MAX_PAYOUT_RANKS);
+}
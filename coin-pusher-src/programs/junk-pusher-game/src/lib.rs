@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
 
+#[cfg(not(feature = "localnet"))]
 declare_id!("11111111111111111111111111111111");
+// The placeholder above is the system program's address, which no runtime
+// can load a second program at, so the test suites build with this one
+#[cfg(feature = "localnet")]
+declare_id!("8VGrmBAEtkKhH1b4m4Mx5kEzPQQkr4c5Y3B23eKzGark");
 
 #[program]
 pub mod junk_pusher_game {
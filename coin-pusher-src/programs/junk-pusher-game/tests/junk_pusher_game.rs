@@ -0,0 +1,276 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use junk_pusher_game::{accounts, instruction, GameError, GameState};
+use pusher_test_harness::Harness;
+
+struct Game {
+    harness: Harness,
+    player: Pubkey,
+    game_state: Pubkey,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: junk_pusher_game::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn game_error(err: GameError) -> ProgramError {
+    ProgramError::Custom(err.into())
+}
+
+fn anchor_error(err: ErrorCode) -> ProgramError {
+    ProgramError::Custom(err.into())
+}
+
+fn game_state_address(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"game_state", player.as_ref()], &junk_pusher_game::ID).0
+}
+
+impl Game {
+    fn new() -> Self {
+        let mut harness = Harness::new();
+        harness.add_program(junk_pusher_game::ID, junk_pusher_game::entry);
+        let player = harness.new_funded_account();
+        let mut game = Self {
+            harness,
+            player,
+            game_state: game_state_address(&player),
+        };
+        game.initialize(100).unwrap();
+        game
+    }
+
+    fn state(&self) -> GameState {
+        self.harness.get(&self.game_state)
+    }
+
+    fn initialize(&mut self, initial_balance: u64) -> Result<(), ProgramError> {
+        let ix = build(
+            accounts::InitializeGame {
+                game_state: self.game_state,
+                player: self.player,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeGame { initial_balance },
+        );
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn collect(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let ix = build(
+            accounts::RecordCoinCollection {
+                game_state: self.game_state,
+                player: self.player,
+            },
+            instruction::RecordCoinCollection { amount },
+        );
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn deposit(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let ix = build(
+            accounts::DepositBalance {
+                game_state: self.game_state,
+                player: self.player,
+                system_program: system_program::ID,
+            },
+            instruction::DepositBalance { amount },
+        );
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn withdraw(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let ix = build(
+            accounts::WithdrawBalance {
+                game_state: self.game_state,
+                player: self.player,
+                system_program: system_program::ID,
+            },
+            instruction::WithdrawBalance { amount },
+        );
+        self.harness.process(&ix, &[self.player])
+    }
+
+    fn reset(&mut self) -> Result<(), ProgramError> {
+        let ix = build(
+            accounts::ResetGame {
+                game_state: self.game_state,
+                player: self.player,
+            },
+            instruction::ResetGame {},
+        );
+        self.harness.process(&ix, &[self.player])
+    }
+}
+
+#[test]
+fn initialize_game_creates_player_state() {
+    let game = Game::new();
+    let state = game.state();
+
+    assert_eq!(state.player, game.player);
+    assert_eq!(state.balance, 100);
+    assert_eq!(state.score, 0);
+    assert_eq!(state.created_at, game.harness.clock().unix_timestamp);
+}
+
+#[test]
+fn initialize_game_twice_fails() {
+    let mut game = Game::new();
+
+    assert_eq!(
+        game.initialize(100),
+        Err(ProgramError::Custom(
+            anchor_lang::solana_program::system_instruction::SystemError::AccountAlreadyInUse
+                as u32
+        ))
+    );
+}
+
+#[test]
+fn record_coin_collection_is_rate_limited() {
+    let mut game = Game::new();
+
+    // Initialization counts as the last update
+    assert_eq!(
+        game.collect(10),
+        Err(game_error(GameError::TooManyRequests))
+    );
+
+    game.harness.warp_seconds(1);
+    game.collect(10).unwrap();
+    assert_eq!(
+        game.collect(10),
+        Err(game_error(GameError::TooManyRequests))
+    );
+
+    game.harness.warp_seconds(1);
+    game.collect(5).unwrap();
+
+    let state = game.state();
+    assert_eq!(state.balance, 115);
+    assert_eq!(state.net_profit, 15);
+    assert_eq!(state.total_coins_collected, 2);
+}
+
+#[test]
+fn record_coin_collection_rejects_zero_amount() {
+    let mut game = Game::new();
+    game.harness.warp_seconds(1);
+
+    assert_eq!(game.collect(0), Err(game_error(GameError::InvalidAmount)));
+}
+
+#[test]
+fn record_coin_collection_requires_owner() {
+    let mut game = Game::new();
+    game.harness.warp_seconds(1);
+    let intruder = game.harness.new_funded_account();
+
+    let ix = build(
+        accounts::RecordCoinCollection {
+            game_state: game.game_state,
+            player: intruder,
+        },
+        instruction::RecordCoinCollection { amount: 10 },
+    );
+    assert_eq!(
+        game.harness.process(&ix, &[intruder]),
+        Err(anchor_error(ErrorCode::ConstraintSeeds))
+    );
+}
+
+#[test]
+fn record_score_sets_score() {
+    let mut game = Game::new();
+
+    let ix = build(
+        accounts::RecordScore {
+            game_state: game.game_state,
+            player: game.player,
+        },
+        instruction::RecordScore { score: 4_200 },
+    );
+    game.harness.process(&ix, &[game.player]).unwrap();
+
+    assert_eq!(game.state().score, 4_200);
+}
+
+#[test]
+fn deposit_moves_lamports_into_game_state() {
+    let mut game = Game::new();
+    let player_before = game.harness.lamports(&game.player);
+    let state_before = game.harness.lamports(&game.game_state);
+
+    game.deposit(1_000_000).unwrap();
+
+    assert_eq!(
+        game.harness.lamports(&game.player),
+        player_before - 1_000_000
+    );
+    assert_eq!(
+        game.harness.lamports(&game.game_state),
+        state_before + 1_000_000
+    );
+    assert_eq!(game.state().balance, 1_000_100);
+}
+
+#[test]
+fn deposit_rejects_zero_amount() {
+    let mut game = Game::new();
+
+    assert_eq!(game.deposit(0), Err(game_error(GameError::InvalidAmount)));
+}
+
+#[test]
+fn withdraw_fails_because_the_game_state_carries_data() {
+    let mut game = Game::new();
+    game.deposit(1_000_000).unwrap();
+    let player_before = game.harness.lamports(&game.player);
+    let state_before = game.harness.lamports(&game.game_state);
+
+    // The system program refuses to debit an account with data, so the
+    // transfer out of the game state PDA fails and nothing moves
+    assert_eq!(game.withdraw(400_000), Err(ProgramError::InvalidArgument));
+
+    assert_eq!(game.harness.lamports(&game.player), player_before);
+    assert_eq!(game.harness.lamports(&game.game_state), state_before);
+    assert_eq!(game.state().balance, 1_000_100);
+}
+
+#[test]
+fn withdraw_rejects_zero_amount() {
+    let mut game = Game::new();
+
+    assert_eq!(game.withdraw(0), Err(game_error(GameError::InvalidAmount)));
+}
+
+#[test]
+fn withdraw_rejects_more_than_balance() {
+    let mut game = Game::new();
+
+    assert_eq!(
+        game.withdraw(101),
+        Err(game_error(GameError::InsufficientBalance))
+    );
+}
+
+#[test]
+fn reset_game_restores_defaults() {
+    let mut game = Game::new();
+    game.harness.warp_seconds(1);
+    game.collect(50).unwrap();
+
+    game.reset().unwrap();
+
+    let state = game.state();
+    assert_eq!(state.balance, 100);
+    assert_eq!(state.net_profit, 0);
+    assert_eq!(state.total_coins_collected, 0);
+}
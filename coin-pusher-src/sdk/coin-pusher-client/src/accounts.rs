@@ -0,0 +1,206 @@
+//! Account fetch and deserialise helpers
+
+use anchor_lang::error::{Error, ErrorCode};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, ZeroCopy};
+use coin_pusher_game::{
+    Challenge, Distribution, DistributionClaims, GameConfig, GameState, GlobalStats, Guild,
+    GuildMember, Inventory, MachineSnapshot, PendingWithdrawal, PlayHistory, Raffle, RaffleTickets,
+    RewardVault, Schedule, SponsorBudget, StakePosition, StatsDay, Tournament, TournamentEntry,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+
+use crate::{pda, ClientError, Result};
+
+/// Deserialise any program account from raw account data (discriminator included)
+pub fn deserialize<T: AccountDeserialize>(mut data: &[u8]) -> Result<T> {
+    Ok(T::try_deserialize(&mut data)?)
+}
+
+/// Deserialise a zero-copy program account (discriminator included)
+pub fn deserialize_zero_copy<T: ZeroCopy>(data: &[u8]) -> Result<T> {
+    let body = data
+        .strip_prefix(T::DISCRIMINATOR.as_slice())
+        .ok_or(Error::from(ErrorCode::AccountDiscriminatorMismatch))?;
+    let body = body
+        .get(..std::mem::size_of::<T>())
+        .ok_or(Error::from(ErrorCode::AccountDidNotDeserialize))?;
+    Ok(bytemuck::pod_read_unaligned(body))
+}
+
+/// Fetch and deserialise the account at `address`
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    deserialize(&rpc.get_account_data(address)?)
+}
+
+/// Like [`fetch`], but `Ok(None)` if the account does not exist
+pub fn fetch_optional<T: AccountDeserialize>(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<Option<T>> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value;
+    account
+        .map(|account| deserialize(&account.data))
+        .transpose()
+}
+
+pub fn fetch_game_state(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    player: &Pubkey,
+) -> Result<GameState> {
+    let address = pda::game_state(program_id, player).0;
+    fetch_optional(rpc, &address)?.ok_or(ClientError::AccountNotFound(address))
+}
+
+pub fn fetch_play_history(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    player: &Pubkey,
+) -> Result<Option<PlayHistory>> {
+    let address = pda::play_history(program_id, player).0;
+    let account = rpc
+        .get_account_with_commitment(&address, rpc.commitment())?
+        .value;
+    account
+        .map(|account| deserialize_zero_copy(&account.data))
+        .transpose()
+}
+
+pub fn fetch_sponsor_budget(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    sponsor: &Pubkey,
+) -> Result<Option<SponsorBudget>> {
+    fetch_optional(rpc, &pda::sponsor_budget(program_id, sponsor).0)
+}
+
+pub fn fetch_reward_vault(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    reward_id: u64,
+) -> Result<Option<RewardVault>> {
+    fetch_optional(rpc, &pda::reward_vault(program_id, reward_id).0)
+}
+
+pub fn fetch_config(rpc: &RpcClient, program_id: &Pubkey) -> Result<GameConfig> {
+    fetch(rpc, &pda::config(program_id).0)
+}
+
+pub fn fetch_global_stats(rpc: &RpcClient, program_id: &Pubkey) -> Result<GlobalStats> {
+    fetch(rpc, &pda::global_stats(program_id).0)
+}
+
+pub fn fetch_stats_day(rpc: &RpcClient, program_id: &Pubkey, day: i64) -> Result<Option<StatsDay>> {
+    fetch_optional(rpc, &pda::stats_day(program_id, day).0)
+}
+
+pub fn fetch_schedule(rpc: &RpcClient, program_id: &Pubkey) -> Result<Schedule> {
+    fetch(rpc, &pda::schedule(program_id).0)
+}
+
+pub fn fetch_stake_position(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    player: &Pubkey,
+) -> Result<Option<StakePosition>> {
+    fetch_optional(rpc, &pda::stake_position(program_id, player).0)
+}
+
+pub fn fetch_inventory(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    player: &Pubkey,
+) -> Result<Option<Inventory>> {
+    fetch_optional(rpc, &pda::inventory(program_id, player).0)
+}
+
+pub fn fetch_distribution(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    distribution_id: u64,
+) -> Result<Distribution> {
+    fetch(rpc, &pda::distribution(program_id, distribution_id).0)
+}
+
+pub fn fetch_distribution_claims(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    distribution: &Pubkey,
+) -> Result<DistributionClaims> {
+    fetch(rpc, &pda::distribution_claims(program_id, distribution).0)
+}
+
+pub fn fetch_tournament(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    tournament_id: u64,
+) -> Result<Tournament> {
+    fetch(rpc, &pda::tournament(program_id, tournament_id).0)
+}
+
+pub fn fetch_tournament_entry(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    tournament_id: u64,
+    player: &Pubkey,
+) -> Result<Option<TournamentEntry>> {
+    let tournament = pda::tournament(program_id, tournament_id).0;
+    fetch_optional(
+        rpc,
+        &pda::tournament_entry(program_id, &tournament, player).0,
+    )
+}
+
+pub fn fetch_challenge(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    challenger: &Pubkey,
+    challenge_id: u64,
+) -> Result<Option<Challenge>> {
+    fetch_optional(rpc, &pda::challenge(program_id, challenger, challenge_id).0)
+}
+
+pub fn fetch_raffle(rpc: &RpcClient, program_id: &Pubkey, round: u64) -> Result<Raffle> {
+    fetch(rpc, &pda::raffle(program_id, round).0)
+}
+
+pub fn fetch_raffle_tickets(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    round: u64,
+    player: &Pubkey,
+) -> Result<Option<RaffleTickets>> {
+    let raffle = pda::raffle(program_id, round).0;
+    fetch_optional(rpc, &pda::raffle_tickets(program_id, &raffle, player).0)
+}
+
+pub fn fetch_guild(rpc: &RpcClient, program_id: &Pubkey, guild_id: u64) -> Result<Guild> {
+    fetch(rpc, &pda::guild(program_id, guild_id).0)
+}
+
+pub fn fetch_guild_member(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    player: &Pubkey,
+) -> Result<Option<GuildMember>> {
+    fetch_optional(rpc, &pda::guild_member(program_id, player).0)
+}
+
+pub fn fetch_pending_withdrawal(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    player: &Pubkey,
+) -> Result<Option<PendingWithdrawal>> {
+    fetch_optional(rpc, &pda::pending_withdrawal(program_id, player).0)
+}
+
+pub fn fetch_machine_snapshot(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    player: &Pubkey,
+) -> Result<Option<MachineSnapshot>> {
+    fetch_optional(rpc, &pda::machine_snapshot(program_id, player).0)
+}
@@ -0,0 +1,161 @@
+//! Decode `coin_pusher_game` events from transaction logs
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use coin_pusher_game as program;
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+macro_rules! game_events {
+    ($($event:ident),* $(,)?) => {
+        /// Every event emitted by the program
+        #[allow(clippy::large_enum_variant)]
+        pub enum GameEvent {
+            $($event(program::$event),)*
+        }
+
+        impl GameEvent {
+            /// Decode one `emit!` payload (discriminator followed by borsh data)
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                if data.len() < 8 {
+                    return None;
+                }
+                let (discriminator, mut body) = data.split_at(8);
+                $(
+                    if discriminator == program::$event::DISCRIMINATOR {
+                        return program::$event::deserialize(&mut body).ok().map(GameEvent::$event);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(GameEvent::$event(_) => stringify!($event),)*
+                }
+            }
+        }
+    };
+}
+
+game_events!(
+    ConfigInitialized,
+    PauseUpdated,
+    FeeSplitUpdated,
+    JunkBurned,
+    StatsSnapshotTaken,
+    GameAuthorityUpdated,
+    ModeratorUpdated,
+    PlayerStatusUpdated,
+    PauseAuthorityUpdated,
+    GameInitialized,
+    CoinDropped,
+    CoinCollected,
+    MachineBumped,
+    BumpCurveUpdated,
+    TrashcoinAwarded,
+    RewardVaultRegistered,
+    RewardVaultUpdated,
+    TokenAwarded,
+    DistributionCreated,
+    DistributionClaimed,
+    DistributionClawedBack,
+    GameReset,
+    SponsorBudgetUpdated,
+    GameSponsored,
+    GameClosed,
+    Staked,
+    Unstaked,
+    LockExtended,
+    StakeTiersUpdated,
+    TournamentCreated,
+    TournamentEntered,
+    TournamentScoreUpdated,
+    TournamentFinalized,
+    TournamentPrizeClaimed,
+    ChallengeRulesUpdated,
+    ChallengeCreated,
+    ChallengeAccepted,
+    ChallengeScoreUpdated,
+    ChallengeSettled,
+    ChallengeRefunded,
+    RaffleRulesUpdated,
+    RaffleOpened,
+    RaffleJoined,
+    RaffleTicketsCredited,
+    RaffleClosed,
+    RaffleDrawn,
+    RafflePrizeClaimed,
+    RaffleRefunded,
+    ItemCatalogueUpdated,
+    ItemPurchased,
+    ItemUsed,
+    RedemptionOptionsUpdated,
+    Redeemed,
+    GiftLimitsUpdated,
+    BalanceGifted,
+    GuildSeasonUpdated,
+    GuildCreated,
+    GuildJoined,
+    GuildLeft,
+    GuildRoleChanged,
+    GuildRenamed,
+    GuildTitheUpdated,
+    GuildTreasuryDistributed,
+    GuildScoreUpdated,
+    CashOutLimitsUpdated,
+    CashedOut,
+    CashOutQueued,
+    CashOutFrozen,
+    CashOutCancelled,
+    CoinTypesUpdated,
+    EmissionScheduleUpdated,
+    ScheduleUpdated,
+    SnapshotSaved,
+);
+
+/// Decode the events emitted by `program_id` from a transaction's log
+/// messages. Data lines logged by other programs (e.g. CPI callees) are
+/// skipped by tracking the invoke stack.
+pub fn decode_events(program_id: &Pubkey, logs: &[String]) -> Vec<GameEvent> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(id), Some("invoke")) => {
+                    stack.push(id);
+                    continue;
+                }
+                (Some(id), Some("success")) | (Some(id), Some("failed:"))
+                    if stack.last() == Some(&id) =>
+                {
+                    stack.pop();
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let Some(payload) = line.strip_prefix(PROGRAM_DATA) else {
+            continue;
+        };
+        if stack.last() != Some(&program_id.as_str()) {
+            continue;
+        }
+        if let Some(event) = STANDARD
+            .decode(payload)
+            .ok()
+            .and_then(|data| GameEvent::decode(&data))
+        {
+            events.push(event);
+        }
+    }
+
+    events
+}
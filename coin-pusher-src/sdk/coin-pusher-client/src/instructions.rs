@@ -0,0 +1,1595 @@
+//! Typed instruction builders. Player token accounts are the player's
+//! associated token accounts for the configured mints.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+use anchor_spl::token;
+use coin_pusher_game::{
+    accounts, instruction, BumpCurve, CashOutLimits, ChallengeRules, CoinType, EmissionSchedule,
+    FeeSplit, GiftLimits, GuildRole, GuildSeason, ItemDef, PaymentToken, PlayerStatus, RaffleRules,
+    RedemptionOption, ScheduleWindow, SnapshotCoin, StakeTier, GUILD_NAME_LEN, MAX_COIN_TYPES,
+    MAX_ITEMS, MAX_PAYOUT_RANKS, MAX_REDEMPTION_OPTIONS, MAX_SCHEDULE_WINDOWS, MAX_STAKE_TIERS,
+};
+
+use crate::{pda, Deployment};
+
+/// Optional accounts for `drop_coin_with`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DropOptions {
+    /// Add the drop's prize pool share to this raffle round
+    pub raffle_round: Option<u64>,
+    /// Credit raffle tickets too; the player must have joined the round
+    pub raffle_tickets: bool,
+    /// Record the drop in the player's `PlayHistory`
+    pub play_history: bool,
+}
+
+/// Strength and optional accounts for `bump_machine_with`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BumpOptions {
+    /// Bump strength, priced by the config's `BumpCurve`; 0 bumps at 1
+    pub strength: u8,
+    /// Attach the player's `StakePosition` for the bump discount
+    pub stake_position: bool,
+    /// Record the bump in the player's `PlayHistory`
+    pub play_history: bool,
+}
+
+/// Optional accounts that turn on boosts and scoring in `collect_coin`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CollectOptions {
+    /// Attach the player's `StakePosition` for the staking multiplier
+    pub stake_position: bool,
+    /// Attach the player's `Inventory` for active item effects
+    pub inventory: bool,
+    /// Credit the collection to this tournament as well
+    pub tournament_id: Option<u64>,
+    /// The player's guild, to score the collection and pay its tithe
+    pub guild_id: Option<u64>,
+    /// A challenge the player is in (see `pda::challenge`)
+    pub challenge: Option<Pubkey>,
+    /// Payout mint of the coin type, if it pays a token bonus. The vault's
+    /// JUNK/TRASHCOIN accounts are used for those mints, the vault's
+    /// associated token account otherwise.
+    pub payout_mint: Option<Pubkey>,
+    /// Record the collection in the player's `PlayHistory`
+    pub play_history: bool,
+}
+
+/// Arguments of `create_tournament`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TournamentParams {
+    pub tournament_id: u64,
+    pub entry_fee: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub max_players: u32,
+    pub rake_bps: u16,
+    pub payout_bps: [u16; MAX_PAYOUT_RANKS],
+}
+
+/// Arguments of `create_distribution`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DistributionParams {
+    pub distribution_id: u64,
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_claims: u32,
+    pub expires_at: i64,
+}
+
+/// Zero-pad a guild name, or `None` if it doesn't fit
+pub fn guild_name(name: &str) -> Option<[u8; GUILD_NAME_LEN]> {
+    let bytes = name.as_bytes();
+    let mut padded = [0; GUILD_NAME_LEN];
+    padded.get_mut(..bytes.len())?.copy_from_slice(bytes);
+    Some(padded)
+}
+
+/// Builds `coin_pusher_game` instructions for one deployment
+#[derive(Clone, Debug)]
+pub struct CoinPusherClient {
+    pub deployment: Deployment,
+}
+
+impl CoinPusherClient {
+    pub fn new(deployment: Deployment) -> Self {
+        Self { deployment }
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.deployment.program_id
+    }
+
+    fn instruction(
+        &self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+    ) -> Instruction {
+        Instruction {
+            program_id: self.deployment.program_id,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    fn config(&self) -> Pubkey {
+        pda::config(&self.deployment.program_id).0
+    }
+
+    fn global_stats(&self) -> Pubkey {
+        pda::global_stats(&self.deployment.program_id).0
+    }
+
+    fn schedule(&self) -> Pubkey {
+        pda::schedule(&self.deployment.program_id).0
+    }
+
+    fn vault(&self) -> Pubkey {
+        pda::vault(&self.deployment.program_id).0
+    }
+
+    fn game_state(&self, player: &Pubkey) -> Pubkey {
+        pda::game_state(&self.deployment.program_id, player).0
+    }
+
+    fn machine_snapshot(&self, player: &Pubkey) -> Pubkey {
+        pda::machine_snapshot(&self.deployment.program_id, player).0
+    }
+
+    fn junk_ata(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address(owner, &self.deployment.junk_mint)
+    }
+
+    fn trashcoin_ata(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address(owner, &self.deployment.trashcoin_mint)
+    }
+
+    /// The house vault's token account for `mint`
+    fn vault_token_account(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.deployment.junk_mint {
+            self.deployment.vault_junk_account
+        } else if *mint == self.deployment.trashcoin_mint {
+            self.deployment.vault_trashcoin_account
+        } else {
+            get_associated_token_address(&self.vault(), mint)
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Admin
+    // ------------------------------------------------------------------------
+
+    pub fn initialize_config(&self, admin: &Pubkey, pause_authority: Pubkey) -> Instruction {
+        self.instruction(
+            accounts::InitializeConfig {
+                config: self.config(),
+                program_data: pda::program_data(&self.deployment.program_id).0,
+                global_stats: self.global_stats(),
+                schedule: self.schedule(),
+                junk_mint: self.deployment.junk_mint,
+                trashcoin_mint: self.deployment.trashcoin_mint,
+                admin: *admin,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeConfig { pause_authority },
+        )
+    }
+
+    pub fn set_bump_curve(&self, admin: &Pubkey, curve: BumpCurve) -> Instruction {
+        self.instruction(
+            accounts::SetBumpCurve {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetBumpCurve { curve },
+        )
+    }
+
+    pub fn set_moderator(&self, admin: &Pubkey, new_moderator: Pubkey) -> Instruction {
+        self.instruction(
+            accounts::SetModerator {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetModerator { new_moderator },
+        )
+    }
+
+    /// Freeze, ban or reinstate `player`; `expires_at` of 0 never lapses
+    pub fn set_player_status(
+        &self,
+        moderator: &Pubkey,
+        player: &Pubkey,
+        status: PlayerStatus,
+        reason: u16,
+        expires_at: i64,
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetPlayerStatus {
+                config: self.config(),
+                game_state: self.game_state(player),
+                moderator: *moderator,
+            },
+            instruction::SetPlayerStatus {
+                status,
+                reason,
+                expires_at,
+            },
+        )
+    }
+
+    pub fn set_pause(
+        &self,
+        pause_authority: &Pubkey,
+        paused: bool,
+        disabled_instructions: u64,
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetPause {
+                config: self.config(),
+                pause_authority: *pause_authority,
+            },
+            instruction::SetPause {
+                paused,
+                disabled_instructions,
+            },
+        )
+    }
+
+    pub fn set_fee_split(
+        &self,
+        admin: &Pubkey,
+        fee_split: FeeSplit,
+        jackpot_junk_account: Pubkey,
+        treasury_junk_account: Pubkey,
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetFeeSplit {
+                config: self.config(),
+                jackpot_junk_account,
+                treasury_junk_account,
+                admin: *admin,
+            },
+            instruction::SetFeeSplit { fee_split },
+        )
+    }
+
+    pub fn set_game_authority(&self, admin: &Pubkey, new_game_authority: Pubkey) -> Instruction {
+        self.instruction(
+            accounts::SetGameAuthority {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetGameAuthority { new_game_authority },
+        )
+    }
+
+    pub fn set_pause_authority(&self, admin: &Pubkey, new_pause_authority: Pubkey) -> Instruction {
+        self.instruction(
+            accounts::SetPauseAuthority {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetPauseAuthority {
+                new_pause_authority,
+            },
+        )
+    }
+
+    pub fn set_stake_tiers(
+        &self,
+        admin: &Pubkey,
+        tiers: [StakeTier; MAX_STAKE_TIERS],
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetStakeTiers {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetStakeTiers { tiers },
+        )
+    }
+
+    pub fn set_item_catalogue(&self, admin: &Pubkey, items: [ItemDef; MAX_ITEMS]) -> Instruction {
+        self.instruction(
+            accounts::SetItemCatalogue {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetItemCatalogue { items },
+        )
+    }
+
+    pub fn set_coin_types(
+        &self,
+        admin: &Pubkey,
+        coin_types: [CoinType; MAX_COIN_TYPES],
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetCoinTypes {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetCoinTypes { coin_types },
+        )
+    }
+
+    pub fn set_schedule(
+        &self,
+        admin: &Pubkey,
+        windows: [ScheduleWindow; MAX_SCHEDULE_WINDOWS],
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetSchedule {
+                config: self.config(),
+                schedule: self.schedule(),
+                admin: *admin,
+            },
+            instruction::SetSchedule { windows },
+        )
+    }
+
+    pub fn set_emission_schedule(&self, admin: &Pubkey, schedule: EmissionSchedule) -> Instruction {
+        self.instruction(
+            accounts::SetEmissionSchedule {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetEmissionSchedule { schedule },
+        )
+    }
+
+    pub fn set_redemption_options(
+        &self,
+        admin: &Pubkey,
+        options: [RedemptionOption; MAX_REDEMPTION_OPTIONS],
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetRedemptionOptions {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetRedemptionOptions { options },
+        )
+    }
+
+    pub fn set_guild_season(&self, admin: &Pubkey, season: GuildSeason) -> Instruction {
+        self.instruction(
+            accounts::SetGuildSeason {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetGuildSeason { season },
+        )
+    }
+
+    pub fn set_challenge_rules(&self, admin: &Pubkey, rules: ChallengeRules) -> Instruction {
+        self.instruction(
+            accounts::SetChallengeRules {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetChallengeRules { rules },
+        )
+    }
+
+    pub fn set_raffle_rules(&self, admin: &Pubkey, rules: RaffleRules) -> Instruction {
+        self.instruction(
+            accounts::SetRaffleRules {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetRaffleRules { rules },
+        )
+    }
+
+    /// `seed_hash` is the SHA-256 of the seed later passed to `draw_raffle`
+    pub fn open_raffle(&self, admin: &Pubkey, round: u64, seed_hash: [u8; 32]) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let raffle = pda::raffle(program_id, round).0;
+        self.instruction(
+            accounts::OpenRaffle {
+                config: self.config(),
+                raffle,
+                raffle_index: pda::raffle_index(program_id, &raffle).0,
+                raffle_vault: pda::raffle_vault(program_id, &raffle).0,
+                junk_mint: self.deployment.junk_mint,
+                admin: *admin,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::OpenRaffle { round, seed_hash },
+        )
+    }
+
+    pub fn set_gift_limits(&self, admin: &Pubkey, limits: GiftLimits) -> Instruction {
+        self.instruction(
+            accounts::SetGiftLimits {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetGiftLimits { limits },
+        )
+    }
+
+    pub fn set_cash_out_limits(&self, admin: &Pubkey, limits: CashOutLimits) -> Instruction {
+        self.instruction(
+            accounts::SetCashOutLimits {
+                config: self.config(),
+                admin: *admin,
+            },
+            instruction::SetCashOutLimits { limits },
+        )
+    }
+
+    pub fn set_cash_out_frozen(
+        &self,
+        admin: &Pubkey,
+        player: &Pubkey,
+        frozen: bool,
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetCashOutFrozen {
+                config: self.config(),
+                pending_withdrawal: pda::pending_withdrawal(&self.deployment.program_id, player).0,
+                admin: *admin,
+            },
+            instruction::SetCashOutFrozen { frozen },
+        )
+    }
+
+    /// Return `player`'s queued cash-out to their balance
+    pub fn cancel_cash_out(&self, admin: &Pubkey, player: &Pubkey) -> Instruction {
+        self.instruction(
+            accounts::CancelCashOut {
+                config: self.config(),
+                game_state: self.game_state(player),
+                pending_withdrawal: pda::pending_withdrawal(&self.deployment.program_id, player).0,
+                player: *player,
+                admin: *admin,
+            },
+            instruction::CancelCashOut {},
+        )
+    }
+
+    pub fn create_tournament(&self, admin: &Pubkey, params: TournamentParams) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let tournament = pda::tournament(program_id, params.tournament_id).0;
+        self.instruction(
+            accounts::CreateTournament {
+                config: self.config(),
+                tournament,
+                tournament_vault: pda::tournament_vault(program_id, &tournament).0,
+                junk_mint: self.deployment.junk_mint,
+                admin: *admin,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateTournament {
+                tournament_id: params.tournament_id,
+                entry_fee: params.entry_fee,
+                start_time: params.start_time,
+                end_time: params.end_time,
+                max_players: params.max_players,
+                rake_bps: params.rake_bps,
+                payout_bps: params.payout_bps,
+            },
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Gameplay
+    // ------------------------------------------------------------------------
+
+    pub fn initialize_game(&self, player: &Pubkey, initial_balance: u64) -> Instruction {
+        self.instruction(
+            accounts::InitializeGame {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                player: *player,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeGame { initial_balance },
+        )
+    }
+
+    /// Like `initialize_game`, but `sponsor` pays the rent from its budget
+    /// and gets it back when the game is closed
+    pub fn initialize_sponsored_game(
+        &self,
+        player: &Pubkey,
+        sponsor: &Pubkey,
+        initial_balance: u64,
+    ) -> Instruction {
+        self.instruction(
+            accounts::InitializeSponsoredGame {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                sponsor_budget: pda::sponsor_budget(&self.deployment.program_id, sponsor).0,
+                player: *player,
+                sponsor: *sponsor,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeSponsoredGame { initial_balance },
+        )
+    }
+
+    pub fn set_sponsor_budget(
+        &self,
+        sponsor: &Pubkey,
+        max_accounts: u32,
+        max_lamports: u64,
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetSponsorBudget {
+                config: self.config(),
+                sponsor_budget: pda::sponsor_budget(&self.deployment.program_id, sponsor).0,
+                sponsor: *sponsor,
+                system_program: system_program::ID,
+            },
+            instruction::SetSponsorBudget {
+                max_accounts,
+                max_lamports,
+            },
+        )
+    }
+
+    /// Close the player's game. `rent_recipient` must be the game's sponsor,
+    /// or the player if it wasn't sponsored.
+    pub fn close_game(&self, player: &Pubkey, rent_recipient: &Pubkey) -> Instruction {
+        self.instruction(
+            accounts::CloseGame {
+                config: self.config(),
+                game_state: self.game_state(player),
+                player: *player,
+                rent_recipient: *rent_recipient,
+            },
+            instruction::CloseGame {},
+        )
+    }
+
+    pub fn drop_coin(&self, player: &Pubkey) -> Instruction {
+        self.drop_coin_with(player, DropOptions::default())
+    }
+
+    pub fn drop_coin_with(&self, player: &Pubkey, options: DropOptions) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let raffle = options
+            .raffle_round
+            .map(|round| pda::raffle(program_id, round).0);
+        let ticketed = raffle.filter(|_| options.raffle_tickets);
+        self.instruction(
+            accounts::DropCoin {
+                config: self.config(),
+                game_state: self.game_state(player),
+                global_stats: self.global_stats(),
+                schedule: self.schedule(),
+                player: *player,
+                junk_mint: self.deployment.junk_mint,
+                player_junk_account: self.junk_ata(player),
+                vault_authority: self.vault(),
+                vault_junk_account: self.deployment.vault_junk_account,
+                jackpot_junk_account: self.deployment.jackpot_junk_account,
+                treasury_junk_account: self.deployment.treasury_junk_account,
+                raffle,
+                raffle_vault: raffle.map(|raffle| pda::raffle_vault(program_id, &raffle).0),
+                raffle_index: ticketed.map(|raffle| pda::raffle_index(program_id, &raffle).0),
+                raffle_tickets: ticketed
+                    .map(|raffle| pda::raffle_tickets(program_id, &raffle, player).0),
+                play_history: options
+                    .play_history
+                    .then(|| pda::play_history(program_id, player).0),
+                token_program: token::ID,
+            },
+            instruction::DropCoin {},
+        )
+    }
+
+    /// Must also be signed by the configured game authority
+    pub fn collect_coin(
+        &self,
+        player: &Pubkey,
+        game_authority: &Pubkey,
+        coin_type: u8,
+        options: CollectOptions,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let tournament = options
+            .tournament_id
+            .map(|id| pda::tournament(program_id, id).0);
+        let payout_mint = options.payout_mint;
+        self.instruction(
+            accounts::CollectCoin {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                schedule: self.schedule(),
+                stake_position: options
+                    .stake_position
+                    .then(|| pda::stake_position(program_id, player).0),
+                inventory: options
+                    .inventory
+                    .then(|| pda::inventory(program_id, player).0),
+                tournament,
+                tournament_entry: tournament
+                    .map(|tournament| pda::tournament_entry(program_id, &tournament, player).0),
+                guild: options.guild_id.map(|id| pda::guild(program_id, id).0),
+                guild_membership: options
+                    .guild_id
+                    .map(|_| pda::guild_member(program_id, player).0),
+                challenge: options.challenge,
+                play_history: options
+                    .play_history
+                    .then(|| pda::play_history(program_id, player).0),
+                vault_authority: payout_mint.map(|_| self.vault()),
+                vault_payout_account: payout_mint.map(|mint| self.vault_token_account(&mint)),
+                player_payout_account: payout_mint
+                    .map(|mint| get_associated_token_address(player, &mint)),
+                token_program: payout_mint.map(|_| token::ID),
+                player: *player,
+                game_authority: *game_authority,
+            },
+            instruction::CollectCoin { coin_type },
+        )
+    }
+
+    /// `staked` attaches the player's `StakePosition` for the bump discount
+    pub fn bump_machine(&self, player: &Pubkey, staked: bool) -> Instruction {
+        self.bump_machine_with(
+            player,
+            BumpOptions {
+                stake_position: staked,
+                ..BumpOptions::default()
+            },
+        )
+    }
+
+    pub fn bump_machine_with(&self, player: &Pubkey, options: BumpOptions) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::BumpMachine {
+                config: self.config(),
+                game_state: self.game_state(player),
+                global_stats: self.global_stats(),
+                stake_position: options
+                    .stake_position
+                    .then(|| pda::stake_position(program_id, player).0),
+                player: *player,
+                junk_mint: self.deployment.junk_mint,
+                player_junk_account: self.junk_ata(player),
+                vault_authority: self.vault(),
+                vault_junk_account: self.deployment.vault_junk_account,
+                jackpot_junk_account: self.deployment.jackpot_junk_account,
+                treasury_junk_account: self.deployment.treasury_junk_account,
+                play_history: options
+                    .play_history
+                    .then(|| pda::play_history(program_id, player).0),
+                token_program: token::ID,
+            },
+            instruction::BumpMachine {
+                strength: options.strength.max(1),
+            },
+        )
+    }
+
+    /// Must also be signed by the configured game authority
+    pub fn award_trashcoin(
+        &self,
+        player: &Pubkey,
+        game_authority: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        self.award_trashcoin_with(player, game_authority, amount, false)
+    }
+
+    /// `play_history` records the award in the player's `PlayHistory`
+    pub fn award_trashcoin_with(
+        &self,
+        player: &Pubkey,
+        game_authority: &Pubkey,
+        amount: u64,
+        play_history: bool,
+    ) -> Instruction {
+        self.instruction(
+            accounts::AwardTrashcoin {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                vault_authority: self.vault(),
+                vault_trashcoin_account: self.deployment.vault_trashcoin_account,
+                player_trashcoin_account: self.trashcoin_ata(player),
+                play_history: play_history
+                    .then(|| pda::play_history(&self.deployment.program_id, player).0),
+                player: *player,
+                game_authority: *game_authority,
+                token_program: token::ID,
+            },
+            instruction::AwardTrashcoin { amount },
+        )
+    }
+
+    /// Register `mint` as reward `reward_id`. `token_program` is the mint's
+    /// owner (SPL Token or Token-2022); the vault is the house vault's
+    /// associated token account for the mint under that program.
+    pub fn register_reward_vault(
+        &self,
+        admin: &Pubkey,
+        reward_id: u64,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        emission_budget: u64,
+    ) -> Instruction {
+        self.instruction(
+            accounts::RegisterRewardVault {
+                config: self.config(),
+                reward_vault: pda::reward_vault(&self.deployment.program_id, reward_id).0,
+                mint: *mint,
+                vault_authority: self.vault(),
+                vault: get_associated_token_address_with_program_id(
+                    &self.vault(),
+                    mint,
+                    token_program,
+                ),
+                admin: *admin,
+                token_program: *token_program,
+                system_program: system_program::ID,
+            },
+            instruction::RegisterRewardVault {
+                reward_id,
+                emission_budget,
+            },
+        )
+    }
+
+    pub fn set_reward_vault(
+        &self,
+        admin: &Pubkey,
+        reward_id: u64,
+        emission_budget: u64,
+        enabled: bool,
+    ) -> Instruction {
+        self.instruction(
+            accounts::SetRewardVault {
+                config: self.config(),
+                reward_vault: pda::reward_vault(&self.deployment.program_id, reward_id).0,
+                admin: *admin,
+            },
+            instruction::SetRewardVault {
+                emission_budget,
+                enabled,
+            },
+        )
+    }
+
+    /// Award `amount` of reward `reward_id`, whose `mint` and `token_program`
+    /// must match the registry, into the player's associated token account
+    pub fn award_token(
+        &self,
+        player: &Pubkey,
+        game_authority: &Pubkey,
+        reward_id: u64,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        self.instruction(
+            accounts::AwardToken {
+                config: self.config(),
+                game_state: self.game_state(player),
+                reward_vault: pda::reward_vault(&self.deployment.program_id, reward_id).0,
+                mint: *mint,
+                vault_authority: self.vault(),
+                vault: get_associated_token_address_with_program_id(
+                    &self.vault(),
+                    mint,
+                    token_program,
+                ),
+                player_token_account: get_associated_token_address_with_program_id(
+                    player,
+                    mint,
+                    token_program,
+                ),
+                player: *player,
+                game_authority: *game_authority,
+                token_program: *token_program,
+            },
+            instruction::AwardToken { reward_id, amount },
+        )
+    }
+
+    /// Copy the global stats into the `StatsDay` of `day` (unix time / 86400),
+    /// which must be today. `payer` funds the account the first time.
+    pub fn stats_snapshot(&self, payer: &Pubkey, day: i64) -> Instruction {
+        self.instruction(
+            accounts::StatsSnapshot {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                stats_day: pda::stats_day(&self.deployment.program_id, day).0,
+                payer: *payer,
+                system_program: system_program::ID,
+            },
+            instruction::StatsSnapshot { day },
+        )
+    }
+
+    pub fn initialize_play_history(&self, player: &Pubkey) -> Instruction {
+        self.instruction(
+            accounts::InitializePlayHistory {
+                config: self.config(),
+                play_history: pda::play_history(&self.deployment.program_id, player).0,
+                player: *player,
+                system_program: system_program::ID,
+            },
+            instruction::InitializePlayHistory {},
+        )
+    }
+
+    pub fn reset_game(&self, player: &Pubkey) -> Instruction {
+        self.instruction(
+            accounts::ResetGame {
+                config: self.config(),
+                game_state: self.game_state(player),
+                player: *player,
+            },
+            instruction::ResetGame {},
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Staking
+    // ------------------------------------------------------------------------
+
+    pub fn stake(&self, player: &Pubkey, amount: u64, tier: u8) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::Stake {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                stake_position: pda::stake_position(program_id, player).0,
+                stake_vault: pda::stake_vault(program_id, player).0,
+                junk_mint: self.deployment.junk_mint,
+                player_junk_account: self.junk_ata(player),
+                player: *player,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::Stake { amount, tier },
+        )
+    }
+
+    pub fn unstake(&self, player: &Pubkey, amount: u64) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::Unstake {
+                global_stats: self.global_stats(),
+                stake_position: pda::stake_position(program_id, player).0,
+                stake_vault: pda::stake_vault(program_id, player).0,
+                player_junk_account: self.junk_ata(player),
+                player: *player,
+                token_program: token::ID,
+            },
+            instruction::Unstake { amount },
+        )
+    }
+
+    pub fn extend_lock(&self, player: &Pubkey, tier: u8) -> Instruction {
+        self.instruction(
+            accounts::ExtendLock {
+                config: self.config(),
+                stake_position: pda::stake_position(&self.deployment.program_id, player).0,
+                player: *player,
+            },
+            instruction::ExtendLock { tier },
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Tournaments
+    // ------------------------------------------------------------------------
+
+    pub fn enter_tournament(&self, player: &Pubkey, tournament_id: u64) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let tournament = pda::tournament(program_id, tournament_id).0;
+        self.instruction(
+            accounts::EnterTournament {
+                config: self.config(),
+                game_state: self.game_state(player),
+                tournament,
+                tournament_entry: pda::tournament_entry(program_id, &tournament, player).0,
+                tournament_vault: pda::tournament_vault(program_id, &tournament).0,
+                player_junk_account: self.junk_ata(player),
+                player: *player,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::EnterTournament {},
+        )
+    }
+
+    pub fn finalize_tournament(&self, tournament_id: u64) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let tournament = pda::tournament(program_id, tournament_id).0;
+        self.instruction(
+            accounts::FinalizeTournament {
+                config: self.config(),
+                tournament,
+                tournament_vault: pda::tournament_vault(program_id, &tournament).0,
+                vault_authority: self.vault(),
+                vault_junk_account: self.deployment.vault_junk_account,
+                token_program: token::ID,
+            },
+            instruction::FinalizeTournament {},
+        )
+    }
+
+    pub fn claim_tournament_prize(
+        &self,
+        player: &Pubkey,
+        tournament_id: u64,
+        rank: u8,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let tournament = pda::tournament(program_id, tournament_id).0;
+        self.instruction(
+            accounts::ClaimTournamentPrize {
+                tournament,
+                game_state: self.game_state(player),
+                tournament_vault: pda::tournament_vault(program_id, &tournament).0,
+                player_junk_account: self.junk_ata(player),
+                player: *player,
+                token_program: token::ID,
+            },
+            instruction::ClaimTournamentPrize { rank },
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Raffle
+    // ------------------------------------------------------------------------
+
+    pub fn join_raffle(&self, player: &Pubkey, round: u64) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let raffle = pda::raffle(program_id, round).0;
+        self.instruction(
+            accounts::JoinRaffle {
+                config: self.config(),
+                game_state: self.game_state(player),
+                raffle,
+                raffle_tickets: pda::raffle_tickets(program_id, &raffle, player).0,
+                player: *player,
+                system_program: system_program::ID,
+            },
+            instruction::JoinRaffle {},
+        )
+    }
+
+    pub fn use_raffle_entries(&self, player: &Pubkey, round: u64, quantity: u32) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let raffle = pda::raffle(program_id, round).0;
+        self.instruction(
+            accounts::UseRaffleEntries {
+                config: self.config(),
+                game_state: self.game_state(player),
+                raffle,
+                raffle_index: pda::raffle_index(program_id, &raffle).0,
+                raffle_tickets: pda::raffle_tickets(program_id, &raffle, player).0,
+                inventory: pda::inventory(program_id, player).0,
+                player: *player,
+            },
+            instruction::UseRaffleEntries { quantity },
+        )
+    }
+
+    pub fn close_raffle(&self, round: u64) -> Instruction {
+        self.instruction(
+            accounts::CloseRaffle {
+                raffle: pda::raffle(&self.deployment.program_id, round).0,
+            },
+            instruction::CloseRaffle {},
+        )
+    }
+
+    /// `seed` is ignored once the round's seed has been forfeited
+    pub fn draw_raffle(&self, round: u64, seed: [u8; 32]) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let raffle = pda::raffle(program_id, round).0;
+        self.instruction(
+            accounts::DrawRaffle {
+                config: self.config(),
+                raffle,
+                raffle_index: pda::raffle_index(program_id, &raffle).0,
+                raffle_vault: pda::raffle_vault(program_id, &raffle).0,
+                vault_authority: self.vault(),
+                vault_junk_account: self.deployment.vault_junk_account,
+                slot_hashes: slot_hashes::ID,
+                token_program: token::ID,
+            },
+            instruction::DrawRaffle { seed },
+        )
+    }
+
+    pub fn claim_raffle_prize(&self, player: &Pubkey, round: u64, rank: u8) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let raffle = pda::raffle(program_id, round).0;
+        self.instruction(
+            accounts::ClaimRafflePrize {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                raffle,
+                raffle_tickets: pda::raffle_tickets(program_id, &raffle, player).0,
+                raffle_vault: pda::raffle_vault(program_id, &raffle).0,
+                player_junk_account: self.junk_ata(player),
+                player: *player,
+                token_program: token::ID,
+            },
+            instruction::ClaimRafflePrize { rank },
+        )
+    }
+
+    /// Refund `player`'s share of a round whose seed was forfeited
+    pub fn refund_raffle_tickets(&self, player: &Pubkey, round: u64) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let raffle = pda::raffle(program_id, round).0;
+        self.instruction(
+            accounts::RefundRaffleTickets {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                raffle,
+                raffle_tickets: pda::raffle_tickets(program_id, &raffle, player).0,
+                raffle_vault: pda::raffle_vault(program_id, &raffle).0,
+                player_junk_account: self.junk_ata(player),
+                player: *player,
+                token_program: token::ID,
+            },
+            instruction::RefundRaffleTickets {},
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Challenges
+    // ------------------------------------------------------------------------
+
+    /// Challenge `opponent`, or anyone if it is the default key
+    pub fn create_challenge(
+        &self,
+        challenger: &Pubkey,
+        challenge_id: u64,
+        opponent: &Pubkey,
+        stake: u64,
+        duration: i64,
+        accept_deadline: i64,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let challenge = pda::challenge(program_id, challenger, challenge_id).0;
+        self.instruction(
+            accounts::CreateChallenge {
+                config: self.config(),
+                game_state: self.game_state(challenger),
+                challenge,
+                challenge_vault: pda::challenge_vault(program_id, &challenge).0,
+                junk_mint: self.deployment.junk_mint,
+                challenger_junk_account: self.junk_ata(challenger),
+                challenger: *challenger,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateChallenge {
+                challenge_id,
+                opponent: *opponent,
+                stake,
+                duration,
+                accept_deadline,
+            },
+        )
+    }
+
+    pub fn accept_challenge(
+        &self,
+        opponent: &Pubkey,
+        challenger: &Pubkey,
+        challenge_id: u64,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let challenge = pda::challenge(program_id, challenger, challenge_id).0;
+        self.instruction(
+            accounts::AcceptChallenge {
+                config: self.config(),
+                game_state: self.game_state(opponent),
+                challenge,
+                challenge_vault: pda::challenge_vault(program_id, &challenge).0,
+                opponent_junk_account: self.junk_ata(opponent),
+                opponent: *opponent,
+                token_program: token::ID,
+            },
+            instruction::AcceptChallenge {},
+        )
+    }
+
+    pub fn settle_challenge(
+        &self,
+        challenger: &Pubkey,
+        challenge_id: u64,
+        opponent: &Pubkey,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let challenge = pda::challenge(program_id, challenger, challenge_id).0;
+        self.instruction(
+            accounts::SettleChallenge {
+                config: self.config(),
+                challenge,
+                challenge_vault: pda::challenge_vault(program_id, &challenge).0,
+                challenger_junk_account: self.junk_ata(challenger),
+                opponent_junk_account: self.junk_ata(opponent),
+                vault_authority: self.vault(),
+                vault_junk_account: self.deployment.vault_junk_account,
+                challenger: *challenger,
+                token_program: token::ID,
+            },
+            instruction::SettleChallenge {},
+        )
+    }
+
+    pub fn refund_challenge(&self, challenger: &Pubkey, challenge_id: u64) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let challenge = pda::challenge(program_id, challenger, challenge_id).0;
+        self.instruction(
+            accounts::RefundChallenge {
+                challenge,
+                challenge_vault: pda::challenge_vault(program_id, &challenge).0,
+                challenger_junk_account: self.junk_ata(challenger),
+                challenger: *challenger,
+                token_program: token::ID,
+            },
+            instruction::RefundChallenge {},
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Items
+    // ------------------------------------------------------------------------
+
+    pub fn buy_item(
+        &self,
+        player: &Pubkey,
+        item_id: u8,
+        quantity: u16,
+        payment_token: PaymentToken,
+    ) -> Instruction {
+        let (player_token_account, vault_token_account) = match payment_token {
+            PaymentToken::Junk => (self.junk_ata(player), self.deployment.vault_junk_account),
+            PaymentToken::Trashcoin => (
+                self.trashcoin_ata(player),
+                self.deployment.vault_trashcoin_account,
+            ),
+        };
+        self.instruction(
+            accounts::BuyItem {
+                config: self.config(),
+                game_state: self.game_state(player),
+                inventory: pda::inventory(&self.deployment.program_id, player).0,
+                player_token_account,
+                vault_authority: self.vault(),
+                vault_token_account,
+                player: *player,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::BuyItem {
+                item_id,
+                quantity,
+                payment_token,
+            },
+        )
+    }
+
+    pub fn use_item(&self, player: &Pubkey, item_id: u8) -> Instruction {
+        self.instruction(
+            accounts::UseItem {
+                config: self.config(),
+                game_state: self.game_state(player),
+                inventory: pda::inventory(&self.deployment.program_id, player).0,
+                player: *player,
+            },
+            instruction::UseItem { item_id },
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Redemption
+    // ------------------------------------------------------------------------
+
+    pub fn redeem(&self, player: &Pubkey, option_id: u8, quantity: u32) -> Instruction {
+        self.instruction(
+            accounts::Redeem {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                inventory: pda::inventory(&self.deployment.program_id, player).0,
+                trashcoin_mint: self.deployment.trashcoin_mint,
+                player_trashcoin_account: self.trashcoin_ata(player),
+                player_junk_account: self.junk_ata(player),
+                vault_authority: self.vault(),
+                vault_trashcoin_account: self.deployment.vault_trashcoin_account,
+                vault_junk_account: self.deployment.vault_junk_account,
+                player: *player,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::Redeem {
+                option_id,
+                quantity,
+            },
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Guilds
+    // ------------------------------------------------------------------------
+
+    pub fn create_guild(
+        &self,
+        player: &Pubkey,
+        guild_id: u64,
+        name: [u8; GUILD_NAME_LEN],
+        tithe_bps: u16,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::CreateGuild {
+                config: self.config(),
+                guild: pda::guild(program_id, guild_id).0,
+                membership: pda::guild_member(program_id, player).0,
+                player: *player,
+                system_program: system_program::ID,
+            },
+            instruction::CreateGuild {
+                guild_id,
+                name,
+                tithe_bps,
+            },
+        )
+    }
+
+    pub fn join_guild(&self, player: &Pubkey, guild_id: u64) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::JoinGuild {
+                config: self.config(),
+                guild: pda::guild(program_id, guild_id).0,
+                membership: pda::guild_member(program_id, player).0,
+                player: *player,
+                system_program: system_program::ID,
+            },
+            instruction::JoinGuild {},
+        )
+    }
+
+    pub fn leave_guild(&self, player: &Pubkey, guild_id: u64) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::LeaveGuild {
+                config: self.config(),
+                guild: pda::guild(program_id, guild_id).0,
+                membership: pda::guild_member(program_id, player).0,
+                player: *player,
+            },
+            instruction::LeaveGuild {},
+        )
+    }
+
+    pub fn kick_guild_member(
+        &self,
+        officer: &Pubkey,
+        guild_id: u64,
+        player: &Pubkey,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::KickGuildMember {
+                config: self.config(),
+                guild: pda::guild(program_id, guild_id).0,
+                officer_membership: pda::guild_member(program_id, officer).0,
+                membership: pda::guild_member(program_id, player).0,
+                player: *player,
+                officer: *officer,
+            },
+            instruction::KickGuildMember {},
+        )
+    }
+
+    pub fn set_guild_role(
+        &self,
+        leader: &Pubkey,
+        guild_id: u64,
+        player: &Pubkey,
+        role: GuildRole,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::SetGuildRole {
+                config: self.config(),
+                guild: pda::guild(program_id, guild_id).0,
+                leader_membership: pda::guild_member(program_id, leader).0,
+                membership: pda::guild_member(program_id, player).0,
+                leader: *leader,
+            },
+            instruction::SetGuildRole { role },
+        )
+    }
+
+    fn update_guild(&self, officer: &Pubkey, guild_id: u64) -> accounts::UpdateGuild {
+        let program_id = &self.deployment.program_id;
+        accounts::UpdateGuild {
+            config: self.config(),
+            guild: pda::guild(program_id, guild_id).0,
+            officer_membership: pda::guild_member(program_id, officer).0,
+            officer: *officer,
+        }
+    }
+
+    pub fn rename_guild(
+        &self,
+        officer: &Pubkey,
+        guild_id: u64,
+        name: [u8; GUILD_NAME_LEN],
+    ) -> Instruction {
+        self.instruction(
+            self.update_guild(officer, guild_id),
+            instruction::RenameGuild { name },
+        )
+    }
+
+    pub fn set_guild_tithe(&self, leader: &Pubkey, guild_id: u64, tithe_bps: u16) -> Instruction {
+        self.instruction(
+            self.update_guild(leader, guild_id),
+            instruction::SetGuildTithe { tithe_bps },
+        )
+    }
+
+    pub fn distribute_guild_treasury(
+        &self,
+        leader: &Pubkey,
+        guild_id: u64,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        self.instruction(
+            accounts::DistributeGuildTreasury {
+                config: self.config(),
+                guild: pda::guild(program_id, guild_id).0,
+                leader_membership: pda::guild_member(program_id, leader).0,
+                recipient_membership: pda::guild_member(program_id, recipient).0,
+                recipient_game_state: self.game_state(recipient),
+                leader: *leader,
+            },
+            instruction::DistributeGuildTreasury { amount },
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Gifting
+    // ------------------------------------------------------------------------
+
+    pub fn gift_balance(&self, sender: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+        self.instruction(
+            accounts::GiftBalance {
+                config: self.config(),
+                sender_game_state: self.game_state(sender),
+                recipient_game_state: self.game_state(recipient),
+                recipient: *recipient,
+                sender: *sender,
+            },
+            instruction::GiftBalance { amount },
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Cash-out
+    // ------------------------------------------------------------------------
+
+    pub fn cash_out(&self, player: &Pubkey, amount: u64) -> Instruction {
+        self.instruction(
+            accounts::CashOut {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                vault_authority: self.vault(),
+                vault_junk_account: self.deployment.vault_junk_account,
+                player_junk_account: self.junk_ata(player),
+                player: *player,
+                token_program: token::ID,
+            },
+            instruction::CashOut { amount },
+        )
+    }
+
+    pub fn queue_cash_out(&self, player: &Pubkey, amount: u64) -> Instruction {
+        self.instruction(
+            accounts::QueueCashOut {
+                config: self.config(),
+                game_state: self.game_state(player),
+                pending_withdrawal: pda::pending_withdrawal(&self.deployment.program_id, player).0,
+                player: *player,
+                system_program: system_program::ID,
+            },
+            instruction::QueueCashOut { amount },
+        )
+    }
+
+    pub fn claim_cash_out(&self, player: &Pubkey) -> Instruction {
+        self.instruction(
+            accounts::ClaimCashOut {
+                config: self.config(),
+                global_stats: self.global_stats(),
+                game_state: self.game_state(player),
+                pending_withdrawal: pda::pending_withdrawal(&self.deployment.program_id, player).0,
+                vault_authority: self.vault(),
+                vault_junk_account: self.deployment.vault_junk_account,
+                player_junk_account: self.junk_ata(player),
+                player: *player,
+                token_program: token::ID,
+            },
+            instruction::ClaimCashOut {},
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Distributions
+    // ------------------------------------------------------------------------
+
+    /// Post a distribution, funded from the admin's associated token account
+    /// for `params.mint`
+    pub fn create_distribution(&self, admin: &Pubkey, params: DistributionParams) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let distribution = pda::distribution(program_id, params.distribution_id).0;
+        self.instruction(
+            accounts::CreateDistribution {
+                config: self.config(),
+                distribution,
+                distribution_vault: pda::distribution_vault(program_id, &distribution).0,
+                distribution_claims: pda::distribution_claims(program_id, &distribution).0,
+                mint: params.mint,
+                admin_token_account: get_associated_token_address(admin, &params.mint),
+                admin: *admin,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateDistribution {
+                distribution_id: params.distribution_id,
+                merkle_root: params.merkle_root,
+                total_amount: params.total_amount,
+                num_claims: params.num_claims,
+                expires_at: params.expires_at,
+            },
+        )
+    }
+
+    /// Claim leaf `index` into the claimant's associated token account for
+    /// the distribution's `mint`; see [`crate::merkle::MerkleTree::proof`]
+    pub fn claim_distribution(
+        &self,
+        claimant: &Pubkey,
+        distribution_id: u64,
+        mint: &Pubkey,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let distribution = pda::distribution(program_id, distribution_id).0;
+        self.instruction(
+            accounts::ClaimDistribution {
+                config: self.config(),
+                claimant_game_state: self.game_state(claimant),
+                distribution,
+                distribution_vault: pda::distribution_vault(program_id, &distribution).0,
+                distribution_claims: pda::distribution_claims(program_id, &distribution).0,
+                claimant_token_account: get_associated_token_address(claimant, mint),
+                claimant: *claimant,
+                token_program: token::ID,
+            },
+            instruction::ClaimDistribution {
+                index,
+                amount,
+                proof,
+            },
+        )
+    }
+
+    pub fn clawback_distribution(
+        &self,
+        admin: &Pubkey,
+        distribution_id: u64,
+        mint: &Pubkey,
+    ) -> Instruction {
+        let program_id = &self.deployment.program_id;
+        let distribution = pda::distribution(program_id, distribution_id).0;
+        self.instruction(
+            accounts::ClawbackDistribution {
+                config: self.config(),
+                distribution,
+                distribution_vault: pda::distribution_vault(program_id, &distribution).0,
+                admin_token_account: get_associated_token_address(admin, mint),
+                admin: *admin,
+                token_program: token::ID,
+            },
+            instruction::ClawbackDistribution {},
+        )
+    }
+
+    // ------------------------------------------------------------------------
+    // Snapshots
+    // ------------------------------------------------------------------------
+
+    pub fn begin_snapshot(
+        &self,
+        player: &Pubkey,
+        session_id: u64,
+        pusher_phase: u16,
+        coin_count: u16,
+    ) -> Instruction {
+        self.instruction(
+            accounts::BeginSnapshot {
+                config: self.config(),
+                snapshot: self.machine_snapshot(player),
+                player: *player,
+                system_program: system_program::ID,
+            },
+            instruction::BeginSnapshot {
+                session_id,
+                pusher_phase,
+                coin_count,
+            },
+        )
+    }
+
+    pub fn write_snapshot(
+        &self,
+        player: &Pubkey,
+        offset: u16,
+        coins: Vec<SnapshotCoin>,
+    ) -> Instruction {
+        self.instruction(
+            accounts::WriteSnapshot {
+                config: self.config(),
+                snapshot: self.machine_snapshot(player),
+                player: *player,
+            },
+            instruction::WriteSnapshot { offset, coins },
+        )
+    }
+
+    pub fn save_snapshot(&self, player: &Pubkey) -> Instruction {
+        self.instruction(
+            accounts::SaveSnapshot {
+                config: self.config(),
+                game_state: self.game_state(player),
+                snapshot: self.machine_snapshot(player),
+                player: *player,
+            },
+            instruction::SaveSnapshot {},
+        )
+    }
+
+    pub fn close_snapshot(&self, player: &Pubkey) -> Instruction {
+        self.instruction(
+            accounts::CloseSnapshot {
+                snapshot: self.machine_snapshot(player),
+                player: *player,
+            },
+            instruction::CloseSnapshot {},
+        )
+    }
+}
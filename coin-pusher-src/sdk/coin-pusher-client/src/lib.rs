@@ -0,0 +1,83 @@
+//! Rust client SDK for the `coin_pusher_game` program.
+//!
+//! Counterpart of `sdk/JunkPusherClient.ts` for backends and bots:
+//! - [`pda`]: program-derived address helpers
+//! - [`CoinPusherClient`]: typed instruction builders for every instruction
+//! - [`accounts`]: account fetch and deserialise helpers
+//! - [`events`]: event decoding from transaction logs
+//! - [`snapshot`]: saving and restoring the playfield
+//! - [`merkle`]: distribution merkle trees and claim proofs
+
+use anchor_lang::prelude::Pubkey;
+
+pub mod accounts;
+pub mod events;
+pub mod instructions;
+pub mod merkle;
+pub mod pda;
+pub mod snapshot;
+
+pub use coin_pusher_game;
+pub use events::{decode_events, GameEvent};
+pub use instructions::CoinPusherClient;
+
+/// Errors returned by the client helpers
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("rpc error: {0}")]
+    Rpc(Box<solana_rpc_client_api::client_error::Error>),
+    #[error("account deserialisation failed: {0}")]
+    Deserialize(Box<anchor_lang::error::Error>),
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for ClientError {
+    fn from(err: solana_rpc_client_api::client_error::Error) -> Self {
+        ClientError::Rpc(Box::new(err))
+    }
+}
+
+impl From<anchor_lang::error::Error> for ClientError {
+    fn from(err: anchor_lang::error::Error) -> Self {
+        ClientError::Deserialize(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// Addresses fixed for one deployment of the program. The vault token
+/// accounts are whatever the operator created for the `vault` PDA.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deployment {
+    pub program_id: Pubkey,
+    pub junk_mint: Pubkey,
+    pub trashcoin_mint: Pubkey,
+    pub vault_junk_account: Pubkey,
+    pub vault_trashcoin_account: Pubkey,
+    /// `GameConfig::jackpot_junk_account`, if the fee split uses it
+    pub jackpot_junk_account: Option<Pubkey>,
+    /// `GameConfig::treasury_junk_account`, if the fee split uses it
+    pub treasury_junk_account: Option<Pubkey>,
+}
+
+impl Deployment {
+    /// Fill the mint and fee addresses from an on-chain `GameConfig`
+    pub fn from_config(
+        program_id: Pubkey,
+        config: &coin_pusher_game::GameConfig,
+        vault_junk_account: Pubkey,
+        vault_trashcoin_account: Pubkey,
+    ) -> Self {
+        let configured = |key: Pubkey| (key != Pubkey::default()).then_some(key);
+        Self {
+            program_id,
+            junk_mint: config.junk_mint,
+            trashcoin_mint: config.trashcoin_mint,
+            vault_junk_account,
+            vault_trashcoin_account,
+            jackpot_junk_account: configured(config.jackpot_junk_account),
+            treasury_junk_account: configured(config.treasury_junk_account),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Building the merkle tree of a `Distribution` and its claim proofs
+
+use anchor_lang::prelude::Pubkey;
+use coin_pusher_game::{distribution_leaf, merkle_parent};
+
+/// One leaf of a distribution; `index` is the position in the claim list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Claim {
+    pub claimant: Pubkey,
+    pub amount: u64,
+}
+
+/// Every level of the tree, leaves first. A node without a sibling is
+/// carried up unchanged, matching what the program verifies.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Tree over `claims` in order; the first claim is leaf 0
+    pub fn new(claims: &[Claim]) -> Self {
+        let leaves = claims
+            .iter()
+            .enumerate()
+            .map(|(index, claim)| distribution_leaf(index as u32, &claim.claimant, claim.amount))
+            .collect();
+        let mut levels: Vec<Vec<[u8; 32]>> = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => merkle_parent(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    /// Root to post with `create_distribution`; all zeroes when empty
+    pub fn root(&self) -> [u8; 32] {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Siblings from leaf `index` up to the root
+    pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            position /= 2;
+        }
+        proof
+    }
+}
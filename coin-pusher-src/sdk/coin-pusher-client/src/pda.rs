@@ -0,0 +1,145 @@
+//! Program-derived addresses. Every helper returns `(address, bump)`.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+pub fn config(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], program_id)
+}
+
+pub fn global_stats(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"global_stats"], program_id)
+}
+
+/// Daily copy of the global stats; `day` is unix time / 86400
+pub fn stats_day(program_id: &Pubkey, day: i64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"stats_day", &day.to_le_bytes()], program_id)
+}
+
+pub fn schedule(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"schedule"], program_id)
+}
+
+/// Authority of the house vault token accounts
+pub fn vault(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault"], program_id)
+}
+
+pub fn game_state(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"game_state", player.as_ref()], program_id)
+}
+
+/// The player's recent plays
+pub fn play_history(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"play_history", player.as_ref()], program_id)
+}
+
+/// Rent budget of a sponsor paying for new players' accounts
+pub fn sponsor_budget(program_id: &Pubkey, sponsor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sponsor_budget", sponsor.as_ref()], program_id)
+}
+
+pub fn distribution(program_id: &Pubkey, distribution_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"distribution", &distribution_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Token account holding a distribution's unclaimed tokens
+pub fn distribution_vault(program_id: &Pubkey, distribution: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"distribution_vault", distribution.as_ref()], program_id)
+}
+
+/// Bitmap of a distribution's claimed leaves
+pub fn distribution_claims(program_id: &Pubkey, distribution: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"distribution_claims", distribution.as_ref()], program_id)
+}
+
+/// Registry entry of a partner token the game can award
+pub fn reward_vault(program_id: &Pubkey, reward_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"reward_vault", &reward_id.to_le_bytes()], program_id)
+}
+
+pub fn stake_position(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"stake", player.as_ref()], program_id)
+}
+
+pub fn stake_vault(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"stake_vault", player.as_ref()], program_id)
+}
+
+pub fn tournament(program_id: &Pubkey, tournament_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"tournament", &tournament_id.to_le_bytes()], program_id)
+}
+
+pub fn tournament_vault(program_id: &Pubkey, tournament: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"tournament_vault", tournament.as_ref()], program_id)
+}
+
+pub fn tournament_entry(program_id: &Pubkey, tournament: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"tournament_entry", tournament.as_ref(), player.as_ref()],
+        program_id,
+    )
+}
+
+pub fn challenge(program_id: &Pubkey, challenger: &Pubkey, challenge_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"challenge",
+            challenger.as_ref(),
+            &challenge_id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn challenge_vault(program_id: &Pubkey, challenge: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"challenge_vault", challenge.as_ref()], program_id)
+}
+
+pub fn raffle(program_id: &Pubkey, round: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"raffle", &round.to_le_bytes()], program_id)
+}
+
+pub fn raffle_index(program_id: &Pubkey, raffle: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"raffle_index", raffle.as_ref()], program_id)
+}
+
+pub fn raffle_vault(program_id: &Pubkey, raffle: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"raffle_vault", raffle.as_ref()], program_id)
+}
+
+pub fn raffle_tickets(program_id: &Pubkey, raffle: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"raffle_tickets", raffle.as_ref(), player.as_ref()],
+        program_id,
+    )
+}
+
+pub fn inventory(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"inventory", player.as_ref()], program_id)
+}
+
+pub fn pending_withdrawal(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pending_withdrawal", player.as_ref()], program_id)
+}
+
+pub fn guild(program_id: &Pubkey, guild_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"guild", &guild_id.to_le_bytes()], program_id)
+}
+
+/// A player's guild membership; a player is in at most one guild
+pub fn guild_member(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"guild_member", player.as_ref()], program_id)
+}
+
+pub fn machine_snapshot(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"snapshot", player.as_ref()], program_id)
+}
+
+/// ProgramData account of the upgradeable program (used by `initialize_config`)
+pub fn program_data(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID)
+}
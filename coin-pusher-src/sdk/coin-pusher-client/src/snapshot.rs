@@ -0,0 +1,111 @@
+//! Saving and restoring the playfield through the player's `MachineSnapshot`
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use coin_pusher_game::{GameState, MachineSnapshot, SnapshotCoin, SNAPSHOT_POSITION_SCALE};
+use solana_rpc_client::rpc_client::RpcClient;
+
+use crate::{accounts, CoinPusherClient, Result};
+
+/// Coins per `write_snapshot` instruction, sized to fit a transaction
+pub const SNAPSHOT_CHUNK_COINS: usize = 120;
+
+/// A coin in world units, as the physics engine sees it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CoinPosition {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub coin_type: u8,
+}
+
+impl CoinPosition {
+    /// Fixed-point encoding; coordinates outside ±32.767 are clamped
+    pub fn encode(&self) -> SnapshotCoin {
+        let fixed = |value: f32| {
+            (value * SNAPSHOT_POSITION_SCALE)
+                .round()
+                .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+        };
+        SnapshotCoin {
+            x: fixed(self.x),
+            y: fixed(self.y),
+            z: fixed(self.z),
+            coin_type: self.coin_type,
+        }
+    }
+
+    pub fn decode(coin: &SnapshotCoin) -> Self {
+        Self {
+            x: coin.x as f32 / SNAPSHOT_POSITION_SCALE,
+            y: coin.y as f32 / SNAPSHOT_POSITION_SCALE,
+            z: coin.z as f32 / SNAPSHOT_POSITION_SCALE,
+            coin_type: coin.coin_type,
+        }
+    }
+}
+
+/// Whether a stored snapshot can be restored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotStatus {
+    /// Committed, intact and taken at the game state's latest update
+    Valid,
+    /// A save was started but never committed
+    Incomplete,
+    /// The coins don't match the committed hash
+    Corrupt,
+    /// The game state changed after the snapshot was saved
+    Stale,
+}
+
+/// Check `snapshot` against its hash and the player's current game state
+pub fn verify_snapshot(snapshot: &MachineSnapshot, game_state: &GameState) -> SnapshotStatus {
+    if !snapshot.committed {
+        SnapshotStatus::Incomplete
+    } else if snapshot.compute_hash() != snapshot.hash {
+        SnapshotStatus::Corrupt
+    } else if snapshot.state_last_updated != game_state.last_updated {
+        SnapshotStatus::Stale
+    } else {
+        SnapshotStatus::Valid
+    }
+}
+
+/// Fetch the player's snapshot and decoded coins if it is [`SnapshotStatus::Valid`]
+pub fn load_snapshot(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    player: &Pubkey,
+) -> Result<Option<(MachineSnapshot, Vec<CoinPosition>)>> {
+    let Some(snapshot) = accounts::fetch_machine_snapshot(rpc, program_id, player)? else {
+        return Ok(None);
+    };
+    let game_state = accounts::fetch_game_state(rpc, program_id, player)?;
+    if verify_snapshot(&snapshot, &game_state) != SnapshotStatus::Valid {
+        return Ok(None);
+    }
+    let coins = snapshot.coins.iter().map(CoinPosition::decode).collect();
+    Ok(Some((snapshot, coins)))
+}
+
+impl CoinPusherClient {
+    /// Every instruction needed to save `coins`, in order: begin, one write
+    /// per chunk, save. They can be spread over several transactions.
+    pub fn save_snapshot_instructions(
+        &self,
+        player: &Pubkey,
+        session_id: u64,
+        pusher_phase: u16,
+        coins: &[CoinPosition],
+    ) -> Vec<Instruction> {
+        let coins: Vec<SnapshotCoin> = coins.iter().map(CoinPosition::encode).collect();
+        let mut instructions =
+            vec![self.begin_snapshot(player, session_id, pusher_phase, coins.len() as u16)];
+        for (index, chunk) in coins.chunks(SNAPSHOT_CHUNK_COINS).enumerate() {
+            let offset = (index * SNAPSHOT_CHUNK_COINS) as u16;
+            instructions.push(self.write_snapshot(player, offset, chunk.to_vec()));
+        }
+        instructions.push(self.save_snapshot(player));
+        instructions
+    }
+}
@@ -0,0 +1,831 @@
+//! In-process runtime for exercising the Anchor programs from `cargo test`.
+//!
+//! Programs run natively through their `entry` functions. Syscalls are
+//! routed through `solana_program`'s syscall stubs, so clock/rent sysvars,
+//! logs, events and CPIs into the system, SPL Token and Token-2022 programs behave like
+//! they do on chain, without a validator or a BPF toolchain. The SlotHashes
+//! sysvar account follows the clock: every earlier slot produced a block
+//! whose hash is [`slot_hash`].
+//!
+//! When a program returns, its account changes are checked against the
+//! runtime's rules (read-only accounts unchanged, only the owner debits or
+//! writes an account, lamports balance), so a missing `mut` or a write to a
+//! foreign account fails like it would on chain.
+//!
+//! Each test thread owns its own runtime state, so tests can run in parallel.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Once;
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
+    hash::{hashv, Hash},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    slot_hashes::MAX_ENTRIES,
+    system_instruction::{SystemError, SystemInstruction},
+    system_program, sysvar,
+};
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Native entrypoint of a program, e.g. an Anchor crate's `entry`
+pub type ProcessInstruction = for<'a> fn(&Pubkey, &'a [AccountInfo<'a>], &[u8]) -> ProgramResult;
+
+/// Lamports given to accounts funded with [`Harness::new_funded_account`]
+pub const AIRDROP_LAMPORTS: u64 = 100_000_000_000;
+
+/// Stored state of one account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+}
+
+impl Default for StoredAccount {
+    fn default() -> Self {
+        Self {
+            lamports: 0,
+            data: Vec::new(),
+            owner: system_program::ID,
+        }
+    }
+}
+
+/// Account state a program started from
+#[derive(Clone)]
+struct PreAccount {
+    is_writable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+    owner: Pubkey,
+}
+
+impl PreAccount {
+    fn new(info: &AccountInfo) -> Self {
+        Self {
+            is_writable: info.is_writable,
+            lamports: info.lamports(),
+            data: info.data.borrow().to_vec(),
+            owner: *info.owner,
+        }
+    }
+}
+
+/// One program on the call stack and the accounts it was given
+struct Frame {
+    program_id: Pubkey,
+    accounts: HashMap<Pubkey, PreAccount>,
+}
+
+#[derive(Default)]
+struct Runtime {
+    programs: HashMap<Pubkey, ProcessInstruction>,
+    clock: Clock,
+    logs: Vec<String>,
+    call_stack: Vec<Frame>,
+}
+
+thread_local! {
+    static RUNTIME: RefCell<Runtime> = RefCell::new(Runtime::default());
+}
+
+fn log(message: String) {
+    RUNTIME.with(|runtime| runtime.borrow_mut().logs.push(message));
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        log(format!("Program log: {message}"));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        log(format!("Program data: {}", fields.join(" ")));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = RUNTIME.with(|runtime| runtime.borrow().clock.clone());
+        unsafe { std::ptr::write(var_addr as *mut Clock, clock) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { std::ptr::write(var_addr as *mut Rent, Rent::default()) };
+        SUCCESS
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        RUNTIME.with(|runtime| runtime.borrow().call_stack.len() as u64)
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke(instruction, account_infos, signers_seeds)
+    }
+}
+
+/// A change to an account the runtime would reject, named after the
+/// validator's `InstructionError`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Violation {
+    ModifiedProgramId,
+    ReadonlyLamportChange,
+    ExternalAccountLamportSpend,
+    AccountDataSizeChanged,
+    ReadonlyDataModified,
+    ExternalAccountDataModified,
+    UnbalancedInstruction,
+}
+
+impl Violation {
+    fn describe(self) -> &'static str {
+        match self {
+            Self::ModifiedProgramId => {
+                "instruction illegally modified the program id of an account"
+            }
+            Self::ReadonlyLamportChange => "instruction changed the balance of a read-only account",
+            Self::ExternalAccountLamportSpend => {
+                "instruction spent from the balance of an account it does not own"
+            }
+            Self::AccountDataSizeChanged => {
+                "program other than the account's owner changed the size of the account data"
+            }
+            Self::ReadonlyDataModified => "instruction modified data of a read-only account",
+            Self::ExternalAccountDataModified => {
+                "instruction modified data of an account it does not own"
+            }
+            Self::UnbalancedInstruction => {
+                "sum of account balances before and after instruction do not match"
+            }
+        }
+    }
+
+    /// `ProgramError` has no variants for these: changes to accounts the
+    /// program doesn't own surface as `IllegalOwner`, the rest (like a CPI
+    /// escalating a read-only account) as `InvalidArgument`
+    fn into_program_error(self) -> ProgramError {
+        match self {
+            Self::ModifiedProgramId
+            | Self::ExternalAccountLamportSpend
+            | Self::AccountDataSizeChanged
+            | Self::ExternalAccountDataModified => ProgramError::IllegalOwner,
+            Self::ReadonlyLamportChange
+            | Self::ReadonlyDataModified
+            | Self::UnbalancedInstruction => ProgramError::InvalidArgument,
+        }
+    }
+
+    fn fail(self, program_id: &Pubkey) -> ProgramError {
+        log(format!("Program {program_id} {}", self.describe()));
+        self.into_program_error()
+    }
+}
+
+fn verify_account(
+    program_id: &Pubkey,
+    pre: &PreAccount,
+    post: &AccountInfo,
+) -> Result<(), Violation> {
+    let owned = pre.owner == *program_id;
+    let data = post.data.borrow();
+    if *post.owner != pre.owner
+        && (!pre.is_writable || !owned || data.iter().any(|byte| *byte != 0))
+    {
+        return Err(Violation::ModifiedProgramId);
+    }
+    if post.lamports() != pre.lamports {
+        if !pre.is_writable {
+            return Err(Violation::ReadonlyLamportChange);
+        }
+        if !owned && post.lamports() < pre.lamports {
+            return Err(Violation::ExternalAccountLamportSpend);
+        }
+    }
+    if data.len() != pre.data.len() && (!pre.is_writable || !owned) {
+        return Err(Violation::AccountDataSizeChanged);
+    }
+    if data[..] != pre.data[..] {
+        if !pre.is_writable {
+            return Err(Violation::ReadonlyDataModified);
+        }
+        if !owned {
+            return Err(Violation::ExternalAccountDataModified);
+        }
+    }
+    Ok(())
+}
+
+/// Check the changes `program_id` made to `accounts` since `pre` was taken
+fn verify_accounts(
+    program_id: &Pubkey,
+    pre: &HashMap<Pubkey, PreAccount>,
+    accounts: &[AccountInfo],
+) -> Result<(), Violation> {
+    let mut seen = Vec::with_capacity(pre.len());
+    let (mut pre_lamports, mut post_lamports) = (0u128, 0u128);
+    for info in accounts {
+        if info.executable || seen.contains(info.key) {
+            continue;
+        }
+        seen.push(*info.key);
+        let pre = &pre[info.key];
+        verify_account(program_id, pre, info)?;
+        pre_lamports += u128::from(pre.lamports);
+        post_lamports += u128::from(info.lamports());
+    }
+    if pre_lamports != post_lamports {
+        return Err(Violation::UnbalancedInstruction);
+    }
+    Ok(())
+}
+
+fn execute<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], data: &[u8]) -> ProgramResult {
+    let mut pre = HashMap::with_capacity(accounts.len());
+    for info in accounts {
+        let entry = pre
+            .entry(*info.key)
+            .or_insert_with(|| PreAccount::new(info));
+        entry.is_writable |= info.is_writable;
+    }
+    let (process, depth) = RUNTIME.with(|runtime| {
+        let mut runtime = runtime.borrow_mut();
+        runtime.call_stack.push(Frame {
+            program_id: *program_id,
+            accounts: pre,
+        });
+        (
+            runtime.programs.get(program_id).copied(),
+            runtime.call_stack.len(),
+        )
+    });
+    log(format!("Program {program_id} invoke [{depth}]"));
+
+    let result = match process {
+        Some(process) => process(program_id, accounts, data),
+        None => Err(ProgramError::IncorrectProgramId),
+    };
+
+    let frame = RUNTIME
+        .with(|runtime| runtime.borrow_mut().call_stack.pop())
+        .expect("call stack");
+    let result = result.and_then(|()| {
+        verify_accounts(program_id, &frame.accounts, accounts)
+            .map_err(|violation| violation.fail(program_id))
+    });
+    match &result {
+        Ok(()) => log(format!("Program {program_id} success")),
+        Err(err) => log(format!("Program {program_id} failed: {err}")),
+    }
+    result
+}
+
+/// Cross-program invocation: the callee sees the caller's account infos with
+/// the privileges granted by the instruction and the caller's PDA signers.
+/// The caller's changes to those accounts are checked before the call, and
+/// the callee's become part of the caller's starting state after it.
+fn invoke(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let caller = RUNTIME
+        .with(|runtime| {
+            runtime
+                .borrow()
+                .call_stack
+                .last()
+                .map(|frame| frame.program_id)
+        })
+        .ok_or(ProgramError::InvalidArgument)?;
+    let pda_signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let is_signer = info.is_signer || pda_signers.contains(info.key);
+        if meta.is_signer && !is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut info = info.clone();
+        info.is_signer = meta.is_signer;
+        info.is_writable = meta.is_writable;
+        accounts.push(info);
+    }
+
+    sync_caller(&caller, &accounts, true)?;
+    let accounts = Vec::leak(accounts);
+    execute(&instruction.program_id, accounts, &instruction.data)?;
+    sync_caller(&caller, accounts, false)
+}
+
+/// Check the caller's changes to the accounts of a CPI (when `verify`) and
+/// restart the caller's tracking of them from their current state
+fn sync_caller(caller: &Pubkey, accounts: &[AccountInfo], verify: bool) -> ProgramResult {
+    RUNTIME
+        .with(|runtime| {
+            let mut runtime = runtime.borrow_mut();
+            let frame = runtime.call_stack.last_mut().expect("caller frame");
+            for info in accounts {
+                if info.executable {
+                    continue;
+                }
+                let pre = frame.accounts.get_mut(info.key).expect("caller account");
+                if verify {
+                    verify_account(caller, pre, info)?;
+                }
+                *pre = PreAccount {
+                    is_writable: pre.is_writable,
+                    ..PreAccount::new(info)
+                };
+            }
+            Ok(())
+        })
+        .map_err(|violation: Violation| violation.fail(caller))
+}
+
+/// Leak `data` laid out like the loader's input buffer: its length as a u64
+/// right before the bytes and `MAX_PERMITTED_DATA_INCREASE` spare bytes
+/// after, which is where `AccountInfo::realloc` writes
+fn leak_data(data: &[u8]) -> &'static mut [u8] {
+    let len = 8 + data.len() + MAX_PERMITTED_DATA_INCREASE;
+    let words: &'static mut [u64] = Vec::leak(vec![0; len.div_ceil(8)]);
+    let bytes: &'static mut [u8] = word_bytes(words);
+    bytes[..8].copy_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes[8..8 + data.len()].copy_from_slice(data);
+    &mut bytes[8..8 + data.len()]
+}
+
+/// Leak `key` preceded by the account's original data length as a u32,
+/// which `AccountInfo::original_data_len` reads
+fn leak_key(key: &Pubkey, original_data_len: usize) -> &'static Pubkey {
+    let words: &'static mut [u64] = Vec::leak(vec![0; 5]);
+    let bytes = word_bytes(words);
+    bytes[..4].copy_from_slice(&(original_data_len as u32).to_le_bytes());
+    bytes[4..36].copy_from_slice(key.as_ref());
+    // SAFETY: `Pubkey` is a 32-byte array with alignment 1
+    unsafe { &*(bytes[4..36].as_ptr() as *const Pubkey) }
+}
+
+fn word_bytes(words: &'static mut [u64]) -> &'static mut [u8] {
+    let len = words.len() * 8;
+    // SAFETY: any u64 buffer is a valid, more strictly aligned byte buffer
+    unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, len) }
+}
+
+fn set_data(info: &AccountInfo, data: Vec<u8>) -> ProgramResult {
+    *info.try_borrow_mut_data()? = leak_data(&data);
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if from.lamports() < lamports {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    **from.try_borrow_mut_lamports()? -= lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn is_unallocated(info: &AccountInfo) -> bool {
+    info.data_is_empty() && *info.owner == system_program::ID
+}
+
+/// The subset of the system program the programs use through CPI
+fn process_system<'a>(_: &Pubkey, accounts: &'a [AccountInfo<'a>], data: &[u8]) -> ProgramResult {
+    let instruction: SystemInstruction =
+        bincode::deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if !from.is_signer || !to.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if to.lamports() > 0 || !is_unallocated(to) {
+                return Err(ProgramError::Custom(
+                    SystemError::AccountAlreadyInUse as u32,
+                ));
+            }
+            move_lamports(from, to, lamports)?;
+            set_data(to, vec![0; space as usize])?;
+            to.assign(&owner);
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if !from.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !is_unallocated(from) {
+                return Err(ProgramError::InvalidArgument);
+            }
+            move_lamports(from, to, lamports)?;
+        }
+        SystemInstruction::Allocate { space } => {
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if !account.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !is_unallocated(account) {
+                return Err(ProgramError::Custom(
+                    SystemError::AccountAlreadyInUse as u32,
+                ));
+            }
+            set_data(account, vec![0; space as usize])?;
+        }
+        SystemInstruction::Assign { owner } => {
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if !account.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *account.owner != system_program::ID {
+                return Err(ProgramError::IllegalOwner);
+            }
+            account.assign(&owner);
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+fn process_token<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &[u8],
+) -> ProgramResult {
+    spl_token::processor::Processor::process(program_id, accounts, data)
+}
+
+fn process_token_2022<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &[u8],
+) -> ProgramResult {
+    spl_token_2022::processor::Processor::process(program_id, accounts, data)
+}
+
+/// An account store plus a thread-local runtime that executes instructions
+/// one at a time, each as its own atomic transaction
+pub struct Harness {
+    accounts: HashMap<Pubkey, StoredAccount>,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Harness {
+    /// Fresh runtime with the system, SPL Token and Token-2022 programs loaded
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(Stubs));
+        });
+
+        RUNTIME.with(|runtime| {
+            *runtime.borrow_mut() = Runtime {
+                clock: Clock {
+                    slot: 1,
+                    unix_timestamp: 1_700_000_000,
+                    ..Clock::default()
+                },
+                ..Runtime::default()
+            }
+        });
+
+        let mut harness = Self {
+            accounts: HashMap::new(),
+        };
+        harness.add_program(system_program::ID, process_system);
+        harness.add_program(spl_token::ID, process_token);
+        harness.add_program(spl_token_2022::ID, process_token_2022);
+        harness.update_slot_hashes();
+        harness
+    }
+
+    pub fn add_program(&mut self, program_id: Pubkey, process: ProcessInstruction) {
+        RUNTIME.with(|runtime| runtime.borrow_mut().programs.insert(program_id, process));
+    }
+
+    /// Add the ProgramData account of an upgradeable program
+    pub fn add_program_data(&mut self, program_id: &Pubkey, upgrade_authority: Option<Pubkey>) {
+        let (address, _) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
+        let state = UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: upgrade_authority,
+        };
+        let data = bincode::serialize(&state).expect("serialize ProgramData");
+        self.set_account(
+            address,
+            rent_exempt(data.len()),
+            data,
+            bpf_loader_upgradeable::ID,
+        );
+    }
+
+    fn is_program(&self, address: &Pubkey) -> bool {
+        RUNTIME.with(|runtime| runtime.borrow().programs.contains_key(address))
+    }
+
+    // ------------------------------------------------------------------------
+    // Accounts
+    // ------------------------------------------------------------------------
+
+    pub fn set_account(&mut self, address: Pubkey, lamports: u64, data: Vec<u8>, owner: Pubkey) {
+        self.accounts.insert(
+            address,
+            StoredAccount {
+                lamports,
+                data,
+                owner,
+            },
+        );
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<&StoredAccount> {
+        self.accounts.get(address)
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.account(address).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        self.accounts.entry(*address).or_default().lamports += lamports;
+    }
+
+    /// A new system account holding [`AIRDROP_LAMPORTS`]
+    pub fn new_funded_account(&mut self) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.airdrop(&address, AIRDROP_LAMPORTS);
+        address
+    }
+
+    /// Deserialise an Anchor account (panics if missing or malformed)
+    pub fn get<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self
+            .account(address)
+            .unwrap_or_else(|| panic!("account {address} not found"));
+        T::try_deserialize(&mut account.data.as_slice())
+            .unwrap_or_else(|err| panic!("account {address}: {err}"))
+    }
+
+    // ------------------------------------------------------------------------
+    // SPL Token
+    // ------------------------------------------------------------------------
+
+    pub fn create_mint(&mut self, authority: &Pubkey, decimals: u8) -> Pubkey {
+        let address = Pubkey::new_unique();
+        let mint = spl_token::state::Mint {
+            mint_authority: COption::Some(*authority),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        self.set_account(address, rent_exempt(data.len()), data, spl_token::ID);
+        address
+    }
+
+    /// Like [`Self::create_mint`], owned by Token-2022 (no extensions)
+    pub fn create_token_2022_mint(&mut self, authority: &Pubkey, decimals: u8) -> Pubkey {
+        let address = self.create_mint(authority, decimals);
+        self.accounts.get_mut(&address).expect("mint").owner = spl_token_2022::ID;
+        address
+    }
+
+    /// New token account holding `amount`, minted out of thin air
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.create_token_account_at(address, mint, owner, amount);
+        address
+    }
+
+    /// Same as [`Self::create_token_account`] at a given address (e.g. an ATA).
+    /// The account belongs to the same token program as the mint.
+    pub fn create_token_account_at(
+        &mut self,
+        address: Pubkey,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) {
+        let account = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        account.pack_into_slice(&mut data);
+        let token_program = self.account(mint).expect("mint not found").owner;
+        self.set_account(address, rent_exempt(data.len()), data, token_program);
+
+        let mint_account = self.accounts.get_mut(mint).expect("mint not found");
+        let mut state = spl_token::state::Mint::unpack(&mint_account.data).expect("not a mint");
+        state.supply += amount;
+        state.pack_into_slice(&mut mint_account.data);
+    }
+
+    pub fn token_balance(&self, address: &Pubkey) -> u64 {
+        let account = self.account(address).expect("token account not found");
+        spl_token::state::Account::unpack(&account.data)
+            .expect("not a token account")
+            .amount
+    }
+
+    pub fn mint_supply(&self, mint: &Pubkey) -> u64 {
+        let account = self.account(mint).expect("mint not found");
+        spl_token::state::Mint::unpack(&account.data)
+            .expect("not a mint")
+            .supply
+    }
+
+    // ------------------------------------------------------------------------
+    // Clock
+    // ------------------------------------------------------------------------
+
+    pub fn clock(&self) -> Clock {
+        RUNTIME.with(|runtime| runtime.borrow().clock.clone())
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        RUNTIME.with(|runtime| runtime.borrow_mut().clock = clock);
+        self.update_slot_hashes();
+    }
+
+    /// Move the clock forward by `seconds` (and the slot by ~400ms per slot)
+    pub fn warp_seconds(&mut self, seconds: i64) {
+        let mut clock = self.clock();
+        clock.unix_timestamp += seconds;
+        clock.slot += (seconds.max(0) as u64) * 5 / 2;
+        self.set_clock(clock);
+    }
+
+    pub fn warp_slots(&mut self, slots: u64) {
+        let mut clock = self.clock();
+        clock.slot += slots;
+        self.set_clock(clock);
+    }
+
+    /// Rebuild the SlotHashes sysvar for the slots before the current one,
+    /// newest first, as the runtime lays it out
+    fn update_slot_hashes(&mut self) {
+        let slot = self.clock().slot;
+        let entries = slot.min(MAX_ENTRIES as u64);
+        let mut data = Vec::with_capacity(8 + MAX_ENTRIES * 40);
+        data.extend_from_slice(&entries.to_le_bytes());
+        for previous in (slot - entries..slot).rev() {
+            data.extend_from_slice(&previous.to_le_bytes());
+            data.extend_from_slice(slot_hash(previous).as_ref());
+        }
+        self.set_account(
+            sysvar::slot_hashes::ID,
+            rent_exempt(data.len()),
+            data,
+            sysvar::ID,
+        );
+    }
+
+    // ------------------------------------------------------------------------
+    // Execution
+    // ------------------------------------------------------------------------
+
+    /// Execute `instruction` as a transaction signed by `signers`. Account
+    /// changes are only kept if it succeeds.
+    pub fn process(&mut self, instruction: &Instruction, signers: &[Pubkey]) -> ProgramResult {
+        RUNTIME.with(|runtime| runtime.borrow_mut().logs.clear());
+
+        let mut keys: Vec<Pubkey> = Vec::new();
+        for meta in &instruction.accounts {
+            if meta.is_signer && !signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !keys.contains(&meta.pubkey) {
+                keys.push(meta.pubkey);
+            }
+        }
+
+        let mut infos: HashMap<Pubkey, AccountInfo<'static>> = HashMap::new();
+        for key in &keys {
+            let is_program = self.is_program(key);
+            let stored = self.accounts.get(key).cloned().unwrap_or_default();
+            let owner = if is_program {
+                bpf_loader_upgradeable::ID
+            } else {
+                stored.owner
+            };
+            let metas = instruction
+                .accounts
+                .iter()
+                .filter(|meta| meta.pubkey == *key);
+            let is_writable = metas.clone().any(|meta| meta.is_writable);
+            let is_signer = metas.clone().any(|meta| meta.is_signer);
+            let info = AccountInfo::new(
+                leak_key(key, stored.data.len()),
+                is_signer,
+                is_writable,
+                Box::leak(Box::new(stored.lamports)),
+                leak_data(&stored.data),
+                Box::leak(Box::new(owner)),
+                is_program,
+                0,
+            );
+            infos.insert(*key, info);
+        }
+
+        let accounts: Vec<AccountInfo<'static>> = instruction
+            .accounts
+            .iter()
+            .map(|meta| infos[&meta.pubkey].clone())
+            .collect();
+        execute(
+            &instruction.program_id,
+            Vec::leak(accounts),
+            &instruction.data,
+        )?;
+
+        let mut updates = Vec::with_capacity(keys.len());
+        for key in &keys {
+            let info = &infos[key];
+            if !info.is_writable || info.executable {
+                continue;
+            }
+            let data = info.try_borrow_data()?.to_vec();
+            let lamports = info.lamports();
+            if lamports > 0 && lamports < rent_exempt(data.len()) {
+                return Err(ProgramError::AccountNotRentExempt);
+            }
+            updates.push((*key, lamports, data, *info.owner));
+        }
+        for (key, lamports, data, owner) in updates {
+            if lamports == 0 {
+                self.accounts.remove(&key);
+            } else {
+                self.set_account(key, lamports, data, owner);
+            }
+        }
+        Ok(())
+    }
+
+    /// Log messages of the last processed instruction, in the validator's
+    /// format (so they can be fed to event decoders)
+    pub fn logs(&self) -> Vec<String> {
+        RUNTIME.with(|runtime| runtime.borrow().logs.clone())
+    }
+}
+
+/// Hash the harness gives the block produced in `slot`
+pub fn slot_hash(slot: u64) -> Hash {
+    hashv(&[b"slot", &slot.to_le_bytes()])
+}
+
+pub fn rent_exempt(data_len: usize) -> u64 {
+    Rent::default().minimum_balance(data_len)
+}
@@ -0,0 +1,145 @@
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+};
+use pusher_test_harness::{rent_exempt, Harness};
+
+/// Lamports the test accounts hold above rent exemption
+const SPARE: u64 = 1_000;
+
+/// Bumps the first data byte of its first account (instruction 0), moves a
+/// lamport from its first account to its second (1), or does a system
+/// transfer and then writes to the recipient (2)
+fn rogue<'a>(_: &Pubkey, accounts: &'a [AccountInfo<'a>], data: &[u8]) -> ProgramResult {
+    match data[0] {
+        0 => accounts[0].try_borrow_mut_data()?[0] += 1,
+        1 => {
+            **accounts[0].try_borrow_mut_lamports()? -= 1;
+            **accounts[1].try_borrow_mut_lamports()? += 1;
+        }
+        _ => {
+            let transfer = system_instruction::transfer(accounts[0].key, accounts[1].key, 1);
+            invoke(&transfer, &accounts[..3])?;
+            accounts[1].try_borrow_mut_data()?[0] += 1;
+        }
+    }
+    Ok(())
+}
+
+struct Rogue {
+    harness: Harness,
+    program_id: Pubkey,
+}
+
+impl Rogue {
+    fn new() -> Self {
+        let mut harness = Harness::new();
+        let program_id = Pubkey::new_unique();
+        harness.add_program(program_id, rogue);
+        Self {
+            harness,
+            program_id,
+        }
+    }
+
+    /// A one-byte account owned by `owner`
+    fn account(&mut self, owner: Pubkey) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.harness
+            .set_account(address, rent_exempt(1) + SPARE, vec![0], owner);
+        address
+    }
+
+    fn run(&mut self, op: u8, accounts: Vec<AccountMeta>, signers: &[Pubkey]) -> ProgramResult {
+        let ix = Instruction::new_with_bytes(self.program_id, &[op], accounts);
+        self.harness.process(&ix, signers)
+    }
+}
+
+#[test]
+fn owned_writable_accounts_are_saved() {
+    let mut rogue = Rogue::new();
+    let owned = rogue.account(rogue.program_id);
+
+    rogue
+        .run(0, vec![AccountMeta::new(owned, false)], &[])
+        .unwrap();
+    assert_eq!(rogue.harness.account(&owned).unwrap().data, vec![1]);
+}
+
+#[test]
+fn read_only_accounts_cannot_change() {
+    let mut rogue = Rogue::new();
+    let owned = rogue.account(rogue.program_id);
+    let other = rogue.account(rogue.program_id);
+
+    assert_eq!(
+        rogue.run(0, vec![AccountMeta::new_readonly(owned, false)], &[]),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert!(rogue
+        .harness
+        .logs()
+        .iter()
+        .any(|log| log.ends_with("instruction modified data of a read-only account")));
+
+    // Crediting a read-only account is just as illegal
+    let accounts = vec![
+        AccountMeta::new(owned, false),
+        AccountMeta::new_readonly(other, false),
+    ];
+    assert_eq!(
+        rogue.run(1, accounts, &[]),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(rogue.harness.account(&owned).unwrap().data, vec![0]);
+}
+
+#[test]
+fn foreign_accounts_cannot_be_written_or_debited() {
+    let mut rogue = Rogue::new();
+    let owned = rogue.account(rogue.program_id);
+    let foreign = rogue.account(Pubkey::new_unique());
+
+    assert_eq!(
+        rogue.run(0, vec![AccountMeta::new(foreign, false)], &[]),
+        Err(ProgramError::IllegalOwner)
+    );
+    let accounts = vec![
+        AccountMeta::new(foreign, false),
+        AccountMeta::new(owned, false),
+    ];
+    assert_eq!(rogue.run(1, accounts, &[]), Err(ProgramError::IllegalOwner));
+
+    // Crediting one is fine
+    let accounts = vec![
+        AccountMeta::new(owned, false),
+        AccountMeta::new(foreign, false),
+    ];
+    rogue.run(1, accounts, &[]).unwrap();
+    assert_eq!(rogue.harness.lamports(&foreign), rent_exempt(1) + SPARE + 1);
+}
+
+#[test]
+fn cpi_changes_are_checked_against_their_owner() {
+    let mut rogue = Rogue::new();
+    let payer = rogue.harness.new_funded_account();
+    let recipient = rogue.account(system_program::ID);
+
+    // The system program may credit the recipient, the rogue may not write it
+    let accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new(recipient, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    assert_eq!(
+        rogue.run(2, accounts, &[payer]),
+        Err(ProgramError::IllegalOwner)
+    );
+    assert_eq!(rogue.harness.lamports(&recipient), rent_exempt(1) + SPARE);
+}